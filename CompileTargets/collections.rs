@@ -0,0 +1,20 @@
+let mut Vec<i32> scores = vec![10, 20, 30];
+let mut HashMap<String, i32> ages = HashMap::new();
+let mut HashSet<str> names = HashSet::new();
+let mut VecDeque<i32> queue = VecDeque::new();
+let String title = String::from("scores");
+
+scores.push(40);
+scores.insert(0, 5);
+ages.insert("bob", 30);
+names.insert("bob");
+queue.push_back(1);
+
+if ages.contains_key("bob") && names.contains("bob") {
+    Console.WriteLine(title.len() + scores.len());
+}
+
+for score in scores.iter() {
+    Console.WriteLine(score);
+}
+let i32 first = queue.pop_front();
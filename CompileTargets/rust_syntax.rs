@@ -0,0 +1,30 @@
+// the declarations are only here to show the syntax
+#![allow(unused_variable, unused_mut, shadowing)]
+
+let mut count: u32 = 5;
+let limit: u32 = 10;
+let name: str = "player";
+let mut scores: Vec<u32> = vec![1, 2, 3];
+let (x, y) = (1, 2);
+let (width, height): (usize, usize) = (3, 4);
+let (mut a, _) = (x, "ignored");
+
+count += limit;
+let total = count + x;
+
+pub fn is_inside(x: u32, y: u32, name: &str) -> bool {
+    Console.WriteLine(name);
+    return x < 3 && y < 3;
+}
+fn grow(mut size: u32, target: &mut Vec<u32>) {
+    size += 1;
+    target.push(size);
+}
+grow(count, &mut scores);
+let inside = is_inside(1, 2, &name);
+
+// `out` without a type writes to an existing `mut` variable
+let mut parsed: u32 = 0;
+if uint.TryParse(Console.ReadLine(), out parsed) {
+    count += parsed;
+}
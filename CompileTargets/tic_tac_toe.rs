@@ -2,7 +2,7 @@ let u16 MapX = 3;
 let u16 MapY = 3;
 
 
-let int[,]map = {
+let mut int[,]map = {
     {0;0;0;};
     {0;0;0;};
     {0;0;0;};
@@ -12,12 +12,12 @@ let int[,]map = {
 // [o][ ][ ]
 fn DrawMap(){
     Console.WriteLine("");
-    let str wholeMap = "";
+    let mut str wholeMap = "";
     for y in 0..MapY{
-        let str line = "";
+        let mut str line = "";
         for x in 0..MapX{
- 	    let i16 playerIndex = map[x,y];
-            let str playerSymbol =  " "; 
+ 	    let i32 playerIndex = map[x,y];
+            let mut str playerSymbol =  " "; 
 
             if ( playerIndex == 1 ){
                 playerSymbol= "x";
@@ -40,9 +40,11 @@ Console.WriteLine("Let's start the game!");
 
 DrawMap();
 
-let bool xNowPlaying = true;
+let mut bool xNowPlaying = true;
+// the game runs until the window is closed
+#[allow(constant_condition)]
 while (true){
-    let str player_symbol = "x";
+    let mut str player_symbol = "x";
     if ( xNowPlaying ){
         player_symbol = "o";
     }
@@ -0,0 +1,225 @@
+use crate::{
+    diagnostic::Diagnostic,
+    expression::Expression,
+    tokens::{Span, TokenKind},
+    types::Type,
+    visitor::{fold_children, Fold},
+};
+
+/// How a statement leaves the block it is in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Exit {
+    // return or a loop that never ends
+    Return,
+    // break or continue
+    Jump,
+}
+
+struct ImplicitReturns;
+impl Fold for ImplicitReturns {
+    fn fold_expression(&mut self, expression: Expression) -> Expression {
+        let mut expression = fold_children(self, expression);
+        if let Expression::Function {
+            output: Some(_),
+            inside,
+            span,
+            ..
+        } = &mut expression
+        {
+            tail_return(inside, *span);
+        }
+        return expression;
+    }
+}
+
+/// Turns the last expression of a function with a return type into a `return`,
+/// `fn double(x: i32) -> i32 { x * 2 }` is the same as `{ return x * 2; }`
+pub fn implicit_returns(expressions: Vec<Expression>) -> Vec<Expression> {
+    return ImplicitReturns.fold_block(expressions);
+}
+fn tail_return(block: &mut Vec<Expression>, span: Span) {
+    let Some(last) = block.last() else {
+        return;
+    };
+    if is_value(last) {
+        let value = block.pop().expect("the block is not empty");
+        block.push(Expression::Return {
+            span: value.span().unwrap_or(span),
+            value: Box::new(value),
+        });
+        block.push(Expression::Keyword(TokenKind::SemiColon));
+        return;
+    }
+
+    // `if x { 1 } else { 2 }` returns from every branch, a chain without a final else is a statement
+    let chain_start = block
+        .iter()
+        .rposition(|expression| matches!(expression, Expression::If { .. }));
+    let Some(chain_start) = chain_start else {
+        return;
+    };
+    let chain = &mut block[chain_start..];
+    let complete = chain[1..]
+        .iter()
+        .all(|expression| matches!(expression, Expression::Else { .. }))
+        && matches!(
+            chain.last(),
+            Some(Expression::Else {
+                condition: None,
+                ..
+            })
+        );
+    if !complete {
+        return;
+    }
+    for branch in chain {
+        if let Expression::If { inside, span, .. } | Expression::Else { inside, span, .. } = branch
+        {
+            tail_return(inside, *span);
+        }
+    }
+}
+/// expressions that produce a value, everything else is a statement
+fn is_value(expression: &Expression) -> bool {
+    return matches!(
+        expression,
+        Expression::Number(_)
+            | Expression::Float(_)
+            | Expression::String(_)
+            | Expression::Identifier { .. }
+            | Expression::Prefix { .. }
+            | Expression::Binary { .. }
+            | Expression::Grouping(_)
+            | Expression::Tuple(_)
+            | Expression::Borrow { .. }
+            | Expression::ClassInstantiation { .. }
+            | Expression::MemberExpr { .. }
+            | Expression::StaticMember { .. }
+            | Expression::MacroCall { .. }
+            | Expression::IndexArray { .. }
+            | Expression::FunctionCall { .. }
+    );
+}
+
+/// Reports functions that don't return a value on every path and warns about statements that can't be reached
+pub fn check_control_flow(expressions: &[Expression]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    check_block(&mut diagnostics, expressions);
+    return diagnostics;
+}
+fn check_block(diagnostics: &mut Vec<Diagnostic>, block: &[Expression]) {
+    let mut unreachable_reported = false;
+    let mut exited = false;
+    for (i, expression) in block.iter().enumerate() {
+        // the else branches belong to the if that exited
+        let statement = !matches!(
+            expression,
+            Expression::Keyword(TokenKind::SemiColon)
+                | Expression::Else { .. }
+                | Expression::Attribute { .. }
+        );
+        if exited && statement && !unreachable_reported {
+            if let Some(span) = expression.span() {
+                diagnostics.push(Diagnostic::warning(
+                    span,
+                    "unreachable statement".to_string(),
+                ));
+                unreachable_reported = true;
+            }
+        }
+        check_nested(diagnostics, expression);
+        exited = exited || statement_exit(block, i).is_some();
+    }
+}
+fn check_nested(diagnostics: &mut Vec<Diagnostic>, expression: &Expression) {
+    match expression {
+        Expression::Function {
+            name,
+            output,
+            inside,
+            span,
+            ..
+        } => {
+            check_block(diagnostics, inside);
+            if let Some(output) = output {
+                check_function_returns(diagnostics, name, output, inside, *span);
+            }
+        }
+        Expression::If { inside, .. }
+        | Expression::Else { inside, .. }
+        | Expression::While { inside, .. }
+        | Expression::For { inside, .. } => check_block(diagnostics, inside),
+        _ => {}
+    }
+}
+fn check_function_returns(
+    diagnostics: &mut Vec<Diagnostic>,
+    name: &str,
+    output: &Type,
+    inside: &[Expression],
+    span: Span,
+) {
+    if block_exit(inside) == Some(Exit::Return) {
+        return;
+    }
+    diagnostics.push(Diagnostic::error(
+        span,
+        format!(
+            "function `{}` doesn't return a value of type `{}` on every path",
+            name, output
+        ),
+    ));
+}
+
+fn block_exit(block: &[Expression]) -> Option<Exit> {
+    return (0..block.len()).find_map(|i| statement_exit(block, i));
+}
+/// How the statement at `index` leaves the block, None when the next statement runs after it.
+/// An if is looked at together with the else branches that follow it
+fn statement_exit(block: &[Expression], index: usize) -> Option<Exit> {
+    match &block[index] {
+        Expression::Return { .. } => Some(Exit::Return),
+        Expression::Keyword(TokenKind::Break | TokenKind::Continue) => Some(Exit::Jump),
+        Expression::While {
+            condition, inside, ..
+        } if is_true(condition) && !contains_break(inside) => Some(Exit::Return),
+        Expression::If { inside, .. } => {
+            let mut exit = block_exit(inside)?;
+            for branch in &block[index + 1..] {
+                match branch {
+                    Expression::Else {
+                        condition, inside, ..
+                    } => {
+                        exit = match (exit, block_exit(inside)?) {
+                            (Exit::Return, Exit::Return) => Exit::Return,
+                            _ => Exit::Jump,
+                        };
+                        if condition.is_none() {
+                            return Some(exit);
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+            // without a final else the condition can be false
+            None
+        }
+        _ => None,
+    }
+}
+/// `true` or `(true)`
+fn is_true(condition: &Expression) -> bool {
+    match condition {
+        Expression::Identifier { name, .. } => name == "true",
+        Expression::Grouping(inside) => is_true(inside),
+        _ => false,
+    }
+}
+/// break of the loop that contains `block`, breaks of nested loops don't count
+pub fn contains_break(block: &[Expression]) -> bool {
+    return block.iter().any(|expression| match expression {
+        Expression::Keyword(TokenKind::Break) => true,
+        Expression::If { inside, .. } | Expression::Else { inside, .. } => contains_break(inside),
+        _ => false,
+    });
+}
@@ -0,0 +1,54 @@
+use std::fmt;
+
+use crate::tokens::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    // stops the transpilation
+    Error,
+    Warning,
+}
+
+/// Problem found in the source by one of the analysis passes
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// position of the token that caused the problem
+    pub span: Span,
+    pub message: String,
+}
+impl Diagnostic {
+    pub fn error(span: Span, message: String) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            span,
+            message,
+        }
+    }
+    pub fn warning(span: Span, message: String) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Warning,
+            span,
+            message,
+        }
+    }
+}
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {} on {}", severity, self.message, self.span)
+    }
+}
+
+/// Prints all diagnostics and returns true if any of them is an error
+pub fn report(diagnostics: &[Diagnostic]) -> bool {
+    for diagnostic in diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+    return diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error);
+}
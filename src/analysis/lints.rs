@@ -0,0 +1,494 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    control_flow::contains_break,
+    diagnostic::Diagnostic,
+    expression::{interpolated_values, Expression},
+    resolver::{Resolution, SymbolId, SymbolKind},
+    tokens::{Span, TokenKind},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    // let x = 5; without any use of x
+    UnusedVariable,
+    UnusedParameter,
+    // functions that are not pub and never called
+    UnusedFunction,
+    // let mut x = 5; without an assignment to x
+    UnusedMut,
+    // let x = 1; let x = 2;
+    Shadowing,
+    // if true, while false, while true without a break
+    ConstantCondition,
+    // x = x;
+    SelfAssignment,
+}
+pub const LINTS: &[Lint] = &[
+    Lint::UnusedVariable,
+    Lint::UnusedParameter,
+    Lint::UnusedFunction,
+    Lint::UnusedMut,
+    Lint::Shadowing,
+    Lint::ConstantCondition,
+    Lint::SelfAssignment,
+];
+impl Lint {
+    /// Name used by `--allow <id>` and `#[allow(id)]`
+    pub fn id(self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused_variable",
+            Lint::UnusedParameter => "unused_parameter",
+            Lint::UnusedFunction => "unused_function",
+            Lint::UnusedMut => "unused_mut",
+            Lint::Shadowing => "shadowing",
+            Lint::ConstantCondition => "constant_condition",
+            Lint::SelfAssignment => "self_assignment",
+        }
+    }
+    pub fn from_id(id: &str) -> Option<Lint> {
+        return LINTS.iter().copied().find(|lint| lint.id() == id);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    // reported as a warning, the default
+    Warn,
+    // reported as an error and stops the transpilation
+    Deny,
+}
+impl LintLevel {
+    pub fn from_name(name: &str) -> Option<LintLevel> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// Level of every lint, lints that were never set are warnings
+#[derive(Debug, Clone, Default)]
+pub struct LintLevels {
+    levels: HashMap<Lint, LintLevel>,
+}
+impl LintLevels {
+    pub fn new() -> LintLevels {
+        LintLevels::default()
+    }
+    pub fn set(&mut self, lint: Lint, level: LintLevel) {
+        self.levels.insert(lint, level);
+    }
+    pub fn get(&self, lint: Lint) -> LintLevel {
+        return self.levels.get(&lint).copied().unwrap_or(LintLevel::Warn);
+    }
+}
+
+struct Linter<'a> {
+    resolution: &'a Resolution,
+    mutated: &'a HashSet<SymbolId>,
+    /// number of references to a symbol that are not its declaration
+    uses: HashMap<SymbolId, usize>,
+    /// levels of the blocks the linter is in, the last one is used
+    levels: Vec<LintLevels>,
+    diagnostics: Vec<Diagnostic>,
+}
+impl Linter<'_> {
+    fn levels(&self) -> &LintLevels {
+        return self.levels.last().expect("the program has levels");
+    }
+    fn report(&mut self, lint: Lint, span: Span, message: String) {
+        let message = format!("{} [{}]", message, lint.id());
+        match self.levels().get(lint) {
+            LintLevel::Allow => {}
+            LintLevel::Warn => self.diagnostics.push(Diagnostic::warning(span, message)),
+            LintLevel::Deny => self.diagnostics.push(Diagnostic::error(span, message)),
+        }
+    }
+    /// sets the levels of `#[allow(lint, ...)]`, `#[warn(...)]` and `#[deny(...)]` in `levels`
+    fn apply_attribute(
+        &mut self,
+        levels: &mut LintLevels,
+        name: &str,
+        arguments: &[String],
+        span: Span,
+    ) {
+        let Some(level) = LintLevel::from_name(name) else {
+            self.diagnostics.push(Diagnostic::warning(
+                span,
+                format!("unknown attribute `{}`", name),
+            ));
+            return;
+        };
+        for argument in arguments {
+            match Lint::from_id(argument) {
+                Some(lint) => levels.set(lint, level),
+                None => self.diagnostics.push(Diagnostic::warning(
+                    span,
+                    format!("unknown lint `{}`", argument),
+                )),
+            }
+        }
+    }
+
+    fn check_variable(&mut self, name: &str, span: Span) {
+        let Some(&id) = self.resolution.references.get(&span) else {
+            return;
+        };
+        let symbol = &self.resolution.symbols[id];
+        if symbol.span != Some(span) {
+            return;
+        }
+        // `_` and `_name` are unused on purpose
+        if !name.starts_with('_') && !self.uses.contains_key(&id) {
+            let lint = match symbol.kind {
+                SymbolKind::Parameter => Lint::UnusedParameter,
+                _ => Lint::UnusedVariable,
+            };
+            let kind = match lint {
+                Lint::UnusedParameter => "parameter",
+                _ => "variable",
+            };
+            self.report(lint, span, format!("unused {} `{}`", kind, name));
+        }
+        // legacy parameters are always mutable so only variables are checked
+        if symbol.kind == SymbolKind::Variable && symbol.mutable && !self.mutated.contains(&id) {
+            self.report(
+                Lint::UnusedMut,
+                span,
+                format!("variable `{}` is declared `mut` but never changed", name),
+            );
+        }
+        if let Some(previous) = symbol.shadows {
+            let message = match self.resolution.symbols[previous].span {
+                Some(previous_span) => format!(
+                    "`{}` shadows an earlier declaration on {}",
+                    name, previous_span
+                ),
+                None => format!("`{}` shadows an earlier declaration", name),
+            };
+            self.report(Lint::Shadowing, span, message);
+        }
+    }
+    fn check_function(&mut self, name: &str, public: bool, span: Span) {
+        if public || name == "main" || name.starts_with('_') {
+            return;
+        }
+        let Some(&id) = self.resolution.references.get(&span) else {
+            return;
+        };
+        if !self.uses.contains_key(&id) {
+            self.report(
+                Lint::UnusedFunction,
+                span,
+                format!("function `{}` is never called", name),
+            );
+        }
+    }
+    fn check_condition(&mut self, condition: &Expression, span: Span) {
+        let Some(value) = constant_condition(condition) else {
+            return;
+        };
+        self.report(
+            Lint::ConstantCondition,
+            span,
+            format!("condition is always {}", value),
+        );
+    }
+    fn check_loop(&mut self, condition: &Expression, span: Span, inside: &[Expression]) {
+        match constant_condition(condition) {
+            // `while true { ... break; }` is the usual endless loop
+            Some(true) if contains_break(inside) => {}
+            Some(true) => self.report(
+                Lint::ConstantCondition,
+                span,
+                "condition is always true and the loop has no `break`".to_string(),
+            ),
+            Some(false) => self.report(
+                Lint::ConstantCondition,
+                span,
+                "condition is always false, the loop never runs".to_string(),
+            ),
+            None => {}
+        }
+    }
+    fn check_self_assignment(
+        &mut self,
+        target: &Expression,
+        operator: TokenKind,
+        value: &Expression,
+    ) {
+        if operator != TokenKind::Assignment || !self.same_place(target, value) {
+            return;
+        }
+        if let Some(span) = target.span() {
+            self.report(
+                Lint::SelfAssignment,
+                span,
+                "variable is assigned to itself".to_string(),
+            );
+        }
+    }
+    /// `x` and `x`, `a.b` and `a.b` that refer to the same symbols
+    fn same_place(&self, a: &Expression, b: &Expression) -> bool {
+        match (a, b) {
+            (Expression::Grouping(a), b) | (b, Expression::Grouping(a)) => self.same_place(a, b),
+            (Expression::Identifier { span: a, .. }, Expression::Identifier { span: b, .. }) => {
+                let a = self.resolution.references.get(a);
+                return a.is_some() && a == self.resolution.references.get(b);
+            }
+            (
+                Expression::MemberExpr {
+                    member: a_member,
+                    name: a_name,
+                    ..
+                },
+                Expression::MemberExpr {
+                    member: b_member,
+                    name: b_name,
+                    ..
+                },
+            ) => a_name == b_name && self.same_place(a_member, b_member),
+            _ => false,
+        }
+    }
+}
+
+/// Reports the lints that are not allowed by `levels` or by attributes in the source
+pub fn check_lints(
+    expressions: &[Expression],
+    resolution: &Resolution,
+    mutated: &HashSet<SymbolId>,
+    levels: &LintLevels,
+) -> Vec<Diagnostic> {
+    let mut uses = HashMap::new();
+    for (span, id) in &resolution.references {
+        if resolution.symbols[*id].span != Some(*span) {
+            *uses.entry(*id).or_insert(0) += 1;
+        }
+    }
+    let mut linter = Linter {
+        resolution,
+        mutated,
+        uses,
+        levels: vec![levels.clone()],
+        diagnostics: Vec::new(),
+    };
+    lint_block(&mut linter, expressions);
+
+    return linter.diagnostics;
+}
+
+fn lint_block(linter: &mut Linter, block: &[Expression]) {
+    let mut block_levels = linter.levels().clone();
+    // levels of the next statement set by `#[...]`
+    let mut pending: Option<LintLevels> = None;
+    // levels of the current statement, they also apply to the else branches of an if
+    let mut statement_levels: Option<LintLevels> = None;
+    for expression in block {
+        match expression {
+            Expression::Attribute {
+                name,
+                arguments,
+                inner: true,
+                span,
+            } => {
+                linter.apply_attribute(&mut block_levels, name, arguments, *span);
+                continue;
+            }
+            Expression::Attribute {
+                name,
+                arguments,
+                span,
+                ..
+            } => {
+                let mut levels = pending.take().unwrap_or_else(|| block_levels.clone());
+                linter.apply_attribute(&mut levels, name, arguments, *span);
+                pending = Some(levels);
+                continue;
+            }
+            Expression::Keyword(TokenKind::SemiColon) => continue,
+            Expression::Else { .. } => {}
+            _ => statement_levels = pending.take(),
+        }
+        let levels = statement_levels
+            .clone()
+            .unwrap_or_else(|| block_levels.clone());
+        linter.levels.push(levels);
+        lint_expr(linter, expression);
+        linter.levels.pop();
+    }
+}
+fn lint_expr(linter: &mut Linter, expression: &Expression) {
+    match expression {
+        Expression::Interpolated(parts) => {
+            for value in interpolated_values(parts) {
+                lint_expr(linter, value);
+            }
+        }
+        Expression::VariableDeclaration { name, span, .. }
+        | Expression::Out {
+            var_type: Some(_),
+            var_name: name,
+            span,
+        } => linter.check_variable(name, *span),
+        Expression::Assignment {
+            target,
+            operator,
+            value,
+        } => {
+            lint_expr(linter, value);
+            lint_expr(linter, target);
+            linter.check_self_assignment(target, operator.kind, value);
+        }
+        Expression::Function {
+            name,
+            span,
+            properties,
+            public,
+            inside,
+            ..
+        } => {
+            linter.check_function(name, *public, *span);
+            for property in properties {
+                if let Expression::FunctionProperty { var_name, span, .. } = property {
+                    linter.check_variable(var_name, *span);
+                }
+            }
+            lint_block(linter, inside);
+        }
+        Expression::Class { functions, .. } => lint_block(linter, functions),
+        Expression::For {
+            iterator_name,
+            iterator_span,
+            iteration_target,
+            inside,
+        } => {
+            lint_expr(linter, iteration_target);
+            linter.check_variable(iterator_name, *iterator_span);
+            lint_block(linter, inside);
+        }
+        Expression::If {
+            condition,
+            inside,
+            span,
+        } => {
+            lint_expr(linter, condition);
+            linter.check_condition(condition, *span);
+            lint_block(linter, inside);
+        }
+        Expression::Else {
+            condition,
+            inside,
+            span,
+        } => {
+            if let Some(condition) = condition {
+                lint_expr(linter, condition);
+                linter.check_condition(condition, *span);
+            }
+            lint_block(linter, inside);
+        }
+        Expression::While {
+            condition,
+            inside,
+            span,
+        } => {
+            lint_expr(linter, condition);
+            linter.check_loop(condition, *span, inside);
+            lint_block(linter, inside);
+        }
+        Expression::Prefix { value, .. }
+        | Expression::Grouping(value)
+        | Expression::Return { value, .. }
+        | Expression::Borrow { value, .. }
+        | Expression::MemberExpr { member: value, .. }
+        | Expression::StaticMember { member: value, .. } => lint_expr(linter, value),
+        Expression::Binary { l, r, .. } => {
+            lint_expr(linter, l);
+            lint_expr(linter, r);
+        }
+        Expression::Range { from, to } => {
+            lint_expr(linter, from);
+            lint_expr(linter, to);
+        }
+        Expression::IndexArray { left, indexes } => {
+            lint_expr(linter, left);
+            lint_all(linter, indexes);
+        }
+        Expression::FunctionCall { left, values } => {
+            lint_expr(linter, left);
+            lint_all(linter, values);
+        }
+        Expression::TupleDeclaration {
+            bindings: values, ..
+        }
+        | Expression::Tuple(values)
+        | Expression::MacroCall { values, .. }
+        | Expression::ClassInstantiation {
+            properties: values, ..
+        }
+        | Expression::ArrayInitialization { properties: values } => lint_all(linter, values),
+        Expression::Number(_)
+        | Expression::Float(_)
+        | Expression::String(_)
+        | Expression::Identifier { .. }
+        | Expression::Keyword(_)
+        | Expression::Out { .. }
+        | Expression::ClassProperty { .. }
+        | Expression::FunctionProperty { .. }
+        | Expression::Attribute { .. } => {}
+    }
+}
+fn lint_all(linter: &mut Linter, expressions: &[Expression]) {
+    for expression in expressions {
+        lint_expr(linter, expression);
+    }
+}
+
+/// Value of a condition made of `true`, `false`, `!` and comparisons of numbers
+fn constant_condition(condition: &Expression) -> Option<bool> {
+    match condition {
+        Expression::Identifier { name, .. } => match name.as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        },
+        Expression::Grouping(inside) => constant_condition(inside),
+        Expression::Prefix { prefix, value } if prefix.kind == TokenKind::Not => {
+            constant_condition(value).map(|value| !value)
+        }
+        Expression::Binary { l, operator, r } => {
+            if let (Expression::Number(l), Expression::Number(r)) = (&**l, &**r) {
+                return match operator.kind {
+                    TokenKind::Equals => Some(l == r),
+                    TokenKind::NotEquals => Some(l != r),
+                    TokenKind::Less => Some(l < r),
+                    TokenKind::LessEquals => Some(l <= r),
+                    TokenKind::Greater => Some(l > r),
+                    TokenKind::GreaterEquals => Some(l >= r),
+                    _ => None,
+                };
+            }
+            let l = constant_condition(l);
+            let r = constant_condition(r);
+            match operator.kind {
+                // `false && x` is false no matter what x is
+                TokenKind::And => match (l, r) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                },
+                TokenKind::Or => match (l, r) {
+                    (Some(true), _) | (_, Some(true)) => Some(true),
+                    (Some(false), Some(false)) => Some(false),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    diagnostic::Diagnostic,
+    expression::{interpolated_values, Expression},
+    resolver::{Resolution, SymbolId, SymbolKind},
+    tokens::{Span, Token, TokenKind},
+};
+
+pub struct Mutability {
+    pub diagnostics: Vec<Diagnostic>,
+    /// variables and parameters that are changed after they got their first value
+    pub mutated: HashSet<SymbolId>,
+}
+
+struct MutabilityChecker<'a> {
+    resolution: &'a Resolution,
+    diagnostics: Vec<Diagnostic>,
+    mutated: HashSet<SymbolId>,
+    /// assignments to `let x;` declarations
+    initializations: HashMap<SymbolId, usize>,
+    /// `let x;` declarations that are assigned on every path to the current expression,
+    /// only straight-line code is followed, a branch forgets what it assigned
+    assigned: HashSet<SymbolId>,
+}
+impl MutabilityChecker<'_> {
+    /// `name` is the span of the mutated identifier, `span` is where the problem is reported
+    fn check_mutation(&mut self, name: Span, span: Span, action: &str, operator: Option<&Token>) {
+        let Some(&id) = self.resolution.references.get(&name) else {
+            return;
+        };
+        let symbol = &self.resolution.symbols[id];
+        if !matches!(symbol.kind, SymbolKind::Variable | SymbolKind::Parameter) {
+            return;
+        }
+        // `let x;` can be assigned later without `mut`, but only once
+        let initialization = !symbol.initialized
+            && !self.assigned.contains(&id)
+            && operator.is_some_and(|operator| operator.kind == TokenKind::Assignment);
+        if initialization {
+            *self.initializations.entry(id).or_default() += 1;
+            self.assigned.insert(id);
+        } else {
+            self.mutated.insert(id);
+        }
+        if symbol.mutable || initialization {
+            return;
+        }
+        let name = &symbol.name;
+        let (kind, declaration) = match (&symbol.kind, &symbol.var_type) {
+            (SymbolKind::Parameter, Some(var_type)) => {
+                ("parameter", format!("mut {}: {}", name, var_type))
+            }
+            (SymbolKind::Parameter, None) => ("parameter", format!("mut {}", name)),
+            _ => ("variable", format!("let mut {}", name)),
+        };
+
+        self.diagnostics.push(Diagnostic::error(
+            span,
+            format!(
+                "cannot {} immutable {} `{}`, declare it with `{}`",
+                action, kind, name, declaration
+            ),
+        ));
+    }
+}
+
+/// Reports assignments, compound assignments, `out` and `&mut` uses of bindings declared without `mut`
+pub fn check_mutability(expressions: &[Expression], resolution: &Resolution) -> Mutability {
+    let mut checker = MutabilityChecker {
+        resolution,
+        diagnostics: Vec::new(),
+        mutated: HashSet::new(),
+        initializations: HashMap::new(),
+        assigned: HashSet::new(),
+    };
+    check_block(&mut checker, expressions);
+
+    // the second assignment of `let mut x; x = 1; x = 2;` changes the value
+    let reassigned = checker
+        .initializations
+        .iter()
+        .filter(|(_, count)| **count > 1)
+        .map(|(id, _)| *id);
+    checker.mutated.extend(reassigned);
+
+    return Mutability {
+        diagnostics: checker.diagnostics,
+        mutated: checker.mutated,
+    };
+}
+fn check_block(checker: &mut MutabilityChecker, expressions: &[Expression]) {
+    for expression in expressions {
+        check_expr(checker, expression);
+    }
+}
+/// body that isn't always run, like a branch or a loop
+fn check_branch(checker: &mut MutabilityChecker, expressions: &[Expression]) {
+    let assigned = checker.assigned.clone();
+    check_block(checker, expressions);
+    checker.assigned = assigned;
+}
+fn check_expr(checker: &mut MutabilityChecker, expression: &Expression) {
+    match expression {
+        Expression::Interpolated(parts) => {
+            for value in interpolated_values(parts) {
+                check_expr(checker, value);
+            }
+        }
+        Expression::Assignment {
+            target,
+            operator,
+            value,
+        } => check_assignment(checker, target, operator, value),
+        // out i32 name declares a new variable
+        Expression::Out {
+            var_type: None,
+            span,
+            ..
+        } => checker.check_mutation(*span, *span, "pass as out", None),
+        Expression::Borrow {
+            value,
+            mutable,
+            span,
+        } => {
+            check_expr(checker, value);
+            if *mutable {
+                if let Some(name) = root_identifier(value) {
+                    checker.check_mutation(name, *span, "borrow as mutable", None);
+                }
+            }
+        }
+        Expression::Function { inside, .. } => check_branch(checker, inside),
+        Expression::For {
+            iteration_target,
+            inside,
+            ..
+        } => {
+            check_expr(checker, iteration_target);
+            check_branch(checker, inside);
+        }
+        Expression::If {
+            condition, inside, ..
+        }
+        | Expression::While {
+            condition, inside, ..
+        } => {
+            check_expr(checker, condition);
+            check_branch(checker, inside);
+        }
+        Expression::Else {
+            condition, inside, ..
+        } => {
+            if let Some(condition) = condition {
+                check_expr(checker, condition);
+            }
+            check_branch(checker, inside);
+        }
+        Expression::ClassInstantiation { properties, .. } => {
+            // `x = 10` inside of the instantiation sets a field of the new instance
+            for property in properties {
+                match property {
+                    Expression::Assignment { value, .. } => check_expr(checker, value),
+                    other => check_expr(checker, other),
+                }
+            }
+        }
+        Expression::Prefix { value, .. }
+        | Expression::Grouping(value)
+        | Expression::Return { value, .. }
+        | Expression::MemberExpr { member: value, .. }
+        | Expression::StaticMember { member: value, .. } => check_expr(checker, value),
+        Expression::Binary { l, r, .. } => {
+            check_expr(checker, l);
+            check_expr(checker, r);
+        }
+        Expression::Range { from, to } => {
+            check_expr(checker, from);
+            check_expr(checker, to);
+        }
+        Expression::IndexArray { left, indexes } => {
+            check_expr(checker, left);
+            check_block(checker, indexes);
+        }
+        Expression::FunctionCall { left, values } => {
+            check_expr(checker, left);
+            check_block(checker, values);
+            // `list.push(x)` can change list, the method itself is not known so it isn't reported
+            if let Expression::MemberExpr { member, .. } = &**left {
+                if let Some(&id) = root_identifier(member)
+                    .and_then(|name| checker.resolution.references.get(&name))
+                {
+                    checker.mutated.insert(id);
+                }
+            }
+        }
+        Expression::Tuple(values)
+        | Expression::MacroCall { values, .. }
+        | Expression::ArrayInitialization { properties: values } => check_block(checker, values),
+        Expression::Number(_)
+        | Expression::Float(_)
+        | Expression::String(_)
+        | Expression::Out { .. }
+        | Expression::VariableDeclaration { .. }
+        | Expression::TupleDeclaration { .. }
+        | Expression::Identifier { .. }
+        | Expression::Keyword(_)
+        | Expression::Attribute { .. }
+        | Expression::Class { .. }
+        | Expression::ClassProperty { .. }
+        | Expression::FunctionProperty { .. } => {}
+    }
+}
+fn check_assignment(
+    checker: &mut MutabilityChecker,
+    target: &Expression,
+    operator: &Token,
+    value: &Expression,
+) {
+    check_expr(checker, value);
+    check_expr(checker, target);
+    if let Some(name) = root_identifier(target) {
+        checker.check_mutation(name, operator.span(), "assign twice to", Some(operator));
+    }
+}
+/// `map[x, y].value` -> map, assigning to an element or a field needs the whole variable to be mutable
+fn root_identifier(expression: &Expression) -> Option<Span> {
+    match expression {
+        Expression::Identifier { span, .. } => Some(*span),
+        Expression::IndexArray { left, .. } => root_identifier(left),
+        Expression::MemberExpr { member, .. } => root_identifier(member),
+        Expression::Grouping(inside) => root_identifier(inside),
+        _ => None,
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use crate::{
+    diagnostic::Diagnostic,
+    expression::Expression,
+    rename::rename_symbols,
+    resolver::{Resolution, ScopeId, SymbolId, SymbolKind},
+};
+
+/// Name of a symbol in the c# naming conventions, None when the name is kept.
+/// Fields keep their names because members of values with an unknown type are not resolved
+fn convention(name: &str, kind: SymbolKind) -> Option<String> {
+    let new_name = match kind {
+        SymbolKind::Function | SymbolKind::Class => pascal_case(name),
+        SymbolKind::Variable | SymbolKind::Parameter => camel_case(name),
+        SymbolKind::Field | SymbolKind::External => return None,
+    };
+    if new_name == name {
+        return None;
+    }
+    return Some(new_name);
+}
+/// `player_symbol` -> `PlayerSymbol`, `MAX_SIZE` -> `MaxSize`, leading underscores are kept
+pub fn pascal_case(name: &str) -> String {
+    let trimmed = name.trim_start_matches('_');
+    if trimmed.is_empty() {
+        return name.to_string();
+    }
+    let mut output = name[..name.len() - trimmed.len()].to_string();
+    for part in trimmed.split('_').filter(|part| !part.is_empty()) {
+        let mut chars = part.chars();
+        let first = chars.next().expect("empty parts are skipped");
+        output.extend(first.to_uppercase());
+        let rest = chars.as_str();
+        // MAX -> Max, but IsPosValid stays
+        if part.len() > 1 && !part.chars().any(|char| char.is_lowercase()) {
+            output += &rest.to_lowercase();
+        } else {
+            output += rest;
+        }
+    }
+    return output;
+}
+/// `player_symbol` -> `playerSymbol`, `MapX` -> `mapX`
+fn camel_case(name: &str) -> String {
+    let pascal = pascal_case(name);
+    let prefix = pascal.len() - pascal.trim_start_matches('_').len();
+    let mut chars = pascal[prefix..].chars();
+    let Some(first) = chars.next() else {
+        return pascal;
+    };
+    return format!(
+        "{}{}{}",
+        &pascal[..prefix],
+        first.to_lowercase(),
+        chars.as_str()
+    );
+}
+
+/// `inner` is `outer` or one of the scopes inside of it
+fn is_inside(resolution: &Resolution, inner: ScopeId, outer: ScopeId) -> bool {
+    let mut current = Some(inner);
+    while let Some(scope) = current {
+        if scope == outer {
+            return true;
+        }
+        current = resolution.scopes[scope].parent;
+    }
+    return false;
+}
+
+/// Renames functions and classes to PascalCase and variables and parameters to camelCase.
+/// A name that would collide with another name visible in the same scopes is kept and reported as a warning
+pub fn convert_names(
+    expressions: &mut [Expression],
+    resolution: &mut Resolution,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut names: Vec<String> = resolution
+        .symbols
+        .iter()
+        .map(|symbol| symbol.name.clone())
+        .collect();
+    let mut renames: HashMap<SymbolId, String> = HashMap::new();
+
+    for (id, symbol) in resolution.symbols.iter().enumerate() {
+        let Some(new_name) = convention(&symbol.name, symbol.kind) else {
+            continue;
+        };
+        let collision = resolution
+            .symbols
+            .iter()
+            .enumerate()
+            .find(|(other_id, other)| {
+                // shadowed declarations with the same name get the same new name
+                *other_id != id
+                    && other.name != symbol.name
+                    && other.kind != SymbolKind::Field
+                    && names[*other_id] == new_name
+                    && (is_inside(resolution, symbol.scope, other.scope)
+                        || is_inside(resolution, other.scope, symbol.scope))
+            });
+        if let Some((_, other)) = collision {
+            let message = match other.span {
+                Some(span) => format!(
+                    "`{}` keeps its name, `{}` is already declared on {}",
+                    symbol.name, new_name, span
+                ),
+                None => format!(
+                    "`{}` keeps its name, `{}` is already declared outside of the source",
+                    symbol.name, new_name
+                ),
+            };
+            diagnostics.push(Diagnostic::warning(
+                symbol.span.unwrap_or_default(),
+                message,
+            ));
+            continue;
+        }
+        names[id] = new_name.clone();
+        renames.insert(id, new_name);
+    }
+
+    rename_symbols(expressions, resolution, &renames);
+    for (id, new_name) in renames {
+        resolution.symbols[id].name = new_name;
+    }
+
+    return diagnostics;
+}
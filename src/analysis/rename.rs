@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use crate::{
+    expression::Expression,
+    resolver::{Resolution, SymbolId, SymbolKind},
+    tokens::Span,
+    types::Type,
+    visitor::{walk_expression_mut, walk_type_mut, VisitorMut},
+};
+
+struct Renamer<'a> {
+    resolution: &'a Resolution,
+    renames: &'a HashMap<SymbolId, String>,
+    /// old name -> new name of renamed classes, types don't have spans
+    classes: HashMap<String, String>,
+}
+impl Renamer<'_> {
+    fn new_name(&self, span: Span) -> Option<&String> {
+        let id = self.resolution.references.get(&span)?;
+        return self.renames.get(id);
+    }
+    /// a declaration or a use, both of them are resolved to the symbol
+    fn use_site(&self, name: &mut String, span: Span) {
+        if let Some(new_name) = self.new_name(span) {
+            *name = new_name.clone();
+        }
+    }
+}
+
+/// Gives the symbols in `renames` their new names at the declaration and every use,
+/// also inside of interpolated strings and in types for classes
+pub fn rename_symbols(
+    expressions: &mut [Expression],
+    resolution: &Resolution,
+    renames: &HashMap<SymbolId, String>,
+) {
+    let mut renamer = Renamer {
+        resolution,
+        renames,
+        classes: HashMap::new(),
+    };
+    for (id, new_name) in renames {
+        let symbol = &resolution.symbols[*id];
+        if symbol.kind == SymbolKind::Class {
+            renamer
+                .classes
+                .insert(symbol.name.clone(), new_name.clone());
+        }
+    }
+    for expression in expressions {
+        renamer.visit_expression_mut(expression);
+    }
+}
+
+impl VisitorMut for Renamer<'_> {
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        match expression {
+            Expression::Identifier { name, span } => self.use_site(name, *span),
+            Expression::VariableDeclaration {
+                var_type,
+                name,
+                span,
+                ..
+            } => {
+                if let Some(var_type) = var_type {
+                    self.visit_type_mut(var_type);
+                }
+                self.use_site(name, *span);
+            }
+            Expression::Out {
+                var_type,
+                var_name,
+                span,
+            } => match var_type {
+                // out i32 name declares a new variable
+                Some(var_type) => {
+                    self.visit_type_mut(var_type);
+                    self.use_site(var_name, *span);
+                }
+                None => self.use_site(var_name, *span),
+            },
+            Expression::FunctionProperty {
+                var_type,
+                var_name,
+                span,
+                ..
+            } => {
+                self.visit_type_mut(var_type);
+                self.use_site(var_name, *span);
+            }
+            Expression::Function { name, span, .. }
+            | Expression::Class { name, span, .. }
+            | Expression::ClassInstantiation { name, span, .. } => {
+                self.use_site(name, *span);
+                walk_expression_mut(self, expression);
+            }
+            Expression::For {
+                iterator_name,
+                iterator_span,
+                ..
+            } => {
+                self.use_site(iterator_name, *iterator_span);
+                walk_expression_mut(self, expression);
+            }
+            _ => walk_expression_mut(self, expression),
+        }
+    }
+    fn visit_type_mut(&mut self, var_type: &mut Type) {
+        match var_type {
+            Type::Symbol(symbol) => {
+                if let Some(new_name) = self.classes.get(symbol) {
+                    *symbol = new_name.clone();
+                }
+            }
+            _ => walk_type_mut(self, var_type),
+        }
+    }
+}
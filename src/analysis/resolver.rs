@@ -0,0 +1,582 @@
+use std::collections::HashMap;
+
+use crate::{
+    diagnostic::Diagnostic,
+    expression::{interpolated_values, Expression},
+    tokens::Span,
+    types::Type,
+};
+
+/// Names that come from c# or the source standard library, they are never reported as undefined.
+/// More of them can be added with `--extern <name>`
+const EXTERNALS: &[&str] = &[
+    "true", "false", "null", "Console", "Math", "Convert", "Random", "int", "uint", "long",
+    "ulong", "short", "ushort", "byte", "sbyte", "float", "double", "decimal", "char", "bool",
+    "string", "object", "i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64", "usize", "isize",
+    "f32", "f64", "str", "String", "Vec", "HashMap", "HashSet", "VecDeque",
+];
+
+pub type SymbolId = usize;
+pub type ScopeId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Variable,
+    Parameter,
+    Function,
+    Class,
+    Field,
+    // defined outside of the source like `Console`
+    External,
+}
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// None for externals
+    pub span: Option<Span>,
+    pub mutable: bool,
+    /// false for `let x;` until it is assigned
+    pub initialized: bool,
+    /// type of a variable, parameter or field, return type of a function
+    pub var_type: Option<Type>,
+    /// scope with the fields of a class
+    pub members: Option<ScopeId>,
+    /// variable or parameter with the same name that this one hides
+    pub shadows: Option<SymbolId>,
+    /// scope the symbol is declared in
+    pub scope: ScopeId,
+}
+
+pub struct Scope {
+    pub parent: Option<ScopeId>,
+    pub symbols: HashMap<String, SymbolId>,
+}
+
+/// Symbol table produced by `resolve`
+pub struct Resolution {
+    pub symbols: Vec<Symbol>,
+    pub scopes: Vec<Scope>,
+    /// span of every identifier, member name and declaration -> symbol it refers to
+    pub references: HashMap<Span, SymbolId>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+impl Resolution {
+    pub fn symbol_at(&self, span: Span) -> Option<&Symbol> {
+        return self.references.get(&span).map(|id| &self.symbols[*id]);
+    }
+    fn find_in(&self, scope: ScopeId, name: &str) -> Option<SymbolId> {
+        let mut current = Some(scope);
+        while let Some(scope) = current {
+            if let Some(id) = self.scopes[scope].symbols.get(name) {
+                return Some(*id);
+            }
+            current = self.scopes[scope].parent;
+        }
+        return None;
+    }
+}
+
+struct Resolver {
+    resolution: Resolution,
+    current: ScopeId,
+    /// class types of `let`s without a type, taken from their value like `let p = Point { ... }`
+    instances: HashMap<SymbolId, Type>,
+}
+impl Resolver {
+    fn push_scope(&mut self) -> ScopeId {
+        self.resolution.scopes.push(Scope {
+            parent: Some(self.current),
+            symbols: HashMap::new(),
+        });
+        self.current = self.resolution.scopes.len() - 1;
+        return self.current;
+    }
+    fn pop_scope(&mut self) {
+        self.current = self.resolution.scopes[self.current]
+            .parent
+            .expect("the root scope is never popped");
+    }
+    fn error(&mut self, span: Span, message: String) {
+        self.resolution
+            .diagnostics
+            .push(Diagnostic::error(span, message));
+    }
+    fn declare(&mut self, mut symbol: Symbol) -> SymbolId {
+        let name = symbol.name.clone();
+        let span = symbol.span;
+        let kind = symbol.kind;
+
+        let variable = |kind| matches!(kind, SymbolKind::Variable | SymbolKind::Parameter);
+        if variable(kind) {
+            symbol.shadows = self
+                .resolution
+                .find_in(self.current, &name)
+                .filter(|previous| variable(self.resolution.symbols[*previous].kind));
+        }
+
+        let mut duplicate = false;
+        if let Some(previous) = self.resolution.scopes[self.current].symbols.get(&name) {
+            let previous = &self.resolution.symbols[*previous];
+            // `let x` can shadow an earlier variable or parameter, everything else has to be unique
+            let shadowing = kind == SymbolKind::Variable && variable(previous.kind);
+            if !shadowing && kind != SymbolKind::External {
+                let message = match previous.span {
+                    Some(previous_span) => format!(
+                        "`{}` is already declared in this scope (first declared on {})",
+                        name, previous_span
+                    ),
+                    None => format!("`{}` is already declared in this scope", name),
+                };
+                self.error(span.unwrap_or_default(), message);
+                duplicate = true;
+            }
+        }
+
+        self.resolution.symbols.push(symbol);
+        let id = self.resolution.symbols.len() - 1;
+        // uses keep referring to the first declaration of a duplicate
+        if !duplicate {
+            self.resolution.scopes[self.current]
+                .symbols
+                .insert(name, id);
+        }
+        if let Some(span) = span {
+            self.resolution.references.insert(span, id);
+        }
+        return id;
+    }
+    fn declare_variable(
+        &mut self,
+        kind: SymbolKind,
+        name: &str,
+        span: Span,
+        mutable: bool,
+        var_type: Option<Type>,
+    ) -> SymbolId {
+        return self.declare(Symbol {
+            name: name.to_string(),
+            kind,
+            span: Some(span),
+            mutable,
+            initialized: true,
+            var_type,
+            members: None,
+            shadows: None,
+            scope: self.current,
+        });
+    }
+    fn reference(&mut self, name: &str, span: Span) -> Option<SymbolId> {
+        match self.resolution.find_in(self.current, name) {
+            Some(id) => {
+                self.resolution.references.insert(span, id);
+                return Some(id);
+            }
+            None => {
+                self.error(span, format!("cannot find `{}` in this scope", name));
+                return None;
+            }
+        }
+    }
+    /// type of the value as far as the names tell it, the type checker infers the rest later
+    fn value_type(&self, expression: &Expression) -> Option<Type> {
+        match expression {
+            Expression::Identifier { span, .. } | Expression::MemberExpr { span, .. } => {
+                let id = *self.resolution.references.get(span)?;
+                let symbol = &self.resolution.symbols[id];
+                match symbol.kind {
+                    SymbolKind::Variable | SymbolKind::Parameter | SymbolKind::Field => {
+                        return symbol
+                            .var_type
+                            .clone()
+                            .or_else(|| self.instances.get(&id).cloned());
+                    }
+                    _ => return None,
+                }
+            }
+            Expression::ClassInstantiation { name, .. } => {
+                return Some(Type::Symbol(name.clone()));
+            }
+            // the output of a function
+            Expression::FunctionCall { left, .. } => {
+                let Expression::Identifier { span, .. } = left.as_ref() else {
+                    return None;
+                };
+                let function = self.resolution.symbol_at(*span)?;
+                match function.kind {
+                    SymbolKind::Function => return function.var_type.clone(),
+                    _ => return None,
+                }
+            }
+            Expression::Grouping(inside) => return self.value_type(inside),
+            _ => return None,
+        }
+    }
+    /// fields of the class that `expression` evaluates to, None when the type is not known
+    fn members_of(&self, expression: &Expression) -> Option<ScopeId> {
+        let Some(Type::Symbol(class_name)) = self.value_type(expression) else {
+            return None;
+        };
+        let class = self.resolution.find_in(self.current, &class_name)?;
+        return self.resolution.symbols[class].members;
+    }
+    fn reference_member(&mut self, members: ScopeId, name: &str, span: Span) {
+        match self.resolution.scopes[members].symbols.get(name) {
+            Some(id) => {
+                self.resolution.references.insert(span, *id);
+            }
+            None => self.error(span, format!("no field `{}` on this class", name)),
+        }
+    }
+}
+
+/// Binds every identifier, member name and declaration to a symbol and reports undefined or duplicate names
+pub fn resolve(expressions: &[Expression], externals: &[String]) -> Resolution {
+    let mut resolver = Resolver {
+        resolution: Resolution {
+            symbols: Vec::new(),
+            scopes: vec![Scope {
+                parent: None,
+                symbols: HashMap::new(),
+            }],
+            references: HashMap::new(),
+            diagnostics: Vec::new(),
+        },
+        current: 0,
+        instances: HashMap::new(),
+    };
+
+    let names = EXTERNALS.iter().map(|name| name.to_string());
+    for name in names.chain(externals.iter().cloned()) {
+        resolver.declare(Symbol {
+            name,
+            kind: SymbolKind::External,
+            span: None,
+            mutable: false,
+            initialized: true,
+            var_type: None,
+            members: None,
+            shadows: None,
+            scope: resolver.current,
+        });
+    }
+
+    // the program gets its own scope so it can redeclare external names
+    resolver.push_scope();
+    resolve_block(&mut resolver, expressions);
+
+    return resolver.resolution;
+}
+
+fn resolve_block(resolver: &mut Resolver, expressions: &[Expression]) {
+    // functions and classes can be used before they are declared
+    for expression in expressions {
+        declare_item(resolver, expression);
+    }
+    for expression in expressions {
+        resolve_expr(resolver, expression);
+    }
+}
+fn resolve_scope(resolver: &mut Resolver, expressions: &[Expression]) {
+    resolver.push_scope();
+    resolve_block(resolver, expressions);
+    resolver.pop_scope();
+}
+fn declare_item(resolver: &mut Resolver, expression: &Expression) {
+    match expression {
+        Expression::Function {
+            name, span, output, ..
+        } => {
+            resolver.declare(Symbol {
+                name: name.clone(),
+                kind: SymbolKind::Function,
+                span: Some(*span),
+                mutable: false,
+                initialized: true,
+                var_type: output.clone(),
+                members: None,
+                shadows: None,
+                scope: resolver.current,
+            });
+        }
+        Expression::Class {
+            name,
+            span,
+            properties,
+            ..
+        } => {
+            let members = resolver.push_scope();
+            for property in properties {
+                if let Expression::ClassProperty {
+                    var_name,
+                    var_type,
+                    span,
+                } = property
+                {
+                    resolver.declare_variable(
+                        SymbolKind::Field,
+                        var_name,
+                        *span,
+                        true,
+                        Some(var_type.clone()),
+                    );
+                }
+            }
+            resolver.pop_scope();
+
+            resolver.declare(Symbol {
+                name: name.clone(),
+                kind: SymbolKind::Class,
+                span: Some(*span),
+                mutable: false,
+                initialized: true,
+                var_type: None,
+                members: Some(members),
+                shadows: None,
+                scope: resolver.current,
+            });
+        }
+        _ => {}
+    }
+}
+fn resolve_expr(resolver: &mut Resolver, expression: &Expression) {
+    match expression {
+        Expression::Identifier { name, span } => {
+            resolver.reference(name, *span);
+        }
+        Expression::Interpolated(parts) => {
+            for value in interpolated_values(parts) {
+                resolve_expr(resolver, value);
+            }
+        }
+        Expression::Assignment { target, value, .. } => {
+            // the value is resolved first so `let x = x + 1` refers to the previous x
+            resolve_expr(resolver, value);
+            resolve_assignment_target(resolver, target);
+            if let Expression::VariableDeclaration {
+                var_type: None,
+                span,
+                ..
+            } = target.as_ref()
+            {
+                if let Some(var_type) = resolver.value_type(value) {
+                    let id = resolver.resolution.references[span];
+                    resolver.instances.insert(id, var_type);
+                }
+            }
+        }
+        Expression::VariableDeclaration {
+            var_type,
+            name,
+            mutable,
+            span,
+        } => {
+            // `let x;` is initialized by a later assignment
+            let id = resolver.declare_variable(
+                SymbolKind::Variable,
+                name,
+                *span,
+                *mutable,
+                var_type.clone(),
+            );
+            resolver.resolution.symbols[id].initialized = false;
+        }
+        Expression::TupleDeclaration { var_type, bindings } => {
+            declare_tuple(resolver, var_type, bindings);
+            for binding in bindings {
+                if let Expression::VariableDeclaration { span, .. } = binding {
+                    let id = resolver.resolution.references[span];
+                    resolver.resolution.symbols[id].initialized = false;
+                }
+            }
+        }
+        Expression::Out {
+            var_type,
+            var_name,
+            span,
+        } => match var_type {
+            // out i32 name declares a new variable in the enclosing scope like in c#
+            Some(var_type) => {
+                resolver.declare_variable(
+                    SymbolKind::Variable,
+                    var_name,
+                    *span,
+                    false,
+                    Some(var_type.clone()),
+                );
+            }
+            None => {
+                resolver.reference(var_name, *span);
+            }
+        },
+        Expression::Function {
+            properties, inside, ..
+        } => {
+            resolver.push_scope();
+            for property in properties {
+                if let Expression::FunctionProperty {
+                    var_name,
+                    var_type,
+                    mutable,
+                    span,
+                } = property
+                {
+                    resolver.declare_variable(
+                        SymbolKind::Parameter,
+                        var_name,
+                        *span,
+                        *mutable,
+                        Some(var_type.clone()),
+                    );
+                }
+            }
+            resolve_block(resolver, inside);
+            resolver.pop_scope();
+        }
+        Expression::ClassInstantiation {
+            name,
+            span,
+            properties,
+        } => {
+            let members = resolver
+                .reference(name, *span)
+                .and_then(|class| resolver.resolution.symbols[class].members);
+            for property in properties {
+                let Expression::Assignment { target, value, .. } = property else {
+                    resolve_expr(resolver, property);
+                    continue;
+                };
+                resolve_expr(resolver, value);
+                // `x = 10` inside of the instantiation sets a field of the new instance
+                match (&**target, members) {
+                    (Expression::Identifier { name, span }, Some(members)) => {
+                        resolver.reference_member(members, name, *span)
+                    }
+                    // the class itself was already reported
+                    (Expression::Identifier { .. }, None) => {}
+                    (other, _) => resolve_expr(resolver, other),
+                }
+            }
+        }
+        Expression::MemberExpr { member, name, span } => {
+            resolve_expr(resolver, member);
+            if let Some(members) = resolver.members_of(member) {
+                resolver.reference_member(members, name, *span);
+            }
+        }
+        Expression::For {
+            iterator_name,
+            iterator_span,
+            iteration_target,
+            inside,
+        } => {
+            resolve_expr(resolver, iteration_target);
+            resolver.push_scope();
+            resolver.declare_variable(
+                SymbolKind::Variable,
+                iterator_name,
+                *iterator_span,
+                false,
+                None,
+            );
+            resolve_block(resolver, inside);
+            resolver.pop_scope();
+        }
+        Expression::If {
+            condition, inside, ..
+        }
+        | Expression::While {
+            condition, inside, ..
+        } => {
+            resolve_expr(resolver, condition);
+            resolve_scope(resolver, inside);
+        }
+        Expression::Else {
+            condition, inside, ..
+        } => {
+            if let Some(condition) = condition {
+                resolve_expr(resolver, condition);
+            }
+            resolve_scope(resolver, inside);
+        }
+        Expression::Prefix { value, .. }
+        | Expression::Grouping(value)
+        | Expression::Return { value, .. }
+        | Expression::Borrow { value, .. }
+        | Expression::StaticMember { member: value, .. } => resolve_expr(resolver, value),
+        Expression::Binary { l, r, .. } => {
+            resolve_expr(resolver, l);
+            resolve_expr(resolver, r);
+        }
+        Expression::Range { from, to } => {
+            resolve_expr(resolver, from);
+            resolve_expr(resolver, to);
+        }
+        Expression::IndexArray { left, indexes } => {
+            resolve_expr(resolver, left);
+            resolve_all(resolver, indexes);
+        }
+        Expression::FunctionCall { left, values } => {
+            resolve_expr(resolver, left);
+            resolve_all(resolver, values);
+        }
+        Expression::Tuple(values)
+        | Expression::MacroCall { values, .. }
+        | Expression::ArrayInitialization { properties: values } => resolve_all(resolver, values),
+        // declared by declare_item
+        Expression::Class { .. }
+        | Expression::ClassProperty { .. }
+        | Expression::FunctionProperty { .. }
+        | Expression::Number(_)
+        | Expression::Float(_)
+        | Expression::String(_)
+        | Expression::Keyword(_)
+        | Expression::Attribute { .. } => {}
+    }
+}
+fn resolve_all(resolver: &mut Resolver, expressions: &[Expression]) {
+    for expression in expressions {
+        resolve_expr(resolver, expression);
+    }
+}
+fn resolve_assignment_target(resolver: &mut Resolver, target: &Expression) {
+    match target {
+        Expression::VariableDeclaration {
+            var_type,
+            name,
+            mutable,
+            span,
+        } => {
+            resolver.declare_variable(
+                SymbolKind::Variable,
+                name,
+                *span,
+                *mutable,
+                var_type.clone(),
+            );
+        }
+        Expression::TupleDeclaration { var_type, bindings } => {
+            declare_tuple(resolver, var_type, bindings)
+        }
+        other => resolve_expr(resolver, other),
+    }
+}
+/// let (a, b): (i32, bool) gives a the type i32 and b the type bool
+fn declare_tuple(resolver: &mut Resolver, var_type: &Option<Type>, bindings: &[Expression]) {
+    for (i, binding) in bindings.iter().enumerate() {
+        if let Expression::VariableDeclaration {
+            name,
+            mutable,
+            span,
+            ..
+        } = binding
+        {
+            let binding_type = match var_type {
+                Some(Type::Tuple(types)) => types.get(i).cloned(),
+                _ => None,
+            };
+            resolver.declare_variable(SymbolKind::Variable, name, *span, *mutable, binding_type);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    expression::Expression,
+    rename::rename_symbols,
+    resolver::{Resolution, SymbolId},
+    tokens::Span,
+    visitor::{walk_expression, Visitor},
+};
+
+/// Variable or parameter found while walking a function
+struct Declaration {
+    id: SymbolId,
+    /// function, or the program for top level code, the declaration is inside of
+    function: usize,
+    /// blocks from the function body down to the block that contains the declaration
+    blocks: Vec<usize>,
+}
+
+struct Collector<'a> {
+    resolution: &'a Resolution,
+    declarations: Vec<Declaration>,
+    function: usize,
+    blocks: Vec<usize>,
+    /// number of functions and blocks seen so far, used as their ids
+    count: usize,
+}
+impl Collector<'_> {
+    fn next_id(&mut self) -> usize {
+        self.count += 1;
+        return self.count;
+    }
+    fn declare(&mut self, span: Span) {
+        let Some(&id) = self.resolution.references.get(&span) else {
+            return;
+        };
+        if self.resolution.symbols[id].span != Some(span) {
+            return;
+        }
+        self.declarations.push(Declaration {
+            id,
+            function: self.function,
+            blocks: self.blocks.clone(),
+        });
+    }
+}
+
+/// C# doesn't allow a local with the same name as a local of the same or an enclosing block,
+/// `let x = 1; let x = x + 1;` is emitted as `var x = 1; var x_1 = x + 1;`.
+/// Local functions can hide the variables of the code around them so every function is checked on its own
+pub fn rename_shadowed(expressions: &mut [Expression], resolution: &Resolution) {
+    let mut collector = Collector {
+        resolution,
+        declarations: Vec::new(),
+        function: 0,
+        blocks: Vec::new(),
+        count: 0,
+    };
+    collector.visit_block(expressions);
+
+    // the new names must not hide anything either
+    let mut taken: HashSet<String> = resolution
+        .symbols
+        .iter()
+        .map(|symbol| symbol.name.clone())
+        .collect();
+    let mut suffixes: HashMap<String, usize> = HashMap::new();
+    let mut renames: HashMap<SymbolId, String> = HashMap::new();
+    let declarations = &collector.declarations;
+    for (i, declaration) in declarations.iter().enumerate() {
+        let name = &resolution.symbols[declaration.id].name;
+        let conflicts = declarations[..i].iter().any(|earlier| {
+            earlier.function == declaration.function
+                && resolution.symbols[earlier.id].name == *name
+                && overlaps(&earlier.blocks, &declaration.blocks)
+        });
+        if !conflicts {
+            continue;
+        }
+        let suffix = suffixes.entry(name.clone()).or_insert(0);
+        let new_name = loop {
+            *suffix += 1;
+            let new_name = format!("{}_{}", name, suffix);
+            if !taken.contains(&new_name) {
+                break new_name;
+            }
+        };
+        taken.insert(new_name.clone());
+        renames.insert(declaration.id, new_name);
+    }
+
+    if !renames.is_empty() {
+        rename_symbols(expressions, resolution, &renames);
+    }
+}
+/// a local is visible in its whole block, two locals collide when one block contains the other
+fn overlaps(a: &[usize], b: &[usize]) -> bool {
+    return a.starts_with(b) || b.starts_with(a);
+}
+
+impl Visitor for Collector<'_> {
+    fn visit_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::VariableDeclaration { span, .. }
+            | Expression::Out {
+                var_type: Some(_),
+                span,
+                ..
+            } => self.declare(*span),
+            Expression::Function {
+                properties, inside, ..
+            } => {
+                let outer_function = self.function;
+                let outer_blocks = std::mem::take(&mut self.blocks);
+                // the parameters are visible in the whole body
+                self.function = self.next_id();
+                for property in properties {
+                    if let Expression::FunctionProperty { span, .. } = property {
+                        self.declare(*span);
+                    }
+                }
+                self.visit_block(inside);
+                self.function = outer_function;
+                self.blocks = outer_blocks;
+            }
+            Expression::For {
+                iterator_span,
+                iteration_target,
+                inside,
+                ..
+            } => {
+                self.visit_expression(iteration_target);
+                let id = self.next_id();
+                self.blocks.push(id);
+                self.declare(*iterator_span);
+                self.visit_block(inside);
+                self.blocks.pop();
+            }
+            Expression::Assignment { target, value, .. } => {
+                self.visit_expression(value);
+                self.visit_expression(target);
+            }
+            // methods of classes are not resolved
+            Expression::Class { .. } => {}
+            _ => walk_expression(self, expression),
+        }
+    }
+    fn visit_block(&mut self, block: &[Expression]) {
+        let id = self.next_id();
+        self.blocks.push(id);
+        for expression in block {
+            self.visit_expression(expression);
+        }
+        self.blocks.pop();
+    }
+}
@@ -0,0 +1,904 @@
+use std::collections::HashMap;
+
+use crate::{
+    collections::handle_collection_name,
+    diagnostic::Diagnostic,
+    expression::{interpolated_values, Expression},
+    resolver::{Resolution, SymbolId, SymbolKind},
+    tokens::{Span, Token, TokenKind},
+    type_map::TypeMap,
+    types::Type,
+};
+
+// literals don't have a concrete type until they are assigned, like `{integer}` in rustc
+const INTEGER_LITERAL: &str = "{integer}";
+const FLOAT_LITERAL: &str = "{float}";
+
+const INTEGERS: [&str; 13] = [
+    "sbyte", "byte", "short", "ushort", "int", "uint", "long", "ulong", "Int128", "UInt128",
+    "nint", "nuint", "char",
+];
+const SIGNED_INTEGERS: [&str; 6] = ["sbyte", "short", "int", "long", "Int128", "nint"];
+const FLOATS: [&str; 3] = ["float", "double", "decimal"];
+/// arithmetic on these gives an int in c#
+const PROMOTED_TO_INT: [&str; 6] = ["sbyte", "byte", "short", "ushort", "char", "int"];
+
+struct TypeChecker<'a> {
+    resolution: &'a Resolution,
+    type_map: &'a TypeMap,
+    /// types of declarations without an annotation, taken from their value
+    inferred: HashMap<SymbolId, Type>,
+    /// parameter types of every function
+    parameters: HashMap<SymbolId, Vec<Type>>,
+    /// return type of every function that is being checked, the innermost one is last
+    returns: Vec<Option<Type>>,
+    diagnostics: Vec<Diagnostic>,
+}
+impl TypeChecker<'_> {
+    fn error(&mut self, span: Span, message: String) {
+        self.diagnostics.push(Diagnostic::error(span, message));
+    }
+    fn mismatch(&mut self, span: Span, expected: &Type, found: &Type) {
+        self.error(
+            span,
+            format!(
+                "mismatched types: expected `{}`, found `{}`",
+                expected, found
+            ),
+        );
+    }
+    /// Source types are compared as the c# types they are emitted as, so `u32` and `uint` are the same
+    fn canonical(&self, var_type: &Type) -> Type {
+        match var_type {
+            Type::Symbol(symbol) => {
+                Type::Symbol(self.type_map.get(symbol).unwrap_or(symbol.clone()))
+            }
+            Type::Array {
+                left_type,
+                dimensions,
+            } => Type::Array {
+                left_type: Box::new(self.canonical(left_type)),
+                dimensions: *dimensions,
+            },
+            Type::Generic { name, arguments } => Type::Generic {
+                name: name.clone(),
+                arguments: arguments.iter().map(|item| self.canonical(item)).collect(),
+            },
+            Type::Tuple(types) => {
+                Type::Tuple(types.iter().map(|item| self.canonical(item)).collect())
+            }
+            Type::Reference { inner, mutable } => Type::Reference {
+                inner: Box::new(self.canonical(inner)),
+                mutable: *mutable,
+            },
+        }
+    }
+    fn assignable(&self, expected: &Type, found: &Type) -> bool {
+        return assignable(&self.canonical(expected), &self.canonical(found), false);
+    }
+    /// reports a mismatch when the type of the value is known and can't be assigned to `expected`
+    fn expect_type(&mut self, span: Span, expected: &Type, found: Option<Type>) {
+        if let Some(found) = found {
+            if !self.assignable(expected, &found) {
+                self.mismatch(span, expected, &found);
+            }
+        }
+    }
+    fn is(&self, var_type: &Type, check: fn(&str) -> bool) -> bool {
+        match self.canonical(&dereference(var_type)) {
+            Type::Symbol(symbol) => check(&symbol),
+            _ => false,
+        }
+    }
+    fn symbol_type(&self, span: Span) -> Option<Type> {
+        let id = *self.resolution.references.get(&span)?;
+        let symbol = &self.resolution.symbols[id];
+        match symbol.kind {
+            SymbolKind::Variable | SymbolKind::Parameter | SymbolKind::Field => symbol
+                .var_type
+                .clone()
+                .or_else(|| self.inferred.get(&id).cloned()),
+            SymbolKind::External if symbol.name == "true" || symbol.name == "false" => {
+                Some(Type::Symbol("bool".to_string()))
+            }
+            _ => None,
+        }
+    }
+    /// type of `value.name` when the resolver didn't know the class of the value
+    fn field_type(&mut self, class: &str, name: &str, span: Span) -> Option<Type> {
+        let members = self
+            .resolution
+            .symbols
+            .iter()
+            .find(|symbol| symbol.kind == SymbolKind::Class && symbol.name == class)?
+            .members?;
+        if let Some(&id) = self.resolution.scopes[members].symbols.get(name) {
+            return self.resolution.symbols[id].var_type.clone();
+        }
+        let reported = self
+            .resolution
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.span == span);
+        if !reported {
+            self.error(span, format!("no field `{}` on this class", name));
+        }
+        return None;
+    }
+    /// `let x = value` takes the type of the value
+    fn infer_declaration(&mut self, span: Span, var_type: Option<Type>) {
+        if let (Some(id), Some(var_type)) = (self.resolution.references.get(&span), var_type) {
+            self.inferred.insert(*id, default_literals(var_type));
+        }
+    }
+}
+
+/// Result of `check_types`
+pub struct Typing {
+    pub diagnostics: Vec<Diagnostic>,
+    /// types of declarations without an annotation, literals get their default type
+    pub inferred: HashMap<SymbolId, Type>,
+}
+
+/// Infers the type of every expression and reports mismatched assignments, arguments, return values,
+/// conditions and operators
+pub fn check_types(
+    expressions: &[Expression],
+    resolution: &Resolution,
+    type_map: &TypeMap,
+) -> Typing {
+    let mut checker = TypeChecker {
+        resolution,
+        type_map,
+        inferred: HashMap::new(),
+        parameters: HashMap::new(),
+        returns: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+    collect_parameters(&mut checker, expressions);
+    check_block(&mut checker, expressions);
+
+    return Typing {
+        diagnostics: checker.diagnostics,
+        inferred: checker.inferred,
+    };
+}
+/// functions can be called before they are declared
+fn collect_parameters(checker: &mut TypeChecker, expressions: &[Expression]) {
+    for expression in expressions {
+        match expression {
+            Expression::Function {
+                span,
+                properties,
+                inside,
+                ..
+            } => {
+                let parameters = properties
+                    .iter()
+                    .filter_map(|property| match property {
+                        Expression::FunctionProperty { var_type, .. } => Some(var_type.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                if let Some(id) = checker.resolution.references.get(span) {
+                    checker.parameters.insert(*id, parameters);
+                }
+                collect_parameters(checker, inside);
+            }
+            Expression::If { inside, .. }
+            | Expression::Else { inside, .. }
+            | Expression::While { inside, .. }
+            | Expression::For { inside, .. } => collect_parameters(checker, inside),
+            _ => {}
+        }
+    }
+}
+fn check_block(checker: &mut TypeChecker, expressions: &[Expression]) {
+    for expression in expressions {
+        infer(checker, expression);
+    }
+}
+/// Type of the expression, None when it is not known or the expression is a statement
+fn infer(checker: &mut TypeChecker, expression: &Expression) -> Option<Type> {
+    match expression {
+        Expression::Number(_) => Some(Type::Symbol(INTEGER_LITERAL.to_string())),
+        Expression::Float(_) => Some(Type::Symbol(FLOAT_LITERAL.to_string())),
+        Expression::String(_) => Some(Type::Symbol("str".to_string())),
+        Expression::Interpolated(parts) => {
+            for value in interpolated_values(parts) {
+                infer(checker, value);
+            }
+            Some(Type::Symbol("str".to_string()))
+        }
+        Expression::Identifier { span, .. } => checker.symbol_type(*span),
+        Expression::Prefix { prefix, value } => check_prefix(checker, prefix, value),
+        Expression::Binary { l, operator, r } => check_binary(checker, l, operator, r),
+        Expression::Assignment {
+            target,
+            operator,
+            value,
+        } => {
+            check_assignment(checker, target, operator, value);
+            None
+        }
+        Expression::Grouping(inside) => infer(checker, inside),
+        Expression::Tuple(values) => {
+            let types: Vec<Option<Type>> =
+                values.iter().map(|value| infer(checker, value)).collect();
+            types
+                .into_iter()
+                .collect::<Option<Vec<Type>>>()
+                .map(Type::Tuple)
+        }
+        Expression::Borrow { value, mutable, .. } => {
+            infer(checker, value).map(|inner| Type::Reference {
+                inner: Box::new(inner),
+                mutable: *mutable,
+            })
+        }
+        Expression::ClassInstantiation {
+            name, properties, ..
+        } => {
+            check_class_instantiation(checker, properties);
+            Some(Type::Symbol(name.clone()))
+        }
+        Expression::ArrayInitialization { properties } => {
+            check_block(checker, properties);
+            None
+        }
+        Expression::Function { output, inside, .. } => {
+            checker.returns.push(output.clone());
+            check_block(checker, inside);
+            checker.returns.pop();
+            None
+        }
+        Expression::MemberExpr { member, name, span } => {
+            let member_type = infer(checker, member);
+            if let Some(field_type) = checker.symbol_type(*span) {
+                return Some(field_type);
+            }
+            // the resolver only binds fields of values with a known class
+            match member_type.map(|var_type| dereference(&var_type)) {
+                Some(Type::Symbol(class)) => checker.field_type(&class, name, *span),
+                _ => None,
+            }
+        }
+        Expression::StaticMember { member, .. } => {
+            infer(checker, member);
+            None
+        }
+        Expression::MacroCall { name, values } => {
+            let types: Vec<Option<Type>> =
+                values.iter().map(|value| infer(checker, value)).collect();
+            match (name.as_str(), types.into_iter().next()) {
+                ("vec", Some(Some(element))) => Some(Type::Generic {
+                    name: "Vec".to_string(),
+                    arguments: vec![element],
+                }),
+                _ => None,
+            }
+        }
+        Expression::Return { value, span } => {
+            check_return(checker, value, *span);
+            None
+        }
+        Expression::If {
+            condition,
+            inside,
+            span,
+        }
+        | Expression::While {
+            condition,
+            inside,
+            span,
+        } => {
+            check_condition(checker, condition, *span);
+            check_block(checker, inside);
+            None
+        }
+        Expression::Else {
+            condition,
+            inside,
+            span,
+        } => {
+            if let Some(condition) = condition {
+                check_condition(checker, condition, *span);
+            }
+            check_block(checker, inside);
+            None
+        }
+        Expression::For {
+            iterator_span,
+            iteration_target,
+            inside,
+            ..
+        } => {
+            let target = infer(checker, iteration_target);
+            let element = target.and_then(|target| element_type(&dereference(&target)));
+            checker.infer_declaration(*iterator_span, element);
+            check_block(checker, inside);
+            None
+        }
+        Expression::Range { from, to } => check_range(checker, from, to),
+        Expression::FunctionCall { left, values } => check_call(checker, left, values),
+        Expression::IndexArray { left, indexes } => check_index(checker, left, indexes),
+        Expression::Keyword(_)
+        | Expression::Attribute { .. }
+        | Expression::VariableDeclaration { .. }
+        | Expression::TupleDeclaration { .. }
+        | Expression::Class { .. }
+        | Expression::ClassProperty { .. }
+        | Expression::FunctionProperty { .. }
+        | Expression::Out { .. } => None,
+    }
+}
+/// `Vec::new()`, `HashMap::with_capacity(n)` ... -> the name of the collection
+fn empty_collection(value: &Expression) -> Option<&str> {
+    let Expression::FunctionCall { left, .. } = value else {
+        return None;
+    };
+    let Expression::StaticMember { member, name, .. } = &**left else {
+        return None;
+    };
+    match &**member {
+        Expression::Identifier {
+            name: collection, ..
+        } if (name == "new" || name == "with_capacity")
+            && handle_collection_name(collection).is_some() =>
+        {
+            Some(collection)
+        }
+        _ => None,
+    }
+}
+/// checks `value` against the type it is assigned to, `span` is used when the value has no span
+fn check_value(checker: &mut TypeChecker, expected: &Type, value: &Expression, span: Span) {
+    let span = value.span().unwrap_or(span);
+    match (expected, value) {
+        (
+            Type::Array {
+                left_type,
+                dimensions,
+            },
+            Expression::ArrayInitialization { properties },
+        ) => check_array_initialization(checker, left_type, dimensions + 1, properties, span),
+        _ => {
+            let found = infer(checker, value);
+            checker.expect_type(span, expected, found);
+        }
+    }
+}
+/// `{{0, 0}, {0, 0}}` for i32[,] has rows of i32 nested twice
+fn check_array_initialization(
+    checker: &mut TypeChecker,
+    left_type: &Type,
+    rank: usize,
+    properties: &[Expression],
+    span: Span,
+) {
+    let element_type = match rank {
+        1 => left_type.clone(),
+        _ => Type::Array {
+            left_type: Box::new(left_type.clone()),
+            dimensions: rank - 2,
+        },
+    };
+    for property in properties {
+        match property {
+            Expression::Keyword(_) => {}
+            Expression::ArrayInitialization { properties } if rank > 1 => {
+                check_array_initialization(checker, left_type, rank - 1, properties, span)
+            }
+            _ => check_value(checker, &element_type, property, span),
+        }
+    }
+}
+fn check_assignment(
+    checker: &mut TypeChecker,
+    target: &Expression,
+    operator: &Token,
+    value: &Expression,
+) {
+    match target {
+        Expression::VariableDeclaration {
+            var_type: Some(var_type),
+            ..
+        } => check_value(checker, var_type, value, operator.span()),
+        Expression::VariableDeclaration {
+            var_type: None,
+            name,
+            span,
+            ..
+        } => {
+            // the element types of `HashMap::new()` come from later uses that aren't followed
+            if let Some(collection) = empty_collection(value) {
+                checker.error(
+                    *span,
+                    format!(
+                        "type annotations needed for `{}`, declare it like `let {}: {}<...>`",
+                        name, name, collection
+                    ),
+                );
+            }
+            let found = infer(checker, value);
+            checker.infer_declaration(*span, found);
+        }
+        Expression::TupleDeclaration { var_type, bindings } => {
+            let found = match var_type {
+                Some(var_type) => {
+                    check_value(checker, var_type, value, operator.span());
+                    Some(var_type.clone())
+                }
+                None => infer(checker, value),
+            };
+            if let Some(Type::Tuple(types)) = found {
+                for (binding, binding_type) in bindings.iter().zip(types) {
+                    if let Expression::VariableDeclaration { span, .. } = binding {
+                        checker.infer_declaration(*span, Some(binding_type));
+                    }
+                }
+            }
+        }
+        _ => {
+            let target_type = infer(checker, target);
+            match (target_type, operator.kind) {
+                (Some(target_type), TokenKind::Assignment) => {
+                    check_value(checker, &target_type, value, operator.span())
+                }
+                // `text += 1` appends to the string like in c#
+                (Some(target_type), TokenKind::PlusEquals)
+                    if checker.is(&target_type, is_string) =>
+                {
+                    infer(checker, value);
+                }
+                (Some(target_type), _) => {
+                    let found = infer(checker, value);
+                    if !checker.is(&target_type, is_numeric) {
+                        checker.error(
+                            operator.span(),
+                            format!(
+                                "cannot apply `{}` to a value of type `{}`",
+                                operator.value, target_type
+                            ),
+                        );
+                        return;
+                    }
+                    checker.expect_type(operator.span(), &target_type, found);
+                }
+                (None, _) => {
+                    infer(checker, value);
+                }
+            }
+        }
+    }
+}
+fn check_class_instantiation(checker: &mut TypeChecker, properties: &[Expression]) {
+    for property in properties {
+        match property {
+            // `x = 10` sets the field x of the new instance
+            Expression::Assignment {
+                target,
+                operator,
+                value,
+            } => match &**target {
+                Expression::Identifier { span, .. } => match checker.symbol_type(*span) {
+                    Some(field_type) => check_value(checker, &field_type, value, operator.span()),
+                    None => {
+                        infer(checker, value);
+                    }
+                },
+                _ => {
+                    infer(checker, value);
+                }
+            },
+            other => {
+                infer(checker, other);
+            }
+        }
+    }
+}
+fn check_prefix(checker: &mut TypeChecker, prefix: &Token, value: &Expression) -> Option<Type> {
+    let found = infer(checker, value)?;
+    let valid = match prefix.kind {
+        TokenKind::Not => checker.is(&found, is_bool),
+        _ => checker.is(&found, is_numeric),
+    };
+    if !valid {
+        checker.error(
+            prefix.span(),
+            format!(
+                "cannot apply `{}` to a value of type `{}`",
+                prefix.value, found
+            ),
+        );
+        return None;
+    }
+    return Some(dereference(&found));
+}
+fn check_binary(
+    checker: &mut TypeChecker,
+    l: &Expression,
+    operator: &Token,
+    r: &Expression,
+) -> Option<Type> {
+    let left = infer(checker, l).map(|left| dereference(&left));
+    let right = infer(checker, r).map(|right| dereference(&right));
+    let bool_type = Type::Symbol("bool".to_string());
+
+    if matches!(operator.kind, TokenKind::And | TokenKind::Or) {
+        for (side, found) in [(l, left), (r, right)] {
+            checker.expect_type(side.span().unwrap_or(operator.span()), &bool_type, found);
+        }
+        return Some(bool_type);
+    }
+
+    let (Some(left), Some(right)) = (left, right) else {
+        // the result of a comparison is known even if the operands are not
+        return match operator.kind {
+            TokenKind::Plus | TokenKind::Minus | TokenKind::Star | TokenKind::Slash => None,
+            _ => Some(bool_type),
+        };
+    };
+    let (canonical_left, canonical_right) = (checker.canonical(&left), checker.canonical(&right));
+    let promoted = promote(&canonical_left, &canonical_right);
+    let result = match operator.kind {
+        // c# converts the other side of `+` to a string
+        TokenKind::Plus if is_string_type(&canonical_left) || is_string_type(&canonical_right) => {
+            Some(Type::Symbol("str".to_string()))
+        }
+        // the result keeps the source name of an operand when it has the same type
+        TokenKind::Plus | TokenKind::Minus | TokenKind::Star | TokenKind::Slash => {
+            promoted.map(|promoted| match Type::Symbol(promoted) {
+                promoted if promoted == canonical_left => left.clone(),
+                promoted if promoted == canonical_right => right.clone(),
+                promoted => promoted,
+            })
+        }
+        TokenKind::Equals | TokenKind::NotEquals => {
+            let comparable = promoted.is_some()
+                || assignable(&canonical_left, &canonical_right, false)
+                || assignable(&canonical_right, &canonical_left, false);
+            comparable.then_some(bool_type)
+        }
+        _ => promoted.map(|_| bool_type),
+    };
+    if result.is_none() {
+        checker.error(
+            operator.span(),
+            format!(
+                "cannot apply `{}` to `{}` and `{}`",
+                operator.value, left, right
+            ),
+        );
+    }
+    return result;
+}
+fn check_condition(checker: &mut TypeChecker, condition: &Expression, span: Span) {
+    let found = infer(checker, condition);
+    let span = condition.span().unwrap_or(span);
+    checker.expect_type(span, &Type::Symbol("bool".to_string()), found);
+}
+fn check_return(checker: &mut TypeChecker, value: &Expression, span: Span) {
+    // `return;` parses the semicolon as the value
+    let has_value = !matches!(value, Expression::Keyword(_));
+    match checker.returns.last().cloned() {
+        Some(Some(output)) if has_value => check_value(checker, &output, value, span),
+        Some(Some(output)) => checker.error(
+            span,
+            format!("expected a return value of type `{}`", output),
+        ),
+        Some(None) if has_value => {
+            infer(checker, value);
+            checker.error(
+                span,
+                "unexpected return value, the function has no return type".to_string(),
+            );
+        }
+        _ => {
+            infer(checker, value);
+        }
+    }
+}
+fn check_range(checker: &mut TypeChecker, from: &Expression, to: &Expression) -> Option<Type> {
+    let mut bounds = Vec::new();
+    for bound in [from, to] {
+        let Some(found) = infer(checker, bound) else {
+            continue;
+        };
+        let found = checker.canonical(&dereference(&found));
+        if !checker.is(&found, is_integer) {
+            checker.error(
+                bound.span().unwrap_or_default(),
+                format!("range bounds have to be integers, found `{}`", found),
+            );
+            return None;
+        }
+        bounds.push(found);
+    }
+
+    let element = match bounds.as_slice() {
+        [from, to] => promote(from, to).map(Type::Symbol),
+        [bound] => Some(bound.clone()),
+        _ => None,
+    }?;
+    return Some(Type::Generic {
+        name: "Range".to_string(),
+        arguments: vec![element],
+    });
+}
+fn check_call(checker: &mut TypeChecker, left: &Expression, values: &[Expression]) -> Option<Type> {
+    let found: Vec<Option<Type>> = values
+        .iter()
+        .map(|value| match value {
+            // out arguments are only used for c# functions
+            Expression::Out { .. } => None,
+            value => infer(checker, value),
+        })
+        .collect();
+
+    let (name, span) = match left {
+        Expression::Identifier { name, span } => (name, *span),
+        Expression::StaticMember { member, name, .. } => {
+            infer(checker, member);
+            return match (&**member, name.as_str()) {
+                (Expression::Identifier { name, .. }, "new" | "from") if name == "String" => {
+                    Some(Type::Symbol("String".to_string()))
+                }
+                _ => None,
+            };
+        }
+        other => {
+            infer(checker, other);
+            return None;
+        }
+    };
+    let id = *checker.resolution.references.get(&span)?;
+    let function = &checker.resolution.symbols[id];
+    if function.kind != SymbolKind::Function {
+        return None;
+    }
+    let output = function.var_type.clone();
+    let parameters = checker.parameters.get(&id).cloned().unwrap_or_default();
+
+    if parameters.len() != values.len() {
+        checker.error(
+            span,
+            format!(
+                "function `{}` takes {} arguments but {} were given",
+                name,
+                parameters.len(),
+                values.len()
+            ),
+        );
+        return output;
+    }
+    for ((parameter, value), found) in parameters.iter().zip(values).zip(found) {
+        checker.expect_type(value.span().unwrap_or(span), parameter, found);
+    }
+    return output;
+}
+fn check_index(
+    checker: &mut TypeChecker,
+    left: &Expression,
+    indexes: &[Expression],
+) -> Option<Type> {
+    let collection = infer(checker, left).map(|collection| dereference(&collection));
+    let index_types: Vec<Option<Type>> =
+        indexes.iter().map(|index| infer(checker, index)).collect();
+    let span = left.span().unwrap_or_default();
+    let collection = collection?;
+
+    let (element, key) = match &collection {
+        Type::Array {
+            left_type,
+            dimensions,
+        } => {
+            if indexes.len() != dimensions + 1 {
+                checker.error(
+                    span,
+                    format!(
+                        "`{}` needs {} indexes but {} were given",
+                        collection,
+                        dimensions + 1,
+                        indexes.len()
+                    ),
+                );
+            }
+            (*left_type.clone(), None)
+        }
+        Type::Generic { name, arguments } => match (name.as_str(), arguments.as_slice()) {
+            ("Vec" | "VecDeque", [element]) => (element.clone(), None),
+            ("HashMap", [key, value]) => (value.clone(), Some(key.clone())),
+            _ => return None,
+        },
+        _ if checker.is(&collection, is_string) => (Type::Symbol("char".to_string()), None),
+        Type::Symbol(_) => {
+            checker.error(
+                span,
+                format!("cannot index into a value of type `{}`", collection),
+            );
+            return None;
+        }
+        _ => return None,
+    };
+
+    for (index, found) in indexes.iter().zip(index_types) {
+        let index_span = index.span().unwrap_or(span);
+        match (&key, found) {
+            (Some(key), found) => checker.expect_type(index_span, key, found),
+            (None, Some(found)) if !checker.is(&found, is_integer) => checker.error(
+                index_span,
+                format!("indexes have to be integers, found `{}`", found),
+            ),
+            _ => {}
+        }
+    }
+    return Some(element);
+}
+
+/// type of the items of a for loop over `target`
+fn element_type(target: &Type) -> Option<Type> {
+    match target {
+        Type::Array { left_type, .. } => Some(*left_type.clone()),
+        Type::Generic { name, arguments } => match (name.as_str(), arguments.as_slice()) {
+            ("Range" | "Vec" | "VecDeque" | "HashSet", [element]) => Some(element.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+/// operators and indexing see through references
+fn dereference(var_type: &Type) -> Type {
+    match var_type {
+        Type::Reference { inner, .. } => dereference(inner),
+        other => other.clone(),
+    }
+}
+/// variables declared with a literal get the type c# gives to `var`
+fn default_literals(var_type: Type) -> Type {
+    match var_type {
+        Type::Symbol(symbol) if symbol == INTEGER_LITERAL => Type::Symbol("int".to_string()),
+        Type::Symbol(symbol) if symbol == FLOAT_LITERAL => Type::Symbol("double".to_string()),
+        Type::Array {
+            left_type,
+            dimensions,
+        } => Type::Array {
+            left_type: Box::new(default_literals(*left_type)),
+            dimensions,
+        },
+        Type::Generic { name, arguments } => Type::Generic {
+            name,
+            arguments: arguments.into_iter().map(default_literals).collect(),
+        },
+        Type::Tuple(types) => Type::Tuple(types.into_iter().map(default_literals).collect()),
+        other => other,
+    }
+}
+
+/// Both types have to be canonical, generic arguments and references have to match `exactly`
+fn assignable(expected: &Type, found: &Type, exactly: bool) -> bool {
+    match (expected, found) {
+        (Type::Symbol(expected), Type::Symbol(found)) => {
+            expected == found
+                || (found == INTEGER_LITERAL && is_numeric(expected))
+                || (found == FLOAT_LITERAL && is_float(expected))
+                || (!exactly && widens(found, expected))
+        }
+        (
+            Type::Array {
+                left_type: expected,
+                dimensions: expected_dimensions,
+            },
+            Type::Array {
+                left_type: found,
+                dimensions: found_dimensions,
+            },
+        ) => expected_dimensions == found_dimensions && assignable(expected, found, true),
+        (
+            Type::Generic {
+                name: expected_name,
+                arguments: expected,
+            },
+            Type::Generic {
+                name: found_name,
+                arguments: found,
+            },
+        ) => expected_name == found_name && all_assignable(expected, found, true),
+        (Type::Tuple(expected), Type::Tuple(found)) => all_assignable(expected, found, exactly),
+        (
+            Type::Reference {
+                inner: expected,
+                mutable: expected_mutable,
+            },
+            Type::Reference {
+                inner: found,
+                mutable: found_mutable,
+            },
+        ) => (*found_mutable || !expected_mutable) && assignable(expected, found, true),
+        _ => false,
+    }
+}
+fn all_assignable(expected: &[Type], found: &[Type], exactly: bool) -> bool {
+    return expected.len() == found.len()
+        && expected
+            .iter()
+            .zip(found)
+            .all(|(expected, found)| assignable(expected, found, exactly));
+}
+
+fn is_integer(symbol: &str) -> bool {
+    return symbol == INTEGER_LITERAL || INTEGERS.contains(&symbol);
+}
+fn is_float(symbol: &str) -> bool {
+    return symbol == FLOAT_LITERAL || FLOATS.contains(&symbol);
+}
+fn is_numeric(symbol: &str) -> bool {
+    return is_integer(symbol) || is_float(symbol);
+}
+fn is_bool(symbol: &str) -> bool {
+    return symbol == "bool";
+}
+fn is_string(symbol: &str) -> bool {
+    return symbol == "string" || symbol == "str" || symbol == "String";
+}
+fn is_string_type(var_type: &Type) -> bool {
+    return matches!(var_type, Type::Symbol(symbol) if is_string(symbol));
+}
+
+/// c# implicit numeric conversions
+fn widens(from: &str, to: &str) -> bool {
+    let targets: &[&str] = match from {
+        "sbyte" => &["short", "int", "long", "float", "double", "decimal"],
+        "byte" => &[
+            "short", "ushort", "int", "uint", "long", "ulong", "float", "double", "decimal",
+        ],
+        "short" => &["int", "long", "float", "double", "decimal"],
+        "ushort" => &["int", "uint", "long", "ulong", "float", "double", "decimal"],
+        "int" => &["long", "float", "double", "decimal"],
+        "uint" => &["long", "ulong", "float", "double", "decimal"],
+        "long" | "ulong" => &["float", "double", "decimal"],
+        "char" => &[
+            "ushort", "int", "uint", "long", "ulong", "float", "double", "decimal",
+        ],
+        "float" => &["double"],
+        _ => &[],
+    };
+    return targets.contains(&to);
+}
+/// Result type of an arithmetic operator, follows c# binary numeric promotion
+fn promote(left: &Type, right: &Type) -> Option<String> {
+    let (Type::Symbol(left), Type::Symbol(right)) = (left, right) else {
+        return None;
+    };
+    let (left, right) = (left.as_str(), right.as_str());
+    if !is_numeric(left) || !is_numeric(right) {
+        return None;
+    }
+
+    let result = match (left, right) {
+        (INTEGER_LITERAL, INTEGER_LITERAL) => INTEGER_LITERAL,
+        (FLOAT_LITERAL, INTEGER_LITERAL | FLOAT_LITERAL) | (INTEGER_LITERAL, FLOAT_LITERAL) => {
+            FLOAT_LITERAL
+        }
+        // a literal takes the type of the other side
+        (INTEGER_LITERAL, other) | (other, INTEGER_LITERAL) => {
+            let other = Type::Symbol(other.to_string());
+            return promote(&other, &other);
+        }
+        (FLOAT_LITERAL, other) | (other, FLOAT_LITERAL) if is_float(other) => other,
+        (FLOAT_LITERAL, _) | (_, FLOAT_LITERAL) => "double",
+        ("decimal", "float" | "double") | ("float" | "double", "decimal") => return None,
+        ("decimal", _) | (_, "decimal") => "decimal",
+        ("double", _) | (_, "double") => "double",
+        ("float", _) | (_, "float") => "float",
+        ("ulong", other) | (other, "ulong") if SIGNED_INTEGERS.contains(&other) => return None,
+        ("ulong", _) | (_, "ulong") => "ulong",
+        ("long", _) | (_, "long") => "long",
+        ("uint", other) | (other, "uint") if SIGNED_INTEGERS.contains(&other) => "long",
+        ("uint", _) | (_, "uint") => "uint",
+        _ if PROMOTED_TO_INT.contains(&left) && PROMOTED_TO_INT.contains(&right) => "int",
+        _ if left == right => left,
+        _ => return None,
+    };
+    return Some(result.to_string());
+}
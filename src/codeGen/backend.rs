@@ -0,0 +1,472 @@
+use std::path::Path;
+
+use crate::{
+    c::C,
+    config::Config,
+    csharp::CSharp,
+    gdscript::GDScript,
+    ir::{Callee, Class, Expr, Function, Interpolation, Statement},
+    lua::Lua,
+    naming::pascal_case,
+    python::Python,
+    reserved::ReservedWords,
+    tokens::TokenKind,
+    types::Type,
+    typescript::TypeScript,
+    wat::Wat,
+    writer::Indent,
+};
+
+/// c# and rust integer types, the targets without fixed widths use one type for all of them
+pub const INTEGERS: &[&str] = &[
+    "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    "sbyte", "short", "int", "long", "byte", "ushort", "uint", "ulong", "nint", "nuint",
+];
+pub const UNSIGNED: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "usize", "byte", "ushort", "uint", "ulong", "nuint",
+];
+pub const FLOATS: &[&str] = &["f32", "f64", "float", "double", "decimal"];
+
+/// Language the program is generated in, picked with `--target <name>`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    CSharp,
+    TypeScript,
+    Python,
+    C,
+    Lua,
+    GDScript,
+    Wat,
+}
+impl Target {
+    pub fn from_name(name: &str) -> Option<Target> {
+        let target = match name {
+            "csharp" | "cs" => Target::CSharp,
+            "typescript" | "ts" => Target::TypeScript,
+            "python" | "py" => Target::Python,
+            "c" | "c99" => Target::C,
+            "lua" => Target::Lua,
+            "gdscript" | "gd" => Target::GDScript,
+            "wat" | "wasm" => Target::Wat,
+            _ => return None,
+        };
+        return Some(target);
+    }
+    /// extension of the main output file
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Target::CSharp => "cs",
+            Target::TypeScript => "ts",
+            Target::Python => "py",
+            Target::C => "c",
+            Target::Lua => "lua",
+            Target::GDScript => "gd",
+            Target::Wat => "wat",
+        }
+    }
+}
+
+pub fn create_backend(config: &Config) -> Box<dyn Backend> {
+    // gdscript is indented with tabs by convention, the others with 4 spaces
+    let spaces = config.code_style(Indent::Spaces(4));
+    match config.target {
+        Target::CSharp => {
+            let mut backend = CSharp::new(config.type_map.clone());
+            backend.emit_const = config.emit_const;
+            backend.namespace = config.namespace.clone();
+            backend.class_name = config.class_name.clone();
+            backend.style = spaces;
+            return Box::new(backend);
+        }
+        Target::TypeScript => {
+            let mut backend = TypeScript::new();
+            backend.style = spaces;
+            return Box::new(backend);
+        }
+        Target::Python => {
+            let mut backend = Python::new();
+            backend.style = spaces;
+            return Box::new(backend);
+        }
+        Target::C => {
+            // the source file includes the header that is written next to it
+            let header = Path::new(&config.output_path).with_extension("h");
+            let header = header.file_name().and_then(|name| name.to_str());
+            let mut backend = C::new(header.unwrap_or("Output.h").to_string());
+            backend.style = spaces;
+            return Box::new(backend);
+        }
+        Target::Lua => {
+            let mut backend = Lua::new();
+            backend.style = spaces;
+            return Box::new(backend);
+        }
+        Target::GDScript => {
+            // the script is named after the output file, tic_tac_toe.gd -> TicTacToe
+            let stem = Path::new(&config.output_path).file_stem();
+            let stem = stem.and_then(|stem| stem.to_str()).unwrap_or("Output");
+            let mut name =
+                pascal_case(&stem.replace(|char: char| !char.is_ascii_alphanumeric(), "_"));
+            if name.is_empty() || name.starts_with(|char: char| char.is_ascii_digit()) {
+                name.insert_str(0, "Script");
+            }
+            let mut backend = GDScript::new(name);
+            backend.style = config.code_style(Indent::Tab);
+            return Box::new(backend);
+        }
+        Target::Wat => {
+            let mut backend = Wat::new();
+            backend.style = spaces;
+            return Box::new(backend);
+        }
+    }
+}
+
+/// Generated file, written next to the output path with its extension
+pub struct OutputFile {
+    pub extension: &'static str,
+    pub content: String,
+}
+
+/// Code generator of a target language.
+/// The driver functions `emit_block`, `emit_statement` and `emit_expr` walk the ir and call
+/// the hook of every node, the hooks call them back for the nodes inside
+pub trait Backend {
+    /// extension of the generated file like `cs`
+    fn extension(&self) -> &'static str;
+    fn reserved(&self) -> &ReservedWords;
+    /// target spelling of a source type
+    fn map_type(&self, var_type: &Type) -> String;
+
+    /// files the program is written to, every statement goes to one file by default
+    fn generate(&mut self, program: &[Statement]) -> Vec<OutputFile> {
+        let content = emit_block(self, program);
+        return vec![OutputFile {
+            extension: self.extension(),
+            content,
+        }];
+    }
+    /// `base` -> `@base` for names from the source that are keywords of the target
+    fn name(&self, name: &str) -> String {
+        return self.reserved().escape(name);
+    }
+
+    // statements, every hook returns whole lines
+    fn emit_let(
+        &mut self,
+        name: &str,
+        var_type: Option<&Type>,
+        inferred: Option<&Type>,
+        mutable: bool,
+        constant: bool,
+        value: Option<&Expr>,
+    ) -> String;
+    fn emit_let_tuple(
+        &mut self,
+        names: &[String],
+        types: Option<&[Type]>,
+        value: Option<&Expr>,
+    ) -> String;
+    fn emit_assign(&mut self, target: &Expr, operator: TokenKind, value: &Expr) -> String;
+    fn emit_expression_statement(&mut self, value: &Expr) -> String;
+    fn emit_if(
+        &mut self,
+        condition: &Expr,
+        then: &[Statement],
+        otherwise: Option<&[Statement]>,
+    ) -> String;
+    fn emit_while(&mut self, condition: &Expr, body: &[Statement]) -> String;
+    fn emit_for_range(
+        &mut self,
+        variable: &str,
+        from: &Expr,
+        to: &Expr,
+        body: &[Statement],
+    ) -> String;
+    fn emit_for_each(&mut self, variable: &str, iterable: &Expr, body: &[Statement]) -> String;
+    fn emit_scope(&mut self, body: &[Statement]) -> String;
+    fn emit_return(&mut self, value: Option<&Expr>) -> String;
+    fn emit_break(&mut self) -> String;
+    fn emit_continue(&mut self) -> String;
+    fn emit_function(&mut self, function: &Function) -> String;
+    fn emit_class(&mut self, class: &Class) -> String;
+
+    // values
+    fn emit_number(&mut self, value: i128) -> String {
+        return value.to_string();
+    }
+    /// `2.0` keeps its fraction, so it isn't read as an int
+    fn emit_float(&mut self, value: f32) -> String {
+        return format!("{:?}", value);
+    }
+    fn emit_string(&mut self, text: &str) -> String;
+    /// $"[{x}]", the text keeps the escapes it was written with
+    fn emit_interpolated(&mut self, parts: &[Interpolation]) -> String;
+    fn emit_bool(&mut self, value: bool) -> String;
+    fn emit_variable(&mut self, name: &str, _var_type: Option<&Type>) -> String {
+        return self.name(name);
+    }
+    fn emit_external(&mut self, name: &str) -> String {
+        return name.to_string();
+    }
+    fn emit_unary(&mut self, operator: TokenKind, value: &Expr) -> String;
+    fn emit_binary(&mut self, l: &Expr, operator: TokenKind, r: &Expr) -> String;
+    fn emit_tuple(&mut self, values: &[Expr]) -> String;
+    /// the type of the value is not always known, a field named like a keyword is always declared escaped
+    fn emit_field(&mut self, value: &Expr, name: &str) -> String {
+        return format!("{}.{}", emit_expr(self, value), self.name(name));
+    }
+    fn emit_static_field(&mut self, value: &Expr, name: &str) -> String;
+    fn emit_index(&mut self, value: &Expr, indexes: &[Expr]) -> String;
+    fn emit_call(&mut self, callee: &Callee, arguments: &[Expr]) -> String;
+    fn emit_new(&mut self, class: &str, fields: &[(String, Expr)]) -> String;
+    fn emit_array(&mut self, values: &[Expr]) -> String;
+    fn emit_list(&mut self, element_type: Option<&Type>, values: &[Expr]) -> String;
+    fn emit_new_collection(&mut self, collection: &str, var_type: Option<&Type>) -> String;
+    fn emit_out(&mut self, name: &str, var_type: Option<&Type>) -> String;
+    fn emit_ref(&mut self, value: &Expr, mutable: bool) -> String;
+}
+
+pub fn emit_block<B: Backend + ?Sized>(backend: &mut B, statements: &[Statement]) -> String {
+    let mut output = String::new();
+    for statement in statements {
+        output += &emit_statement(backend, statement);
+    }
+    return output;
+}
+pub fn emit_statement<B: Backend + ?Sized>(backend: &mut B, statement: &Statement) -> String {
+    match statement {
+        Statement::Let {
+            name,
+            var_type,
+            inferred,
+            mutable,
+            constant,
+            value,
+        } => backend.emit_let(
+            name,
+            var_type.as_ref(),
+            inferred.as_ref(),
+            *mutable,
+            *constant,
+            value.as_ref(),
+        ),
+        Statement::LetTuple {
+            names,
+            types,
+            value,
+            ..
+        } => backend.emit_let_tuple(names, types.as_deref(), value.as_ref()),
+        Statement::Assign {
+            target,
+            operator,
+            value,
+        } => backend.emit_assign(target, *operator, value),
+        Statement::Expression(value) => backend.emit_expression_statement(value),
+        Statement::If {
+            condition,
+            then,
+            otherwise,
+        } => backend.emit_if(condition, then, otherwise.as_deref()),
+        Statement::While { condition, body } => backend.emit_while(condition, body),
+        Statement::ForRange {
+            variable,
+            from,
+            to,
+            body,
+        } => backend.emit_for_range(variable, from, to, body),
+        Statement::ForEach {
+            variable,
+            iterable,
+            body,
+        } => backend.emit_for_each(variable, iterable, body),
+        Statement::Block(body) => backend.emit_scope(body),
+        Statement::Return(value) => backend.emit_return(value.as_ref()),
+        Statement::Break => backend.emit_break(),
+        Statement::Continue => backend.emit_continue(),
+        Statement::Function(function) => backend.emit_function(function),
+        Statement::Class(class) => backend.emit_class(class),
+    }
+}
+pub fn emit_expr<B: Backend + ?Sized>(backend: &mut B, expression: &Expr) -> String {
+    match expression {
+        Expr::Number(value) => backend.emit_number(*value),
+        Expr::Float(value) => backend.emit_float(*value),
+        Expr::String(text) => backend.emit_string(text),
+        Expr::Interpolated(parts) => backend.emit_interpolated(parts),
+        Expr::Bool(value) => backend.emit_bool(*value),
+        Expr::Variable { name, var_type } => backend.emit_variable(name, var_type.as_ref()),
+        Expr::External(name) => backend.emit_external(name),
+        Expr::Unary { operator, value } => backend.emit_unary(*operator, value),
+        Expr::Binary { l, operator, r } => backend.emit_binary(l, *operator, r),
+        Expr::Tuple(values) => backend.emit_tuple(values),
+        Expr::Field { value, name } => backend.emit_field(value, name),
+        Expr::StaticField { value, name } => backend.emit_static_field(value, name),
+        Expr::Index { value, indexes } => backend.emit_index(value, indexes),
+        Expr::Call { callee, arguments } => backend.emit_call(callee, arguments),
+        Expr::New { class, fields } => backend.emit_new(class, fields),
+        Expr::Array(values) => backend.emit_array(values),
+        Expr::List {
+            element_type,
+            values,
+        } => backend.emit_list(element_type.as_ref(), values),
+        Expr::NewCollection {
+            collection,
+            var_type,
+        } => backend.emit_new_collection(collection, var_type.as_ref()),
+        Expr::Out { name, var_type } => backend.emit_out(name, var_type.as_ref()),
+        Expr::Ref { value, mutable } => backend.emit_ref(value, *mutable),
+    }
+}
+/// `a, b, c`
+pub fn emit_values<B: Backend + ?Sized>(backend: &mut B, values: &[Expr]) -> String {
+    let values: Vec<String> = values
+        .iter()
+        .map(|value| emit_expr(backend, value))
+        .collect();
+    return values.join(", ");
+}
+
+/// Call with `out` arguments in a target that returns the out values together with the result
+pub struct OutCall {
+    /// the returned values are stored in it and the call is replaced by `temporary[0]`
+    pub temporary: String,
+    /// the call without its out arguments
+    pub call: Expr,
+    /// out arguments, the value of the n-th one is `temporary[n + 1]`,
+    /// arguments with a type declare a new variable
+    pub outs: Vec<(String, Option<Type>)>,
+}
+/// `a && f(out x)` only calls f when `a` is true, the right side has to be assigned inside of an
+/// `if` before its calls can be hoisted: `let __and1 = a; if __and1 { __and1 = f(out x); }`
+pub fn split_short_circuits(value: &mut Expr, counter: &mut usize) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    let Expr::Binary {
+        l,
+        operator: operator @ (TokenKind::And | TokenKind::Or),
+        r,
+    } = value
+    else {
+        for child in value.children_mut() {
+            statements.extend(split_short_circuits(child, counter));
+        }
+        return statements;
+    };
+    statements.extend(split_short_circuits(l, counter));
+    if !has_out_call(r) {
+        statements.extend(split_short_circuits(r, counter));
+        return statements;
+    }
+
+    *counter += 1;
+    let prefix = match operator {
+        TokenKind::And => "__and",
+        _ => "__or",
+    };
+    let temporary = format!("{}{}", prefix, counter);
+    let bool_type = Some(Type::Symbol("bool".to_string()));
+    // the variables the out arguments declare are still used after the `if`
+    let mut right = std::mem::replace(r.as_mut(), Expr::Bool(false));
+    for (name, var_type) in take_declared_outs(&mut right) {
+        statements.push(Statement::Let {
+            name,
+            var_type: Some(var_type.clone()),
+            inferred: Some(var_type),
+            mutable: true,
+            constant: false,
+            value: None,
+        });
+    }
+    statements.push(Statement::Let {
+        name: temporary.clone(),
+        var_type: bool_type.clone(),
+        inferred: bool_type.clone(),
+        mutable: true,
+        constant: false,
+        value: Some(std::mem::replace(l.as_mut(), Expr::Bool(false))),
+    });
+    let variable = Expr::Variable {
+        name: temporary,
+        var_type: bool_type,
+    };
+    // `a || f(out x)` only calls f when `a` is false
+    let condition = match operator {
+        TokenKind::And => variable.clone(),
+        _ => Expr::Unary {
+            operator: TokenKind::Not,
+            value: Box::new(variable.clone()),
+        },
+    };
+    statements.push(Statement::If {
+        condition,
+        then: vec![Statement::Assign {
+            target: variable.clone(),
+            operator: TokenKind::Assignment,
+            value: right,
+        }],
+        otherwise: None,
+    });
+    *value = variable;
+    return statements;
+}
+fn has_out_call(value: &Expr) -> bool {
+    if let Expr::Out { .. } = value {
+        return true;
+    }
+    return value.children().into_iter().any(has_out_call);
+}
+/// `out i32 x` -> `out x`, the declared names and types
+fn take_declared_outs(value: &mut Expr) -> Vec<(String, Type)> {
+    if let Expr::Out { name, var_type } = value {
+        return match var_type.take() {
+            Some(var_type) => vec![(name.clone(), var_type)],
+            None => Vec::new(),
+        };
+    }
+    let mut declared = Vec::new();
+    for child in value.children_mut() {
+        declared.extend(take_declared_outs(child));
+    }
+    return declared;
+}
+/// Takes the calls with `out` arguments out of `value`, the inner calls come first
+pub fn hoist_out_calls(value: &mut Expr, counter: &mut usize) -> Vec<OutCall> {
+    let mut calls = Vec::new();
+    for child in value.children_mut() {
+        calls.extend(hoist_out_calls(child, counter));
+    }
+    let Expr::Call { callee, arguments } = value else {
+        return calls;
+    };
+    if !arguments
+        .iter()
+        .any(|argument| matches!(argument, Expr::Out { .. }))
+    {
+        return calls;
+    }
+
+    let mut outs = Vec::new();
+    let mut values = Vec::new();
+    for argument in arguments.drain(..) {
+        match argument {
+            Expr::Out { name, var_type } => outs.push((name, var_type)),
+            argument => values.push(argument),
+        }
+    }
+    *counter += 1;
+    let temporary = format!("__out{}", counter);
+    let call = Expr::Call {
+        callee: callee.clone(),
+        arguments: values,
+    };
+    *value = Expr::Index {
+        value: Box::new(Expr::External(temporary.clone())),
+        indexes: vec![Expr::Number(0)],
+    };
+    calls.push(OutCall {
+        temporary,
+        call,
+        outs,
+    });
+    return calls;
+}
@@ -0,0 +1,1107 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{
+    backend::{emit_block, emit_expr, emit_statement, emit_values, Backend, OutputFile},
+    ir::{Callee, Class, Expr, Field, Function, Interpolation, Statement},
+    reserved::ReservedWords,
+    tokens::TokenKind,
+    types::Type,
+    writer::{CodeStyle, CodeWriter},
+};
+
+/// helper functions of the generated code, only the used ones are emitted.
+/// A tab at the start of a line is one level of indentation
+fn runtime(name: &str) -> String {
+    match name {
+        "rt_str_format" => "static const char* rt_str_format(const char* format, ...) {
+	va_list args;
+	va_start(args, format);
+	int length = vsnprintf(NULL, 0, format, args);
+	va_end(args);
+	char* text = malloc(length + 1);
+	va_start(args, format);
+	vsnprintf(text, length + 1, format, args);
+	va_end(args);
+	return text;
+}
+"
+        .to_string(),
+        "rt_read_line" => "static const char* rt_read_line(void) {
+	static char buffer[256];
+	if (fgets(buffer, sizeof(buffer), stdin) == NULL) {
+		return \"\";
+	}
+	buffer[strcspn(buffer, \"\\n\")] = '\\0';
+	char* line = malloc(strlen(buffer) + 1);
+	strcpy(line, buffer);
+	return line;
+}
+"
+        .to_string(),
+        // rt_try_parse_uint32_t ...
+        name => {
+            let c_type = name
+                .strip_prefix("rt_try_parse_")
+                .unwrap_or_else(|| panic!("runtime function: {} doesn't exist", name));
+            // int32_t -> INT32_MIN, size_t -> SIZE_MAX
+            let limit = c_type.trim_end_matches("_t").to_uppercase();
+            let (parsed, check) = if c_type.starts_with('u') || c_type == "size_t" {
+                (
+                    "unsigned long long parsed = strtoull(text, &end, 10);".to_string(),
+                    format!("strchr(text, '-') != NULL || parsed > {}_MAX", limit),
+                )
+            } else {
+                (
+                    "long long parsed = strtoll(text, &end, 10);".to_string(),
+                    format!("parsed < {}_MIN || parsed > {}_MAX", limit, limit),
+                )
+            };
+            format!(
+                "static bool {}(const char* text, {}* value) {{
+	char* end;
+	errno = 0;
+	{}
+	if (end == text || *end != '\\0' || errno != 0 || {}) {{
+		return false;
+	}}
+	*value = ({})parsed;
+	return true;
+}}
+",
+                name, c_type, parsed, check, c_type
+            )
+        }
+    }
+}
+/// standard headers the runtime functions include
+fn runtime_includes(name: &str) -> &'static [&'static str] {
+    match name {
+        "rt_str_format" => &["stdarg.h", "stdio.h", "stdlib.h"],
+        "rt_read_line" => &["stdio.h", "stdlib.h", "string.h"],
+        _ => &["errno.h", "stdlib.h", "string.h"],
+    }
+}
+
+pub struct C {
+    pub reserved: ReservedWords,
+    /// name of the header the source file includes, like `Output.h`
+    header: String,
+    /// classes in the order of their fields, `new` calls pass every field to the constructor
+    classes: HashMap<String, Vec<Field>>,
+    /// return types of the functions
+    outputs: HashMap<String, Option<Type>>,
+    /// types of the variables the interpolated strings can read
+    variables: HashMap<String, Type>,
+    /// array parameters of the function being generated, their length is passed next to them
+    array_parameters: HashSet<String>,
+    /// standard headers of the source file
+    includes: BTreeSet<&'static str>,
+    /// runtime functions the generated code calls
+    used: BTreeSet<String>,
+    /// `return` outside of a function ends `main`
+    in_function: bool,
+    pub style: CodeStyle,
+}
+impl C {
+    pub fn new(header: String) -> C {
+        C {
+            reserved: ReservedWords::c(),
+            header,
+            classes: HashMap::new(),
+            outputs: HashMap::new(),
+            variables: HashMap::new(),
+            array_parameters: HashSet::new(),
+            includes: BTreeSet::new(),
+            used: BTreeSet::new(),
+            in_function: false,
+            style: CodeStyle::new(),
+        }
+    }
+    fn use_runtime(&mut self, name: &str) -> String {
+        self.includes.extend(runtime_includes(name));
+        self.used.insert(name.to_string());
+        return name.to_string();
+    }
+
+    /// `int32_t map[3][3]`, the sizes of arrays are taken from their initializer
+    fn declaration(&self, var_type: &Type, name: &str, value: Option<&Expr>) -> String {
+        let Type::Array { left_type, .. } = var_type else {
+            return format!("{} {}", self.map_type(var_type), name);
+        };
+        let sizes = value.map(array_sizes).unwrap_or_default();
+        if sizes.is_empty() {
+            panic!("array: {} needs an initializer to have a size in c", name);
+        }
+        let sizes: String = sizes.iter().map(|size| format!("[{}]", size)).collect();
+        return format!("{} {}{}", self.map_type(left_type), name, sizes);
+    }
+    /// strings are already `const char*`
+    fn const_prefix(&self, var_type: &Type, constant: bool) -> &'static str {
+        if !constant || matches!(var_type, Type::Symbol(symbol) if is_string_type(symbol)) {
+            return "";
+        }
+        return "const ";
+    }
+    /// declarations of the variables that `out` arguments inside of `value` declare
+    fn out_declarations(&mut self, value: &Expr) -> String {
+        let mut output = String::new();
+        for (name, var_type) in typed_outs(value) {
+            self.variables.insert(name.clone(), var_type.clone());
+            output += &format!(
+                "{};\n",
+                self.declaration(&var_type, &self.name(&name), None)
+            );
+        }
+        return output;
+    }
+    /// `%s` and the value that is printed with it
+    fn format_value(&mut self, value: &Expr) -> (String, String) {
+        let value_str = emit_expr(self, value);
+        let Some(var_type) = self.value_type(value) else {
+            panic!("type of the printed value: {} isn't known", value_str);
+        };
+        return self.format_typed(&var_type, value_str);
+    }
+    fn format_typed(&self, var_type: &Type, value_str: String) -> (String, String) {
+        let c_type = self.map_type(var_type);
+        match c_type.as_str() {
+            "const char*" => ("%s".to_string(), value_str),
+            // c# prints True and False
+            "bool" => (
+                "%s".to_string(),
+                format!("({} ? \"True\" : \"False\")", value_str),
+            ),
+            "float" | "double" => ("%g".to_string(), value_str),
+            c_type if c_type.starts_with('u') || c_type == "size_t" => (
+                "%llu".to_string(),
+                format!("(unsigned long long)({})", value_str),
+            ),
+            c_type if c_type.ends_with("_t") => {
+                ("%lld".to_string(), format!("(long long)({})", value_str))
+            }
+            c_type => panic!("value of type: {} can't be printed", c_type),
+        }
+    }
+    /// printf format and its arguments of an interpolated string or a concatenation
+    fn format_parts(&mut self, value: &Expr) -> (String, Vec<String>) {
+        match value {
+            Expr::Interpolated(parts) => {
+                let mut format = String::new();
+                let mut arguments = Vec::new();
+                for part in parts {
+                    match part {
+                        Interpolation::Text(text) => format += &text.replace('%', "%%"),
+                        Interpolation::Value(value) => {
+                            let (spec, argument) = self.format_value(value);
+                            format += &spec;
+                            arguments.push(argument);
+                        }
+                    }
+                }
+                return (format, arguments);
+            }
+            Expr::String(text) => {
+                let inside = &text[1..text.len() - 1];
+                return (inside.replace('%', "%%"), Vec::new());
+            }
+            Expr::Binary {
+                l,
+                operator: TokenKind::Plus,
+                r,
+            } if self.is_string(value) => {
+                let (mut format, mut arguments) = self.format_parts(l);
+                let (right_format, right_arguments) = self.format_parts(r);
+                format += &right_format;
+                arguments.extend(right_arguments);
+                return (format, arguments);
+            }
+            value => {
+                let (spec, argument) = self.format_value(value);
+                return (spec, vec![argument]);
+            }
+        }
+    }
+    /// `rt_str_format("%s%lld", a, (long long)(b))` of a string built from parts
+    fn build_string(&mut self, value: &Expr) -> String {
+        let (format, arguments) = self.format_parts(value);
+        let name = self.use_runtime("rt_str_format");
+        let mut values = vec![format!("\"{}\"", format)];
+        values.extend(arguments);
+        return format!("{}({})", name, values.join(", "));
+    }
+    fn is_string(&self, value: &Expr) -> bool {
+        return self
+            .value_type(value)
+            .is_some_and(|var_type| self.map_type(&var_type) == "const char*");
+    }
+    /// type of a value as far as the backend can tell, printing and comparing depend on it
+    fn value_type(&self, value: &Expr) -> Option<Type> {
+        let symbol = |name: &str| Some(Type::Symbol(name.to_string()));
+        match value {
+            Expr::Number(_) => symbol("i32"),
+            Expr::Float(_) => symbol("f64"),
+            Expr::String(_) | Expr::Interpolated(_) => symbol("str"),
+            Expr::Bool(_) => symbol("bool"),
+            Expr::Variable { var_type, .. } => match var_type.as_ref()? {
+                Type::Reference { inner, .. } => Some(inner.as_ref().clone()),
+                var_type => Some(var_type.clone()),
+            },
+            Expr::Field { value, name } => {
+                let Some(Type::Symbol(class)) = self.value_type(value) else {
+                    return None;
+                };
+                let field = self.classes.get(&class)?.iter().find(|f| &f.name == name)?;
+                return Some(field.var_type.clone());
+            }
+            Expr::Index { value, indexes } => {
+                let Some(Type::Array {
+                    left_type,
+                    dimensions,
+                }) = self.value_type(value)
+                else {
+                    return None;
+                };
+                if indexes.len() > dimensions {
+                    return Some(*left_type);
+                }
+                return Some(Type::Array {
+                    left_type,
+                    dimensions: dimensions - indexes.len(),
+                });
+            }
+            Expr::Unary {
+                operator: TokenKind::Not,
+                ..
+            } => symbol("bool"),
+            Expr::Unary { value, .. } => self.value_type(value),
+            Expr::Binary { l, operator, r } => match operator {
+                TokenKind::Plus
+                | TokenKind::Minus
+                | TokenKind::Star
+                | TokenKind::Slash
+                | TokenKind::Percent => {
+                    let left = self.value_type(l);
+                    let right = self.value_type(r);
+                    let is_str = |var_type: &Option<Type>| matches!(var_type, Some(Type::Symbol(symbol)) if is_string_type(symbol));
+                    if is_str(&left) || is_str(&right) {
+                        return symbol("str");
+                    }
+                    return left.or(right);
+                }
+                _ => symbol("bool"),
+            },
+            Expr::Call { callee, .. } => match callee {
+                Callee::Function(name) => self.outputs.get(name)?.clone(),
+                Callee::Method { receiver, name, .. } => match (receiver.as_ref(), name.as_str()) {
+                    (Expr::External(console), "ReadLine") if console == "Console" => symbol("str"),
+                    (Expr::External(number), "Parse") => symbol(number),
+                    (_, "TryParse") => symbol("bool"),
+                    (_, "len") => symbol("usize"),
+                    (_, "is_empty" | "contains") => symbol("bool"),
+                    (_, "to_string") => symbol("str"),
+                    _ => None,
+                },
+                _ => None,
+            },
+            Expr::New { class, .. } => symbol(class),
+            _ => None,
+        }
+    }
+    /// number of elements of a one-dimensional array, a parameter is only a pointer
+    fn array_length(&mut self, value: &Expr) -> String {
+        if let Expr::Variable { name, .. } = value {
+            if self.array_parameters.contains(name) {
+                return format!("{}_length", self.name(name));
+            }
+        }
+        let value_str = emit_expr(self, value);
+        return format!("(sizeof({}) / sizeof({}[0]))", value_str, value_str);
+    }
+    fn handle_function(&mut self, function: &Function) -> String {
+        let outer = self.variables.clone();
+        for parameter in &function.parameters {
+            self.variables
+                .insert(parameter.name.clone(), parameter.var_type.clone());
+            if let Type::Array { .. } = parameter.var_type {
+                self.array_parameters.insert(parameter.name.clone());
+            }
+        }
+        self.in_function = true;
+        let body_str = emit_block(self, &function.body);
+        self.in_function = false;
+        self.variables = outer;
+        self.array_parameters.clear();
+        let mut writer = CodeWriter::new(self.style);
+        writer.block(&self.signature(function), &body_str);
+        return writer.finish();
+    }
+    /// `int32_t name(int32_t a, const char* b)`
+    fn signature(&self, function: &Function) -> String {
+        let mut parameters = Vec::new();
+        for parameter in &function.parameters {
+            let name = self.name(&parameter.name);
+            parameters.push(match &parameter.var_type {
+                // arrays are passed as a pointer to the first element and their length
+                Type::Array {
+                    left_type,
+                    dimensions: 0,
+                } => format!(
+                    "{} {}[], size_t {}_length",
+                    self.map_type(left_type),
+                    name,
+                    name
+                ),
+                Type::Array { .. } => panic!(
+                    "multi-dimensional array parameter: {} needs a size in c",
+                    name
+                ),
+                var_type => self.declaration(var_type, &name, None),
+            });
+        }
+        let parameters = if parameters.is_empty() {
+            "void".to_string()
+        } else {
+            parameters.join(", ")
+        };
+        let output_str = match &function.output {
+            Some(var_type) => self.map_type(var_type),
+            None => "void".to_string(),
+        };
+        return format!(
+            "{} {}({})",
+            output_str,
+            self.name(&function.name),
+            parameters
+        );
+    }
+    /// `typedef struct Point {...} Point;` and the signature of its constructor
+    fn handle_struct(&self, class: &Class) -> (String, String) {
+        let name = self.name(&class.name);
+        let mut fields_str = String::new();
+        let mut parameters = Vec::new();
+        for field in &class.fields {
+            if matches!(field.var_type, Type::Array { .. }) {
+                panic!("array field: {} needs a size in c", field.name);
+            }
+            let declaration = self.declaration(&field.var_type, &self.name(&field.name), None);
+            fields_str += &format!("{};\n", declaration);
+            parameters.push(declaration);
+        }
+        let parameters = if parameters.is_empty() {
+            "void".to_string()
+        } else {
+            parameters.join(", ")
+        };
+        let mut writer = CodeWriter::new(self.style);
+        writer.open(&format!("typedef struct {}", name));
+        writer.lines(&fields_str);
+        writer.close(&format!(" {};", name));
+        let definition = writer.finish();
+        let constructor = format!("{} {}_new({})", name, name, parameters);
+        return (definition, constructor);
+    }
+    /// `Point Point_new(int32_t x, int32_t y) {...}`
+    fn handle_constructor(&self, class: &Class, signature: &str) -> String {
+        let name = self.name(&class.name);
+        let mut body_str = format!("{} self;\n", name);
+        for field in &class.fields {
+            let field_name = self.name(&field.name);
+            body_str += &format!("self.{} = {};\n", field_name, field_name);
+        }
+        body_str += "return self;\n";
+        let mut writer = CodeWriter::new(self.style);
+        writer.block(signature, &body_str);
+        return writer.finish();
+    }
+    /// a top level variable is a global, its value is set in `main` unless it is constant
+    fn handle_global(
+        &mut self,
+        name: &str,
+        var_type: &Type,
+        constant: bool,
+        value: Option<&Expr>,
+    ) -> (String, String) {
+        let c_name = self.name(name);
+        let declaration = self.declaration(var_type, &c_name, value);
+        let Some(value) = value.filter(|value| !is_literal(value)) else {
+            let const_str = self.const_prefix(var_type, constant);
+            let value_str = match value {
+                Some(value) => format!(" = {}", emit_expr(self, value)),
+                None => String::new(),
+            };
+            return (
+                format!("static {}{}{};\n", const_str, declaration, value_str),
+                String::new(),
+            );
+        };
+        let prefix = self.out_declarations(value);
+        let value_str = self.emit_value(value);
+        let init = match var_type {
+            // arrays can't be assigned, their elements are copied from a compound literal
+            Type::Array { .. } => {
+                self.includes.insert("string.h");
+                let literal_type = self.declaration(var_type, "", Some(value));
+                format!(
+                    "{}memcpy({}, ({}){}, sizeof({}));\n",
+                    prefix, c_name, literal_type, value_str, c_name
+                )
+            }
+            _ => format!("{}{} = {};\n", prefix, c_name, value_str),
+        };
+        return (format!("static {};\n", declaration), init);
+    }
+    /// strings built with `+` are formatted into a new string
+    fn emit_value(&mut self, value: &Expr) -> String {
+        match value {
+            Expr::Binary {
+                operator: TokenKind::Plus,
+                ..
+            } if self.is_string(value) => return self.build_string(value),
+            _ => return emit_expr(self, value),
+        }
+    }
+}
+
+impl Backend for C {
+    fn extension(&self) -> &'static str {
+        return "c";
+    }
+    fn reserved(&self) -> &ReservedWords {
+        return &self.reserved;
+    }
+    fn map_type(&self, var_type: &Type) -> String {
+        return handle_type(self, var_type);
+    }
+    /// the source file comes first, the header is written next to it
+    fn generate(&mut self, program: &[Statement]) -> Vec<OutputFile> {
+        for statement in program {
+            match statement {
+                Statement::Class(class) => {
+                    self.classes
+                        .insert(class.name.clone(), class.fields.clone());
+                }
+                Statement::Function(function) => {
+                    self.outputs
+                        .insert(function.name.clone(), function.output.clone());
+                }
+                Statement::Let {
+                    name,
+                    var_type,
+                    inferred,
+                    ..
+                } => {
+                    if let Some(var_type) = var_type.as_ref().or(inferred.as_ref()) {
+                        self.variables.insert(name.clone(), var_type.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut header_items = String::new();
+        let mut prototypes = String::new();
+        let mut definitions = String::new();
+        let mut globals = String::new();
+        let mut main_body = String::new();
+        for statement in program {
+            match statement {
+                Statement::Class(class) => {
+                    let (definition, constructor) = self.handle_struct(class);
+                    header_items += &format!("{}{};\n", definition, constructor);
+                    definitions += &self.handle_constructor(class, &constructor);
+                }
+                Statement::Function(function) => {
+                    // only public functions are part of the header
+                    if function.public {
+                        header_items += &format!("{};\n", self.signature(function));
+                        definitions += &self.handle_function(function);
+                    } else {
+                        prototypes += &format!("static {};\n", self.signature(function));
+                        definitions += &format!("static {}", self.handle_function(function));
+                    }
+                }
+                Statement::Let {
+                    name,
+                    var_type,
+                    inferred,
+                    constant,
+                    value,
+                    ..
+                } => {
+                    let Some(var_type) = var_type.as_ref().or(inferred.as_ref()) else {
+                        panic!("type of: {} isn't known", name);
+                    };
+                    let (global, init) =
+                        self.handle_global(name, var_type, *constant, value.as_ref());
+                    globals += &global;
+                    main_body += &init;
+                }
+                statement => main_body += &emit_statement(self, statement),
+            }
+        }
+
+        let guard: String = self
+            .header
+            .chars()
+            .map(|char| match char.is_ascii_alphanumeric() {
+                true => char.to_ascii_uppercase(),
+                false => '_',
+            })
+            .collect();
+        let header = format!(
+            "#ifndef {}\n#define {}\n#include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n{}#endif\n",
+            guard, guard, header_items
+        );
+
+        let mut writer = CodeWriter::new(self.style);
+        writer.line(&format!("#include \"{}\"", self.header));
+        for include in &self.includes {
+            writer.line(&format!("#include <{}>", include));
+        }
+        for name in &self.used {
+            writer.template(&runtime(name));
+        }
+        writer.lines(&prototypes);
+        writer.lines(&globals);
+        writer.lines(&definitions);
+        writer.block("int main(void)", &format!("{}return 0;\n", main_body));
+        let source = writer.finish();
+
+        return vec![
+            OutputFile {
+                extension: "c",
+                content: source,
+            },
+            OutputFile {
+                extension: "h",
+                content: header,
+            },
+        ];
+    }
+
+    fn emit_let(
+        &mut self,
+        name: &str,
+        var_type: Option<&Type>,
+        inferred: Option<&Type>,
+        _mutable: bool,
+        constant: bool,
+        value: Option<&Expr>,
+    ) -> String {
+        let Some(var_type) = var_type.or(inferred) else {
+            panic!("type of: {} isn't known", name);
+        };
+        self.variables.insert(name.to_string(), var_type.clone());
+        let declaration = self.declaration(var_type, &self.name(name), value);
+        let Some(value) = value else {
+            return format!("{};\n", declaration);
+        };
+        let prefix = self.out_declarations(value);
+        let const_str = self.const_prefix(var_type, constant);
+        return format!(
+            "{}{}{} = {};\n",
+            prefix,
+            const_str,
+            declaration,
+            self.emit_value(value)
+        );
+    }
+    // c doesn't have tuples, `let (a, b) = (1, 2);` declares every variable on its own
+    fn emit_let_tuple(
+        &mut self,
+        names: &[String],
+        types: Option<&[Type]>,
+        value: Option<&Expr>,
+    ) -> String {
+        let Some(Expr::Tuple(values)) = value else {
+            panic!(
+                "tuple: ({}) isn't supported by the c backend",
+                names.join(", ")
+            );
+        };
+        let mut output = String::new();
+        for (i, (name, value)) in names.iter().zip(values).enumerate() {
+            let var_type = types
+                .map(|types| types[i].clone())
+                .or_else(|| self.value_type(value));
+            output += &self.emit_let(name, var_type.as_ref(), None, true, false, Some(value));
+        }
+        return output;
+    }
+    fn emit_assign(&mut self, target: &Expr, operator: TokenKind, value: &Expr) -> String {
+        let prefix = self.out_declarations(value);
+        let target_str = emit_expr(self, target);
+        // s += x -> s = rt_str_format("%s%s", s, x)
+        if operator == TokenKind::PlusEquals && self.is_string(target) {
+            let concat = Expr::Binary {
+                l: Box::new(target.clone()),
+                operator: TokenKind::Plus,
+                r: Box::new(value.clone()),
+            };
+            let value_str = self.build_string(&concat);
+            return format!("{}{} = {};\n", prefix, target_str, value_str);
+        }
+        return format!(
+            "{}{} {} {};\n",
+            prefix,
+            target_str,
+            operator_text(operator),
+            self.emit_value(value)
+        );
+    }
+    fn emit_expression_statement(&mut self, value: &Expr) -> String {
+        let prefix = self.out_declarations(value);
+        return format!("{}{};\n", prefix, self.emit_value(value));
+    }
+    fn emit_if(
+        &mut self,
+        condition: &Expr,
+        then: &[Statement],
+        otherwise: Option<&[Statement]>,
+    ) -> String {
+        let prefix = self.out_declarations(condition);
+        let condition_str = emit_expr(self, condition);
+        let then_str = emit_block(self, then);
+        let mut writer = CodeWriter::new(self.style);
+        writer.lines(&prefix);
+        writer.open(&format!("if ({})", condition_str));
+        writer.lines(&then_str);
+
+        match otherwise {
+            Some([else_if @ Statement::If { condition, .. }]) => {
+                let declares = !typed_outs(condition).is_empty();
+                let else_if_str = emit_statement(self, else_if);
+                // the variables of `out` arguments are declared before the condition
+                if declares {
+                    writer.close_open("else");
+                    writer.lines(&else_if_str);
+                    writer.close("");
+                } else {
+                    writer.close_with(&format!("else {}", else_if_str));
+                }
+            }
+            Some(otherwise) => {
+                let else_str = emit_block(self, otherwise);
+                writer.close_open("else");
+                writer.lines(&else_str);
+                writer.close("");
+            }
+            None => writer.close(""),
+        }
+        return writer.finish();
+    }
+    fn emit_while(&mut self, condition: &Expr, body: &[Statement]) -> String {
+        let prefix = self.out_declarations(condition);
+        let condition_str = emit_expr(self, condition);
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.lines(&prefix);
+        writer.block(&format!("while ({})", condition_str), &body_str);
+        return writer.finish();
+    }
+    fn emit_for_range(
+        &mut self,
+        variable: &str,
+        from: &Expr,
+        to: &Expr,
+        body: &[Statement],
+    ) -> String {
+        let var_type = self
+            .value_type(to)
+            .or_else(|| self.value_type(from))
+            .unwrap_or(Type::Symbol("i32".to_string()));
+        self.variables
+            .insert(variable.to_string(), var_type.clone());
+        let variable = self.name(variable);
+        let loop_str = format!(
+            "for ({} {} = {}; {} < {}; {}++)",
+            self.map_type(&var_type),
+            variable,
+            emit_expr(self, from),
+            variable,
+            emit_expr(self, to),
+            variable
+        );
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.block(&loop_str, &body_str);
+        return writer.finish();
+    }
+    // for x in a {} -> for (size_t x_index = 0; ...) { int32_t x = a[x_index]; }
+    fn emit_for_each(&mut self, variable: &str, iterable: &Expr, body: &[Statement]) -> String {
+        let Some(Type::Array {
+            left_type,
+            dimensions: 0,
+        }) = self.value_type(iterable)
+        else {
+            panic!(
+                "for loop over: {:?} isn't supported by the c backend, only over one-dimensional arrays",
+                iterable
+            );
+        };
+        self.variables
+            .insert(variable.to_string(), left_type.as_ref().clone());
+        let iterable_str = emit_expr(self, iterable);
+        let length = self.array_length(iterable);
+        let variable = self.name(variable);
+        let index = format!("{}_index", variable);
+        let loop_str = format!(
+            "for (size_t {} = 0; {} < {}; {}++)",
+            index, index, length, index
+        );
+        let element = format!(
+            "{} = {}[{}];\n",
+            self.declaration(&left_type, &variable, None),
+            iterable_str,
+            index
+        );
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.open(&loop_str);
+        writer.lines(&element);
+        writer.lines(&body_str);
+        writer.close("");
+        return writer.finish();
+    }
+    fn emit_scope(&mut self, body: &[Statement]) -> String {
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.block("", &body_str);
+        return writer.finish();
+    }
+    fn emit_return(&mut self, value: Option<&Expr>) -> String {
+        if !self.in_function {
+            return "return 0;\n".to_string();
+        }
+        let Some(value) = value else {
+            return "return;\n".to_string();
+        };
+        let prefix = self.out_declarations(value);
+        return format!("{}return {};\n", prefix, self.emit_value(value));
+    }
+    fn emit_break(&mut self) -> String {
+        return "break;\n".to_string();
+    }
+    fn emit_continue(&mut self) -> String {
+        return "continue;\n".to_string();
+    }
+    // top level functions and classes are written by `generate`
+    fn emit_function(&mut self, function: &Function) -> String {
+        panic!(
+            "function: {} has to be declared at the top level in c",
+            function.name
+        );
+    }
+    fn emit_class(&mut self, class: &Class) -> String {
+        panic!(
+            "struct: {} has to be declared at the top level in c",
+            class.name
+        );
+    }
+
+    fn emit_string(&mut self, text: &str) -> String {
+        return text.to_string();
+    }
+    fn emit_interpolated(&mut self, parts: &[Interpolation]) -> String {
+        return self.build_string(&Expr::Interpolated(parts.to_vec()));
+    }
+    fn emit_bool(&mut self, value: bool) -> String {
+        return value.to_string();
+    }
+    fn emit_variable(&mut self, name: &str, var_type: Option<&Type>) -> String {
+        // references are pointers
+        if let Some(Type::Reference { .. }) = var_type {
+            return format!("(*{})", self.name(name));
+        }
+        return self.name(name);
+    }
+    fn emit_unary(&mut self, operator: TokenKind, value: &Expr) -> String {
+        return format!("{}{}", operator_text(operator), emit_expr(self, value));
+    }
+    fn emit_binary(&mut self, l: &Expr, operator: TokenKind, r: &Expr) -> String {
+        // strings are compared by their content
+        if matches!(operator, TokenKind::Equals | TokenKind::NotEquals)
+            && self.is_string(l)
+            && self.is_string(r)
+        {
+            self.includes.insert("string.h");
+            let left_str = emit_expr(self, l);
+            let right_str = emit_expr(self, r);
+            return format!(
+                "(strcmp({}, {}) {} 0)",
+                left_str,
+                right_str,
+                operator_text(operator)
+            );
+        }
+        if operator == TokenKind::Plus && self.is_string(l) {
+            return self.build_string(&Expr::Binary {
+                l: Box::new(l.clone()),
+                operator,
+                r: Box::new(r.clone()),
+            });
+        }
+        let left_str = emit_expr(self, l);
+        let right_str = emit_expr(self, r);
+        return format!("({} {} {})", left_str, operator_text(operator), right_str);
+    }
+    fn emit_tuple(&mut self, _values: &[Expr]) -> String {
+        panic!("tuples aren't supported by the c backend");
+    }
+    // Type::NAME -> Type_NAME
+    fn emit_static_field(&mut self, value: &Expr, name: &str) -> String {
+        return format!("{}_{}", emit_expr(self, value), name);
+    }
+    // map[x, y] -> map[x][y]
+    fn emit_index(&mut self, value: &Expr, indexes: &[Expr]) -> String {
+        let mut output = emit_expr(self, value);
+        for index in indexes {
+            output += &format!("[{}]", emit_expr(self, index));
+        }
+        return output;
+    }
+    fn emit_call(&mut self, callee: &Callee, arguments: &[Expr]) -> String {
+        match callee {
+            Callee::Function(name) => {
+                // an array argument is followed by its length
+                let mut values = Vec::new();
+                for argument in arguments {
+                    values.push(emit_expr(self, argument));
+                    if let Some(Type::Array { dimensions: 0, .. }) = self.value_type(argument) {
+                        values.push(self.array_length(argument));
+                    }
+                }
+                return format!("{}({})", self.name(name), values.join(", "));
+            }
+            Callee::Method {
+                receiver,
+                name,
+                collection,
+            } => {
+                if let Expr::External(type_name) = receiver.as_ref() {
+                    return handle_static_call(self, type_name, name, arguments);
+                }
+                if let Some(collection) = collection.as_deref() {
+                    return handle_method_call(self, receiver, name, collection, arguments);
+                }
+                // p.move(1) -> Point_move(&p, 1)
+                let Some(Type::Symbol(class)) = self.value_type(receiver) else {
+                    panic!("method: {} of a value of unknown type", name);
+                };
+                let mut values = vec![format!("&{}", emit_expr(self, receiver))];
+                values.push(emit_values(self, arguments));
+                values.retain(|value| !value.is_empty());
+                return format!("{}_{}({})", self.name(&class), name, values.join(", "));
+            }
+            Callee::Static { type_name, name } => {
+                return handle_static_call(self, type_name, name, arguments);
+            }
+            Callee::Value(value) => {
+                let value_str = emit_expr(self, value);
+                return format!("{}({})", value_str, emit_values(self, arguments));
+            }
+        }
+    }
+    // Point { x = 1; y = 2; } -> Point_new(1, 2), missing fields are zero
+    fn emit_new(&mut self, class: &str, fields: &[(String, Expr)]) -> String {
+        let Some(declared) = self.classes.get(class).cloned() else {
+            panic!("struct: {} isn't declared", class);
+        };
+        let mut values = Vec::new();
+        for field in &declared {
+            match fields.iter().find(|(name, _)| name == &field.name) {
+                Some((_, value)) => values.push(self.emit_value(value)),
+                None => values.push(format!("({}){{0}}", self.map_type(&field.var_type))),
+            }
+        }
+        return format!("{}_new({})", self.name(class), values.join(", "));
+    }
+    fn emit_array(&mut self, values: &[Expr]) -> String {
+        return format!("{{{}}}", emit_values(self, values));
+    }
+    fn emit_list(&mut self, _element_type: Option<&Type>, _values: &[Expr]) -> String {
+        panic!("collections aren't supported by the c backend, use an array");
+    }
+    fn emit_new_collection(&mut self, collection: &str, _var_type: Option<&Type>) -> String {
+        panic!(
+            "collection: {} isn't supported by the c backend, use an array",
+            collection
+        );
+    }
+    // the variable is declared before the statement
+    fn emit_out(&mut self, name: &str, _var_type: Option<&Type>) -> String {
+        return format!("&{}", self.name(name));
+    }
+    fn emit_ref(&mut self, value: &Expr, _mutable: bool) -> String {
+        return format!("&{}", emit_expr(self, value));
+    }
+}
+
+/// Console.WriteLine(x) -> printf("%s\n", x), uint.TryParse(s, out x) -> rt_try_parse_uint32_t(s, &x) ...
+fn handle_static_call(
+    generator: &mut C,
+    type_name: &str,
+    name: &str,
+    arguments: &[Expr],
+) -> String {
+    match (type_name, name, arguments) {
+        ("Console", "WriteLine" | "Write", _) => {
+            generator.includes.insert("stdio.h");
+            let newline = if name == "WriteLine" { "\\n" } else { "" };
+            let (format, values) = match arguments {
+                [] => (String::new(), Vec::new()),
+                [value] => generator.format_parts(value),
+                _ => panic!("Console.{} expects one value in c", name),
+            };
+            let values_str: String = values.iter().map(|value| format!(", {}", value)).collect();
+            return format!("printf(\"{}{}\"{})", format, newline, values_str);
+        }
+        ("Console", "ReadLine", []) => {
+            return format!("{}()", generator.use_runtime("rt_read_line"));
+        }
+        (number, "Parse", [text]) => {
+            generator.includes.insert("stdlib.h");
+            let c_type = generator.map_type(&Type::Symbol(number.to_string()));
+            let text_str = emit_expr(generator, text);
+            if c_type == "float" || c_type == "double" {
+                return format!("({})strtod({}, NULL)", c_type, text_str);
+            }
+            return format!("({})strtoll({}, NULL, 10)", c_type, text_str);
+        }
+        (number, "TryParse", [_, _]) => {
+            let c_type = generator.map_type(&Type::Symbol(number.to_string()));
+            let name = generator.use_runtime(&format!("rt_try_parse_{}", c_type));
+            return format!("{}({})", name, emit_values(generator, arguments));
+        }
+        _ => panic!("{}.{} isn't supported by the c backend", type_name, name),
+    }
+}
+/// s.len() -> strlen(s), a.len() -> sizeof(a) / sizeof(a[0])
+fn handle_method_call(
+    generator: &mut C,
+    receiver: &Expr,
+    name: &str,
+    collection: &str,
+    arguments: &[Expr],
+) -> String {
+    let receiver_str = emit_expr(generator, receiver);
+    match (collection, name) {
+        ("String", "len") => {
+            generator.includes.insert("string.h");
+            return format!("strlen({})", receiver_str);
+        }
+        ("String", "is_empty") => return format!("({}[0] == '\\0')", receiver_str),
+        ("String", "contains") => {
+            generator.includes.insert("string.h");
+            let value_str = emit_values(generator, arguments);
+            return format!("(strstr({}, {}) != NULL)", receiver_str, value_str);
+        }
+        ("Array", "len") => return generator.array_length(receiver),
+        (_, "iter" | "iter_mut" | "into_iter") => return receiver_str,
+        (_, "to_string") => return generator.build_string(receiver),
+        _ => panic!(
+            "method: {} of {} isn't supported by the c backend",
+            name, collection
+        ),
+    }
+}
+/// `{{1, 2}, {3, 4}}` -> [2, 2]
+fn array_sizes(value: &Expr) -> Vec<usize> {
+    let Expr::Array(values) = value else {
+        return Vec::new();
+    };
+    let mut sizes = vec![values.len()];
+    if let Some(first) = values.first() {
+        sizes.extend(array_sizes(first));
+    }
+    return sizes;
+}
+/// value that can initialize a global
+fn is_literal(value: &Expr) -> bool {
+    match value {
+        Expr::Number(_) | Expr::Float(_) | Expr::Bool(_) => true,
+        Expr::String(_) => true,
+        Expr::Unary {
+            operator: TokenKind::Minus,
+            value,
+        } => is_literal(value),
+        Expr::Array(values) => values.iter().all(is_literal),
+        _ => false,
+    }
+}
+fn is_string_type(symbol: &str) -> bool {
+    return matches!(symbol, "str" | "String" | "string");
+}
+/// `out` arguments that declare a variable
+fn typed_outs(value: &Expr) -> Vec<(String, Type)> {
+    let mut outs = Vec::new();
+    if let Expr::Out {
+        name,
+        var_type: Some(var_type),
+    } = value
+    {
+        outs.push((name.clone(), var_type.clone()));
+    }
+    for child in value.children() {
+        outs.extend(typed_outs(child));
+    }
+    return outs;
+}
+fn operator_text(operator: TokenKind) -> &'static str {
+    match operator {
+        TokenKind::Plus => "+",
+        TokenKind::Minus => "-",
+        TokenKind::Star => "*",
+        TokenKind::Slash => "/",
+        TokenKind::Percent => "%",
+        TokenKind::Less => "<",
+        TokenKind::LessEquals => "<=",
+        TokenKind::Greater => ">",
+        TokenKind::GreaterEquals => ">=",
+        TokenKind::Equals => "==",
+        TokenKind::NotEquals => "!=",
+        TokenKind::Or => "||",
+        TokenKind::And => "&&",
+        TokenKind::Not => "!",
+        TokenKind::Assignment => "=",
+        TokenKind::PlusEquals => "+=",
+        TokenKind::MinusEquals => "-=",
+        default => panic!("operator: {:?} doesn't have a handler", default),
+    }
+}
+
+fn handle_type(generator: &C, var_type: &Type) -> String {
+    match var_type {
+        Type::Symbol(symbol) => handle_symbol_type(generator, symbol),
+        Type::Array { .. } => panic!("array type: {:?} needs a variable name in c", var_type),
+        Type::Generic { name, .. } => panic!(
+            "collection: {} isn't supported by the c backend, use an array",
+            name
+        ),
+        Type::Tuple(types) if types.is_empty() => "void".to_string(),
+        Type::Tuple(_) => panic!("tuples aren't supported by the c backend"),
+        Type::Reference { inner, .. } => format!("{}*", handle_type(generator, inner)),
+    }
+}
+fn handle_symbol_type(generator: &C, symbol: &str) -> String {
+    let c_type = match symbol {
+        "i8" | "sbyte" => "int8_t",
+        "i16" | "short" => "int16_t",
+        "i32" | "int" => "int32_t",
+        "i64" | "long" => "int64_t",
+        "u8" | "byte" => "uint8_t",
+        "u16" | "ushort" => "uint16_t",
+        "u32" | "uint" => "uint32_t",
+        "u64" | "ulong" => "uint64_t",
+        "isize" | "nint" => "intptr_t",
+        "usize" | "nuint" => "size_t",
+        "f32" | "float" => "float",
+        "f64" | "double" => "double",
+        "bool" => "bool",
+        "char" => "char",
+        "str" | "String" | "string" => "const char*",
+        "i128" | "u128" | "decimal" => panic!("type: {} doesn't have a c99 equivalent", symbol),
+        class => return generator.name(class),
+    };
+    return c_type.to_string();
+}
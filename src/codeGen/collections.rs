@@ -0,0 +1,81 @@
+use crate::types::Type;
+
+/// How a method call on a standard collection is written in c#
+pub enum MethodRewrite {
+    /// v.push(1) -> v.Add(1)
+    Rename(&'static str),
+    /// v.len() -> v.Count
+    Property(&'static str),
+    /// v.iter() -> v
+    Receiver,
+    /// map.insert(k, v) -> map[k] = v
+    IndexAssign,
+    /// s.push_str(x) -> s += x
+    AppendAssign,
+    /// v.is_empty() -> (v.Count == 0)
+    IsEmpty(&'static str),
+}
+
+/// Name of the collection that decides which methods are available on `var_type`
+pub fn collection_name(var_type: &Type) -> Option<&str> {
+    match var_type {
+        Type::Generic { name, .. } => Some(name.as_str()),
+        Type::Symbol(symbol) if symbol == "str" || symbol == "String" => Some("String"),
+        Type::Array { .. } => Some("Array"),
+        Type::Reference { inner, .. } => collection_name(inner),
+        _ => None,
+    }
+}
+
+pub fn handle_collection_name(name: &str) -> Option<&'static str> {
+    match name {
+        "Vec" => Some("List"),
+        "HashMap" => Some("Dictionary"),
+        "HashSet" => Some("HashSet"),
+        "VecDeque" => Some("Queue"),
+        _ => None,
+    }
+}
+
+/// `collection` is empty when the type of the receiver is unknown, then only methods that work the same on every type are rewritten
+pub fn method_rewrite(collection: &str, method: &str) -> Option<MethodRewrite> {
+    let rewrite = match (collection, method) {
+        (_, "iter") | (_, "iter_mut") | (_, "into_iter") => MethodRewrite::Receiver,
+        (_, "to_string") => MethodRewrite::Rename("ToString"),
+
+        ("Vec", "push") => MethodRewrite::Rename("Add"),
+        ("Vec", "insert") => MethodRewrite::Rename("Insert"),
+        ("Vec", "remove") => MethodRewrite::Rename("RemoveAt"),
+        ("Vec", "contains") => MethodRewrite::Rename("Contains"),
+        ("Vec", "clear") => MethodRewrite::Rename("Clear"),
+
+        ("HashMap", "insert") => MethodRewrite::IndexAssign,
+        ("HashMap", "contains_key") => MethodRewrite::Rename("ContainsKey"),
+        ("HashMap", "remove") => MethodRewrite::Rename("Remove"),
+        ("HashMap", "clear") => MethodRewrite::Rename("Clear"),
+        ("HashMap", "keys") => MethodRewrite::Property("Keys"),
+        ("HashMap", "values") => MethodRewrite::Property("Values"),
+
+        ("HashSet", "insert") => MethodRewrite::Rename("Add"),
+        ("HashSet", "contains") => MethodRewrite::Rename("Contains"),
+        ("HashSet", "remove") => MethodRewrite::Rename("Remove"),
+        ("HashSet", "clear") => MethodRewrite::Rename("Clear"),
+
+        ("VecDeque", "push_back") => MethodRewrite::Rename("Enqueue"),
+        ("VecDeque", "pop_front") => MethodRewrite::Rename("Dequeue"),
+        ("VecDeque", "front") => MethodRewrite::Rename("Peek"),
+        ("VecDeque", "contains") => MethodRewrite::Rename("Contains"),
+        ("VecDeque", "clear") => MethodRewrite::Rename("Clear"),
+
+        ("String", "len") | ("Array", "len") => MethodRewrite::Property("Length"),
+        ("String", "is_empty") | ("Array", "is_empty") => MethodRewrite::IsEmpty("Length"),
+        ("String", "push_str") => MethodRewrite::AppendAssign,
+        ("String", "contains") => MethodRewrite::Rename("Contains"),
+
+        ("Vec" | "HashMap" | "HashSet" | "VecDeque", "len") => MethodRewrite::Property("Count"),
+        ("Vec" | "HashMap" | "HashSet" | "VecDeque", "is_empty") => MethodRewrite::IsEmpty("Count"),
+
+        _ => return None,
+    };
+    return Some(rewrite);
+}
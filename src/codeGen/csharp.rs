@@ -0,0 +1,834 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    backend::{emit_block, emit_expr, emit_statement, emit_values, Backend, OutputFile},
+    collections::{handle_collection_name, method_rewrite, MethodRewrite},
+    ir::{Callee, Class, Expr, Field, Function, Interpolation, Statement},
+    reserved::ReservedWords,
+    tokens::TokenKind,
+    type_map::TypeMap,
+    types::Type,
+    writer::{CodeStyle, CodeWriter},
+};
+
+pub struct CSharp {
+    pub type_map: TypeMap,
+    /// immutable variables with constant values are emitted as `const`
+    pub emit_const: bool,
+    pub reserved: ReservedWords,
+    /// classes declared in the source, used to escape type names
+    pub classes: HashSet<String>,
+    pub style: CodeStyle,
+    /// `namespace` around the generated code, only with a `class_name`
+    pub namespace: Option<String>,
+    /// the program is a static class with this name instead of top level statements
+    pub class_name: Option<String>,
+    /// fields of the classes, a float literal stored in a `float` field needs the `f` suffix
+    fields: HashMap<String, Vec<Field>>,
+    /// name -> (types of the parameters, output)
+    functions: HashMap<String, (Vec<Type>, Option<Type>)>,
+    /// output of the function being generated
+    output: Option<Type>,
+    /// the value being generated is a `float`, its float literals aren't `double`
+    float_literals: bool,
+}
+impl CSharp {
+    pub fn new(type_map: TypeMap) -> CSharp {
+        CSharp {
+            type_map,
+            emit_const: false,
+            reserved: ReservedWords::csharp(),
+            classes: HashSet::new(),
+            style: CodeStyle::new(),
+            namespace: None,
+            class_name: None,
+            fields: HashMap::new(),
+            functions: HashMap::new(),
+            output: None,
+            float_literals: false,
+        }
+    }
+    fn is_float_type(&self, var_type: Option<&Type>) -> bool {
+        return var_type.is_some_and(|var_type| self.map_type(var_type) == "float");
+    }
+    /// `value` stored as `var_type`, `let k: f32 = 2.5;` -> `float k = 2.5f;`
+    fn emit_typed(&mut self, value: &Expr, var_type: Option<&Type>) -> String {
+        let outer = self.float_literals;
+        self.float_literals = self.is_float_type(var_type);
+        let value_str = emit_expr(self, value);
+        self.float_literals = outer;
+        return value_str;
+    }
+    /// type of the value when it is known without the type checker
+    fn value_type(&self, value: &Expr) -> Option<Type> {
+        match value {
+            Expr::Variable { var_type, .. } => return var_type.clone(),
+            Expr::Field { value, name } => {
+                let Some(Type::Symbol(class)) = self.value_type(value) else {
+                    return None;
+                };
+                let fields = self.fields.get(&class)?;
+                let field = fields.iter().find(|field| &field.name == name)?;
+                return Some(field.var_type.clone());
+            }
+            Expr::Call {
+                callee: Callee::Function(name),
+                ..
+            } => return self.functions.get(name)?.1.clone(),
+            Expr::Unary { value, .. } => return self.value_type(value),
+            Expr::Binary {
+                l,
+                operator: TokenKind::Plus | TokenKind::Minus | TokenKind::Star | TokenKind::Slash,
+                r,
+            } => return self.value_type(l).or_else(|| self.value_type(r)),
+            _ => return None,
+        }
+    }
+}
+
+impl Backend for CSharp {
+    fn extension(&self) -> &'static str {
+        return "cs";
+    }
+    fn reserved(&self) -> &ReservedWords {
+        return &self.reserved;
+    }
+    fn map_type(&self, var_type: &Type) -> String {
+        return handle_type(self, var_type);
+    }
+    fn generate(&mut self, program: &[Statement]) -> Vec<OutputFile> {
+        for statement in program {
+            match statement {
+                Statement::Class(class) => {
+                    self.classes.insert(class.name.clone());
+                    self.fields.insert(class.name.clone(), class.fields.clone());
+                }
+                Statement::Function(function) => {
+                    let parameters = function
+                        .parameters
+                        .iter()
+                        .map(|parameter| parameter.var_type.clone())
+                        .collect();
+                    self.functions
+                        .insert(function.name.clone(), (parameters, function.output.clone()));
+                }
+                _ => {}
+            }
+        }
+        let content = match self.class_name.clone() {
+            Some(class_name) => handle_program(self, program, &class_name),
+            None => emit_block(self, program),
+        };
+        return vec![OutputFile {
+            extension: self.extension(),
+            content,
+        }];
+    }
+
+    fn emit_let(
+        &mut self,
+        name: &str,
+        var_type: Option<&Type>,
+        inferred: Option<&Type>,
+        _mutable: bool,
+        constant: bool,
+        value: Option<&Expr>,
+    ) -> String {
+        // without an annotation c# infers the type the same way, `let x;` needs the type that was inferred later
+        let type_str = match (var_type, value, inferred) {
+            (Some(var_type), _, _) | (None, None, Some(var_type)) => self.map_type(var_type),
+            (None, _, _) => "var".to_string(),
+        };
+        let const_str = if constant && self.emit_const {
+            "const "
+        } else {
+            ""
+        };
+        let name = self.name(name);
+        match value {
+            Some(value) => {
+                let value_str = self.emit_typed(value, var_type.or(inferred));
+                return format!("{}{} {} = {};\n", const_str, type_str, name, value_str);
+            }
+            None => return format!("{} {};\n", type_str, name),
+        }
+    }
+    fn emit_let_tuple(
+        &mut self,
+        names: &[String],
+        types: Option<&[Type]>,
+        value: Option<&Expr>,
+    ) -> String {
+        let names: Vec<String> = names.iter().map(|name| self.name(name)).collect();
+        let declaration = match types {
+            // (int a, string b) = ...
+            Some(types) => {
+                let declarations: Vec<String> = names
+                    .iter()
+                    .zip(types)
+                    .map(|(name, var_type)| format!("{} {}", self.map_type(var_type), name))
+                    .collect();
+                format!("({})", declarations.join(", "))
+            }
+            // var (a, b) = ...
+            None => format!("var ({})", names.join(", ")),
+        };
+        match value {
+            Some(value) => format!("{} = {};\n", declaration, emit_expr(self, value)),
+            None => format!("{};\n", declaration),
+        }
+    }
+    fn emit_assign(&mut self, target: &Expr, operator: TokenKind, value: &Expr) -> String {
+        let target_type = self.value_type(target);
+        return format!(
+            "{} {} {};\n",
+            emit_expr(self, target),
+            operator_text(operator),
+            self.emit_typed(value, target_type.as_ref())
+        );
+    }
+    fn emit_expression_statement(&mut self, value: &Expr) -> String {
+        return format!("{};\n", emit_expr(self, value));
+    }
+    fn emit_if(
+        &mut self,
+        condition: &Expr,
+        then: &[Statement],
+        otherwise: Option<&[Statement]>,
+    ) -> String {
+        let condition_str = emit_expr(self, condition);
+        let then_str = emit_block(self, then);
+        let mut writer = CodeWriter::new(self.style);
+        writer.open(&format!("if({})", condition_str));
+        writer.lines(&then_str);
+
+        match otherwise {
+            Some([else_if @ Statement::If { .. }]) => {
+                let else_if_str = emit_statement(self, else_if);
+                writer.close_with(&format!("else {}", else_if_str));
+            }
+            Some(otherwise) => {
+                let else_str = emit_block(self, otherwise);
+                writer.close_open("else");
+                writer.lines(&else_str);
+                writer.close("");
+            }
+            None => writer.close(""),
+        }
+        return writer.finish();
+    }
+    fn emit_while(&mut self, condition: &Expr, body: &[Statement]) -> String {
+        let condition_str = emit_expr(self, condition);
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.block(&format!("while({})", condition_str), &body_str);
+        return writer.finish();
+    }
+    fn emit_for_range(
+        &mut self,
+        variable: &str,
+        from: &Expr,
+        to: &Expr,
+        body: &[Statement],
+    ) -> String {
+        let variable = self.name(variable);
+        let loop_str = format!(
+            "for(int {} = {}; {} < {}; {}++)",
+            variable,
+            emit_expr(self, from),
+            variable,
+            emit_expr(self, to),
+            variable
+        );
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.block(&loop_str, &body_str);
+        return writer.finish();
+    }
+    fn emit_for_each(&mut self, variable: &str, iterable: &Expr, body: &[Statement]) -> String {
+        let loop_str = format!(
+            "foreach(var {} in {})",
+            self.name(variable),
+            emit_expr(self, iterable)
+        );
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.block(&loop_str, &body_str);
+        return writer.finish();
+    }
+    fn emit_scope(&mut self, body: &[Statement]) -> String {
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.block("", &body_str);
+        return writer.finish();
+    }
+    fn emit_return(&mut self, value: Option<&Expr>) -> String {
+        match value {
+            Some(value) => {
+                let output = self.output.clone();
+                return format!("return {};\n", self.emit_typed(value, output.as_ref()));
+            }
+            None => return "return;\n".to_string(),
+        }
+    }
+    fn emit_break(&mut self) -> String {
+        return "break;\n".to_string();
+    }
+    fn emit_continue(&mut self) -> String {
+        return "continue;\n".to_string();
+    }
+    fn emit_function(&mut self, function: &Function) -> String {
+        let public_str = if function.public { "public " } else { "" };
+        return handle_function(self, function, public_str);
+    }
+    fn emit_class(&mut self, class: &Class) -> String {
+        let public_str = if class.public { "public " } else { "" };
+        let mut fields_text = String::new();
+        for field in &class.fields {
+            fields_text += &format!(
+                "public {} {};\n",
+                self.map_type(&field.var_type),
+                self.name(&field.name)
+            );
+        }
+        let mut functions_text = String::new();
+        for method in &class.methods {
+            functions_text += &self.emit_function(method);
+        }
+        let mut writer = CodeWriter::new(self.style);
+        writer.open(&format!("{}struct {}", public_str, self.name(&class.name)));
+        writer.lines(&fields_text);
+        writer.lines(&functions_text);
+        writer.close(";");
+        return writer.finish();
+    }
+
+    fn emit_string(&mut self, text: &str) -> String {
+        return text.to_string();
+    }
+    fn emit_interpolated(&mut self, parts: &[Interpolation]) -> String {
+        let mut output = String::from("$\"");
+        for part in parts {
+            match part {
+                Interpolation::Text(text) => output += &text.replace('{', "{{").replace('}', "}}"),
+                Interpolation::Value(value) => output += &format!("{{{}}}", emit_expr(self, value)),
+            }
+        }
+        output.push('"');
+        return output;
+    }
+    fn emit_float(&mut self, value: f32) -> String {
+        match self.float_literals {
+            true => return format!("{:?}f", value),
+            false => return format!("{:?}", value),
+        }
+    }
+    fn emit_bool(&mut self, value: bool) -> String {
+        return value.to_string();
+    }
+    fn emit_unary(&mut self, operator: TokenKind, value: &Expr) -> String {
+        return format!("{}{}", operator_text(operator), emit_expr(self, value));
+    }
+    // k * 2.0 with a `float` k is a `float` too
+    fn emit_binary(&mut self, l: &Expr, operator: TokenKind, r: &Expr) -> String {
+        let outer = self.float_literals;
+        self.float_literals |= self.is_float_type(self.value_type(l).as_ref())
+            || self.is_float_type(self.value_type(r).as_ref());
+        let left_str = emit_expr(self, l);
+        let right_str = emit_expr(self, r);
+        self.float_literals = outer;
+        return format!("({} {} {})", left_str, operator_text(operator), right_str);
+    }
+    fn emit_tuple(&mut self, values: &[Expr]) -> String {
+        return format!("({})", emit_values(self, values));
+    }
+    // c# uses the same syntax for static and instance members
+    fn emit_static_field(&mut self, value: &Expr, name: &str) -> String {
+        return format!("{}.{}", emit_expr(self, value), name);
+    }
+    fn emit_index(&mut self, value: &Expr, indexes: &[Expr]) -> String {
+        let value_str = emit_expr(self, value);
+        return format!("{}[{}]", value_str, emit_values(self, indexes));
+    }
+    fn emit_call(&mut self, callee: &Callee, arguments: &[Expr]) -> String {
+        match callee {
+            Callee::Function(name) => {
+                let parameters = match self.functions.get(name) {
+                    Some((parameters, _)) => parameters.clone(),
+                    None => Vec::new(),
+                };
+                let mut values = Vec::new();
+                for (i, argument) in arguments.iter().enumerate() {
+                    values.push(self.emit_typed(argument, parameters.get(i)));
+                }
+                return format!("{}({})", self.name(name), values.join(", "));
+            }
+            Callee::Method {
+                receiver,
+                name,
+                collection,
+            } => handle_method_call(self, receiver, name, collection.as_deref(), arguments),
+            Callee::Static { type_name, name } => {
+                let type_name = match self.classes.contains(type_name) {
+                    true => self.name(type_name),
+                    false => type_name.clone(),
+                };
+                let arguments_str = emit_values(self, arguments);
+                return format!("{}.{}({})", type_name, name, arguments_str);
+            }
+            Callee::Value(value) => {
+                let value_str = emit_expr(self, value);
+                return format!("{}({})", value_str, emit_values(self, arguments));
+            }
+        }
+    }
+    fn emit_new(&mut self, class: &str, fields: &[(String, Expr)]) -> String {
+        // the lines after the first are indented relative to the statement the value is in
+        let mut writer = CodeWriter::new(self.style);
+        writer.open(&format!("new {}", self.name(class)));
+        let declared = self.fields.get(class).cloned().unwrap_or_default();
+        for (name, value) in fields {
+            let field_type = declared
+                .iter()
+                .find(|field| &field.name == name)
+                .map(|field| field.var_type.clone());
+            let value_str = self.emit_typed(value, field_type.as_ref());
+            writer.lines(&format!("{} = {},", self.name(name), value_str));
+        }
+        writer.close("");
+        return writer.finish().trim_end().to_string();
+    }
+    fn emit_array(&mut self, values: &[Expr]) -> String {
+        return format!("{{{}}}", emit_values(self, values));
+    }
+    fn emit_list(&mut self, element_type: Option<&Type>, values: &[Expr]) -> String {
+        let element_type = match element_type {
+            Some(element_type) => self.map_type(element_type),
+            None => "object".to_string(),
+        };
+        return format!(
+            "new List<{}>{{{}}}",
+            element_type,
+            emit_values(self, values)
+        );
+    }
+    // without the type c# has to infer the type of `new()` from the target
+    fn emit_new_collection(&mut self, _collection: &str, var_type: Option<&Type>) -> String {
+        match var_type {
+            Some(var_type) => return format!("new {}()", self.map_type(var_type)),
+            None => return "new()".to_string(),
+        }
+    }
+    fn emit_out(&mut self, name: &str, var_type: Option<&Type>) -> String {
+        match var_type {
+            Some(var_type) => format!("out {} {}", self.map_type(var_type), self.name(name)),
+            None => format!("out {}", self.name(name)),
+        }
+    }
+    // c# passes classes by reference anyway, only `&mut` needs `ref`
+    fn emit_ref(&mut self, value: &Expr, mutable: bool) -> String {
+        let value_str = emit_expr(self, value);
+        if mutable {
+            return format!("ref {}", value_str);
+        }
+        return value_str;
+    }
+}
+
+/// `namespace Name { public static class Program { ... } }`, top level variables become static fields,
+/// functions static methods and the other statements `Main`
+fn handle_program(generator: &mut CSharp, program: &[Statement], class_name: &str) -> String {
+    check_main_locals(program);
+    let mut types_text = String::new();
+    let mut fields_text = String::new();
+    let mut methods_text = String::new();
+    let mut main_text = String::new();
+    for statement in program {
+        match statement {
+            // the types can be in the signatures of the public methods, they have to be public too
+            Statement::Class(class) => {
+                let class = Class {
+                    public: true,
+                    ..class.clone()
+                };
+                types_text += &generator.emit_class(&class);
+            }
+            Statement::Function(function) => {
+                let modifiers = if function.public {
+                    "public static "
+                } else {
+                    "static "
+                };
+                methods_text += &handle_function(generator, function, modifiers);
+            }
+            Statement::Let {
+                name,
+                inferred: Some(var_type),
+                mutable,
+                constant,
+                value,
+                ..
+            } => {
+                let type_str = generator.map_type(var_type);
+                let name = generator.name(name);
+                match value {
+                    Some(value) if is_static_value(value) => {
+                        let modifiers = match (*constant && generator.emit_const, mutable, value) {
+                            (true, _, _) => "const",
+                            // the fields of a readonly struct can't be assigned
+                            (false, true, _) | (false, false, Expr::New { .. }) => "static",
+                            (false, false, _) => "static readonly",
+                        };
+                        let value_str = generator.emit_typed(value, Some(var_type));
+                        fields_text +=
+                            &format!("{} {} {} = {};\n", modifiers, type_str, name, value_str);
+                    }
+                    Some(value) => {
+                        fields_text += &format!("static {} {};\n", type_str, name);
+                        let value_str = generator.emit_typed(value, Some(var_type));
+                        main_text += &format!("{} = {};\n", name, value_str);
+                    }
+                    None => fields_text += &format!("static {} {};\n", type_str, name),
+                }
+            }
+            Statement::LetTuple {
+                names,
+                inferred: Some(types),
+                value,
+                ..
+            } => {
+                let names: Vec<String> = names.iter().map(|name| generator.name(name)).collect();
+                for (name, var_type) in names.iter().zip(types) {
+                    // `_` is a discard in the assignment
+                    if name != "_" {
+                        let type_str = generator.map_type(var_type);
+                        fields_text += &format!("static {} {};\n", type_str, name);
+                    }
+                }
+                if let Some(value) = value {
+                    let value_str = emit_expr(generator, value);
+                    main_text += &format!("({}) = {};\n", names.join(", "), value_str);
+                }
+            }
+            // a field needs its type, variables with a type from the target stay locals of `Main`
+            _ => main_text += &emit_statement(generator, statement),
+        }
+    }
+
+    let mut writer = CodeWriter::new(generator.style);
+    let namespace = generator.namespace.clone();
+    if let Some(namespace) = &namespace {
+        writer.open(&format!("namespace {}", namespace));
+    }
+    writer.lines(&types_text);
+    writer.open(&format!("public static class {}", class_name));
+    writer.lines(&fields_text);
+    writer.lines(&methods_text);
+    if !main_text.is_empty() {
+        writer.block("static void Main()", &main_text);
+    }
+    writer.close("");
+    if namespace.is_some() {
+        writer.close("");
+    }
+    return writer.finish();
+}
+/// A variable without a known type stays a local of `Main`, the methods can't see it
+fn check_main_locals(program: &[Statement]) {
+    let mut locals = Vec::new();
+    for statement in program {
+        match statement {
+            Statement::Let {
+                name,
+                inferred: None,
+                ..
+            } => locals.push(name),
+            Statement::LetTuple {
+                names,
+                inferred: None,
+                ..
+            } => locals.extend(names),
+            _ => {}
+        }
+    }
+    let mut functions: Vec<&Function> = Vec::new();
+    for statement in program {
+        match statement {
+            Statement::Function(function) => functions.push(function),
+            Statement::Class(class) => functions.extend(&class.methods),
+            _ => {}
+        }
+    }
+    for function in functions {
+        let mut used = HashSet::new();
+        let mut declared: HashSet<String> = function
+            .parameters
+            .iter()
+            .map(|parameter| parameter.name.clone())
+            .collect();
+        collect_names(&function.body, &mut used, &mut declared);
+        if let Some(name) = locals
+            .iter()
+            .find(|name| used.contains(**name) && !declared.contains(**name))
+        {
+            panic!(
+                "variable: {} is used by the function: {} but it stays a local of `Main` in a c# class, declare it with a type",
+                name, function.name
+            );
+        }
+    }
+}
+/// variables the statements use and the ones they declare themselves
+fn collect_names(
+    statements: &[Statement],
+    used: &mut HashSet<String>,
+    declared: &mut HashSet<String>,
+) {
+    for statement in statements {
+        let (values, bodies): (Vec<&Expr>, Vec<&[Statement]>) = match statement {
+            Statement::Let { name, value, .. } => {
+                declared.insert(name.clone());
+                (value.iter().collect(), vec![])
+            }
+            Statement::LetTuple { names, value, .. } => {
+                declared.extend(names.iter().cloned());
+                (value.iter().collect(), vec![])
+            }
+            Statement::Assign { target, value, .. } => (vec![target, value], vec![]),
+            Statement::Expression(value) => (vec![value], vec![]),
+            Statement::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let mut bodies = vec![then.as_slice()];
+                bodies.extend(otherwise.as_deref());
+                (vec![condition], bodies)
+            }
+            Statement::While { condition, body } => (vec![condition], vec![body.as_slice()]),
+            Statement::ForRange {
+                variable,
+                from,
+                to,
+                body,
+            } => {
+                declared.insert(variable.clone());
+                (vec![from, to], vec![body.as_slice()])
+            }
+            Statement::ForEach {
+                variable,
+                iterable,
+                body,
+            } => {
+                declared.insert(variable.clone());
+                (vec![iterable], vec![body.as_slice()])
+            }
+            Statement::Block(body) => (vec![], vec![body.as_slice()]),
+            Statement::Return(value) => (value.iter().collect(), vec![]),
+            Statement::Function(function) => (vec![], vec![function.body.as_slice()]),
+            Statement::Break | Statement::Continue | Statement::Class(_) => (vec![], vec![]),
+        };
+        for value in values {
+            collect_value_names(value, used, declared);
+        }
+        for body in bodies {
+            collect_names(body, used, declared);
+        }
+    }
+}
+fn collect_value_names(value: &Expr, used: &mut HashSet<String>, declared: &mut HashSet<String>) {
+    match value {
+        Expr::Variable { name, .. }
+        | Expr::Out {
+            name,
+            var_type: None,
+        } => {
+            used.insert(name.clone());
+        }
+        Expr::Out { name, .. } => {
+            declared.insert(name.clone());
+        }
+        _ => {}
+    }
+    for child in value.children() {
+        collect_value_names(child, used, declared);
+    }
+}
+/// values that don't depend on other variables or calls can initialize a static field
+fn is_static_value(value: &Expr) -> bool {
+    let own = matches!(
+        value,
+        Expr::String(_)
+            | Expr::Interpolated(_)
+            | Expr::Number(_)
+            | Expr::Float(_)
+            | Expr::Bool(_)
+            | Expr::Unary { .. }
+            | Expr::Binary { .. }
+            | Expr::Tuple(_)
+            | Expr::New { .. }
+            | Expr::Array(_)
+            | Expr::List { .. }
+            | Expr::NewCollection { .. }
+    );
+    return own && value.children().into_iter().all(is_static_value);
+}
+/// `modifiers` are `public`, `static` ... in front of the signature
+fn handle_function(generator: &mut CSharp, function: &Function, modifiers: &str) -> String {
+    let parameters: Vec<String> = function
+        .parameters
+        .iter()
+        .map(|parameter| {
+            format!(
+                "{} {}",
+                generator.map_type(&parameter.var_type),
+                generator.name(&parameter.name)
+            )
+        })
+        .collect();
+    let outer = generator.output.clone();
+    generator.output = function.output.clone();
+    let inside_str = emit_block(generator, &function.body);
+    generator.output = outer;
+
+    let output_str = match &function.output {
+        Some(var_type) => generator.map_type(var_type),
+        None => "void".to_string(),
+    };
+
+    let signature = format!(
+        "{}{} {}({})",
+        modifiers,
+        output_str,
+        generator.name(&function.name),
+        parameters.join(", ")
+    );
+    let mut writer = CodeWriter::new(generator.style);
+    writer.block(&signature, &inside_str);
+    return writer.finish();
+}
+/// v.push(1) -> v.Add(1), map.insert(k, v) -> map[k] = v ...
+fn handle_method_call(
+    generator: &mut CSharp,
+    receiver: &Expr,
+    name: &str,
+    collection: Option<&str>,
+    arguments: &[Expr],
+) -> String {
+    let rewrite = method_rewrite(collection.unwrap_or(""), name);
+
+    let receiver_str = emit_expr(generator, receiver);
+    match rewrite {
+        Some(MethodRewrite::Rename(new_name)) => {
+            let arguments_str = emit_values(generator, arguments);
+            return format!("{}.{}({})", receiver_str, new_name, arguments_str);
+        }
+        Some(MethodRewrite::Property(property)) => return format!("{}.{}", receiver_str, property),
+        Some(MethodRewrite::Receiver) => return receiver_str,
+        Some(MethodRewrite::IndexAssign) => {
+            let [key, value] = arguments else {
+                panic!(
+                    "{}.{} expects 2 values but got {}",
+                    receiver_str,
+                    name,
+                    arguments.len()
+                );
+            };
+            let key = emit_expr(generator, key);
+            let value = emit_expr(generator, value);
+            return format!("{}[{}] = {}", receiver_str, key, value);
+        }
+        Some(MethodRewrite::AppendAssign) => {
+            let arguments_str = emit_values(generator, arguments);
+            return format!("{} += {}", receiver_str, arguments_str);
+        }
+        Some(MethodRewrite::IsEmpty(property)) => {
+            return format!("({}.{} == 0)", receiver_str, property)
+        }
+        None => {
+            let arguments_str = emit_values(generator, arguments);
+            return format!("{}.{}({})", receiver_str, name, arguments_str);
+        }
+    }
+}
+/// c# spelling of an operator
+fn operator_text(operator: TokenKind) -> &'static str {
+    match operator {
+        TokenKind::Plus => "+",
+        TokenKind::Minus => "-",
+        TokenKind::Star => "*",
+        TokenKind::Slash => "/",
+        TokenKind::Percent => "%",
+        TokenKind::Less => "<",
+        TokenKind::LessEquals => "<=",
+        TokenKind::Greater => ">",
+        TokenKind::GreaterEquals => ">=",
+        TokenKind::Equals => "==",
+        TokenKind::NotEquals => "!=",
+        TokenKind::Or => "||",
+        TokenKind::And => "&&",
+        TokenKind::Not => "!",
+        TokenKind::Assignment => "=",
+        TokenKind::PlusEquals => "+=",
+        TokenKind::MinusEquals => "-=",
+        default => panic!("operator: {:?} doesn't have a handler", default),
+    }
+}
+
+fn handle_type(generator: &CSharp, var_type: &Type) -> String {
+    match var_type {
+        Type::Symbol(symbol) => handle_symbol_type(generator, symbol),
+        Type::Array {
+            left_type,
+            dimensions,
+        } => handle_array(generator, left_type, *dimensions),
+        Type::Generic { name, arguments } => handle_generic_type(generator, name, arguments),
+        Type::Tuple(types) => handle_tuple_type(generator, types),
+        Type::Reference { inner, mutable } => handle_reference_type(generator, inner, *mutable),
+    }
+}
+fn handle_array(generator: &CSharp, left_type: &Type, dimensions: usize) -> String {
+    let mut dimensions_str = String::with_capacity(dimensions);
+    for _ in 0..dimensions {
+        dimensions_str += ",";
+    }
+    return format!("{}[{}]", handle_type(generator, left_type), dimensions_str);
+}
+fn handle_generic_type(generator: &CSharp, name: &str, arguments: &[Type]) -> String {
+    let arguments: Vec<String> = arguments
+        .iter()
+        .map(|argument| handle_type(generator, argument))
+        .collect();
+
+    let name_str = match generator.type_map.get(name) {
+        Some(alias) => alias,
+        None => handle_collection_name(name).unwrap_or(name).to_string(),
+    };
+    return format!("{}<{}>", name_str, arguments.join(", "));
+}
+/// c# passes classes by reference anyway, only `&mut` needs `ref`
+fn handle_reference_type(generator: &CSharp, inner: &Type, mutable: bool) -> String {
+    let inner_str = handle_type(generator, inner);
+    if mutable {
+        return format!("ref {}", inner_str);
+    }
+    return inner_str;
+}
+fn handle_tuple_type(generator: &CSharp, types: &[Type]) -> String {
+    if types.is_empty() {
+        return "void".to_string();
+    }
+    let types: Vec<String> = types
+        .iter()
+        .map(|var_type| handle_type(generator, var_type))
+        .collect();
+    return format!("({})", types.join(", "));
+}
+fn handle_symbol_type(generator: &CSharp, symbol: &str) -> String {
+    return generator.type_map.get(symbol).unwrap_or_else(|| {
+        match generator.classes.contains(symbol) {
+            true => generator.reserved.escape(symbol),
+            false => symbol.to_string(),
+        }
+    });
+}
@@ -0,0 +1,804 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    backend::{
+        emit_block, emit_expr, emit_statement, emit_values, hoist_out_calls, split_short_circuits,
+        Backend, OutputFile, FLOATS, INTEGERS, UNSIGNED,
+    },
+    ir::{Callee, Class, Expr, Field, Function, Interpolation, Statement},
+    reserved::ReservedWords,
+    tokens::TokenKind,
+    types::Type,
+    writer::{indent, BraceStyle, CodeStyle, CodeWriter, Indent},
+};
+
+/// methods of the script the rewritten c# calls use, only the used ones are emitted.
+/// A tab at the start of a line is one level of indentation
+fn prelude(name: &str) -> &'static str {
+    match name {
+        "_try_parse_int" => {
+            "func _try_parse_int(text: String, unsigned: bool) -> Array:
+\tif not text.is_valid_int():
+\t\treturn [false, 0]
+\tvar value := text.to_int()
+\treturn [not unsigned or value >= 0, value]
+"
+        }
+        "_try_parse_float" => {
+            "func _try_parse_float(text: String) -> Array:
+\tif not text.is_valid_float():
+\t\treturn [false, 0.0]
+\treturn [true, text.to_float()]
+"
+        }
+        // multi-dimensional arrays are nested, c# counts and iterates all of their elements
+        "_flatten" => {
+            "func _flatten(array: Array, dimensions: int) -> Array:
+\tif dimensions == 0:
+\t\treturn array
+\tvar values := []
+\tfor inner in array:
+\t\tvalues.append_array(_flatten(inner, dimensions - 1))
+\treturn values
+"
+        }
+        name => panic!("prelude function: {} doesn't exist", name),
+    }
+}
+
+pub struct GDScript {
+    pub reserved: ReservedWords,
+    /// `class_name` of the script, the classes of the program are its inner classes
+    class_name: String,
+    /// methods from the prelude the generated code calls
+    used: BTreeSet<&'static str>,
+    /// fields of the classes in declaration order, the arguments of `new` follow it
+    classes: HashMap<String, Vec<Field>>,
+    /// counter for the names of the values returned by calls with `out` arguments
+    out_calls: usize,
+    pub style: CodeStyle,
+}
+impl GDScript {
+    pub fn new(class_name: String) -> GDScript {
+        GDScript {
+            reserved: ReservedWords::gdscript(),
+            class_name,
+            used: BTreeSet::new(),
+            classes: HashMap::new(),
+            out_calls: 0,
+            style: CodeStyle {
+                indent: Indent::Tab,
+                braces: BraceStyle::SameLine,
+            },
+        }
+    }
+    fn use_prelude(&mut self, name: &'static str) -> &'static str {
+        self.used.insert(name);
+        return name;
+    }
+    /// calls with `out` arguments return an array, they are stored before the statement that uses them
+    fn hoist(&mut self, value: &Expr) -> (String, Expr) {
+        let mut value = value.clone();
+        let mut prefix = String::new();
+        for statement in split_short_circuits(&mut value, &mut self.out_calls) {
+            prefix += &emit_statement(self, &statement);
+        }
+        for call in hoist_out_calls(&mut value, &mut self.out_calls) {
+            prefix += &format!(
+                "var {}: Array = {}\n",
+                call.temporary,
+                emit_expr(self, &call.call)
+            );
+            for (i, (name, var_type)) in call.outs.iter().enumerate() {
+                let name = self.name(name);
+                prefix += &match var_type {
+                    Some(var_type) => format!(
+                        "var {}: {} = {}[{}]\n",
+                        name,
+                        self.map_type(var_type),
+                        call.temporary,
+                        i + 1
+                    ),
+                    None => format!("{} = {}[{}]\n", name, call.temporary, i + 1),
+                };
+            }
+        }
+        return (prefix, value);
+    }
+    /// `func name(a: int) -> bool:` and the indented body
+    fn handle_function(&mut self, function: &Function) -> String {
+        let parameters: Vec<String> = function
+            .parameters
+            .iter()
+            .map(|parameter| {
+                format!(
+                    "{}: {}",
+                    self.name(&parameter.name),
+                    self.map_type(&parameter.var_type)
+                )
+            })
+            .collect();
+        let output_str = match &function.output {
+            Some(var_type) => self.map_type(var_type),
+            None => "void".to_string(),
+        };
+        let body_str = emit_block(self, &function.body);
+        return format!(
+            "func {}({}) -> {}:\n{}",
+            self.name(&function.name),
+            parameters.join(", "),
+            output_str,
+            indent(self.style, &body_str)
+        );
+    }
+    /// `: int` of a declaration, nothing when the type isn't known
+    fn annotation(&self, var_type: Option<&Type>, inferred: Option<&Type>) -> String {
+        match var_type.or(inferred) {
+            Some(var_type) => return format!(": {}", self.map_type(var_type)),
+            None => return String::new(),
+        }
+    }
+    fn value_type(&self, value: &Expr) -> Option<Type> {
+        match value {
+            Expr::Variable { var_type, .. } => match var_type.as_ref()? {
+                Type::Reference { inner, .. } => Some(inner.as_ref().clone()),
+                var_type => Some(var_type.clone()),
+            },
+            Expr::Field { value, name } => {
+                let Some(Type::Symbol(class)) = self.value_type(value) else {
+                    return None;
+                };
+                let fields = self.classes.get(&class)?;
+                let field = fields.iter().find(|field| &field.name == name)?;
+                return Some(field.var_type.clone());
+            }
+            Expr::String(_) | Expr::Interpolated(_) => Some(Type::Symbol("str".to_string())),
+            Expr::Binary {
+                l,
+                operator: TokenKind::Plus,
+                r,
+            } if self.is_string(l) || self.is_string(r) => Some(Type::Symbol("str".to_string())),
+            _ => None,
+        }
+    }
+    fn is_string(&self, value: &Expr) -> bool {
+        return matches!(
+            self.value_type(value),
+            Some(Type::Symbol(symbol)) if matches!(symbol.as_str(), "str" | "String" | "string")
+        );
+    }
+    /// value a field has when `new` doesn't set it
+    fn default_value(&self, var_type: &Type) -> String {
+        let value = match var_type {
+            Type::Symbol(symbol) if INTEGERS.contains(&symbol.as_str()) => "0",
+            Type::Symbol(symbol) if FLOATS.contains(&symbol.as_str()) => "0.0",
+            Type::Symbol(symbol) if symbol == "bool" => "false",
+            Type::Symbol(symbol)
+                if matches!(symbol.as_str(), "str" | "String" | "string" | "char") =>
+            {
+                "\"\""
+            }
+            Type::Symbol(_) => "null",
+            Type::Generic { name, .. } if name == "HashMap" || name == "HashSet" => "{}",
+            Type::Reference { inner, .. } => return self.default_value(inner),
+            _ => "[]",
+        };
+        return value.to_string();
+    }
+}
+
+impl Backend for GDScript {
+    fn extension(&self) -> &'static str {
+        return "gd";
+    }
+    fn reserved(&self) -> &ReservedWords {
+        return &self.reserved;
+    }
+    fn map_type(&self, var_type: &Type) -> String {
+        return handle_type(self, var_type);
+    }
+    /// statements can't be at the top of a script, the top level lets become member variables
+    /// and the statements run in `_ready` when the node enters the scene
+    fn generate(&mut self, program: &[Statement]) -> Vec<OutputFile> {
+        for statement in program {
+            if let Statement::Class(class) = statement {
+                self.classes
+                    .insert(class.name.clone(), class.fields.clone());
+            }
+        }
+
+        let mut members = String::new();
+        let mut declarations = String::new();
+        let mut ready = String::new();
+        for statement in program {
+            match statement {
+                Statement::Let {
+                    name,
+                    var_type,
+                    inferred,
+                    constant,
+                    value,
+                    ..
+                } => {
+                    let annotation = self.annotation(var_type.as_ref(), inferred.as_ref());
+                    let name = self.name(name);
+                    match value {
+                        Some(value) if is_literal(value) => {
+                            let keyword = if *constant { "const" } else { "var" };
+                            let value_str = emit_expr(self, value);
+                            members +=
+                                &format!("{} {}{} = {}\n", keyword, name, annotation, value_str);
+                        }
+                        Some(value) => {
+                            members += &format!("var {}{}\n", name, annotation);
+                            let (prefix, value) = self.hoist(value);
+                            let value_str = emit_expr(self, &value);
+                            ready += &format!("{}{} = {}\n", prefix, name, value_str);
+                        }
+                        None => members += &format!("var {}{}\n", name, annotation),
+                    }
+                }
+                Statement::LetTuple {
+                    names,
+                    types,
+                    value,
+                    ..
+                } => {
+                    for (i, name) in names.iter().enumerate() {
+                        let var_type = types.as_ref().and_then(|types| types.get(i));
+                        let annotation = self.annotation(var_type, None);
+                        members += &format!("var {}{}\n", self.name(name), annotation);
+                    }
+                    if let Some(value) = value {
+                        ready += &self.handle_tuple(names, value, false);
+                    }
+                }
+                Statement::Function(_) | Statement::Class(_) => {
+                    declarations += &format!("\n{}", emit_statement(self, statement));
+                }
+                statement => ready += &emit_statement(self, statement),
+            }
+        }
+
+        let mut content = format!("class_name {}\nextends Node\n", self.class_name);
+        if !members.is_empty() {
+            content += &format!("\n{}", members);
+        }
+        content += &declarations;
+        if !ready.is_empty() {
+            content += &format!("\nfunc _ready() -> void:\n{}", indent(self.style, &ready));
+        }
+        for name in &self.used {
+            let mut writer = CodeWriter::new(self.style);
+            writer.template(prelude(name));
+            content += &format!("\n{}", writer.finish());
+        }
+        return vec![OutputFile {
+            extension: self.extension(),
+            content,
+        }];
+    }
+
+    fn emit_let(
+        &mut self,
+        name: &str,
+        var_type: Option<&Type>,
+        inferred: Option<&Type>,
+        _mutable: bool,
+        _constant: bool,
+        value: Option<&Expr>,
+    ) -> String {
+        let name = self.name(name);
+        let annotation = self.annotation(var_type, inferred);
+        let Some(value) = value else {
+            return format!("var {}{}\n", name, annotation);
+        };
+        let (prefix, value) = self.hoist(value);
+        return format!(
+            "{}var {}{} = {}\n",
+            prefix,
+            name,
+            annotation,
+            emit_expr(self, &value)
+        );
+    }
+    fn emit_let_tuple(
+        &mut self,
+        names: &[String],
+        types: Option<&[Type]>,
+        value: Option<&Expr>,
+    ) -> String {
+        let Some(value) = value else {
+            let mut output = String::new();
+            for (i, name) in names.iter().enumerate() {
+                let annotation = self.annotation(types.and_then(|types| types.get(i)), None);
+                output += &format!("var {}{}\n", self.name(name), annotation);
+            }
+            return output;
+        };
+        return self.handle_tuple(names, value, true);
+    }
+    fn emit_assign(&mut self, target: &Expr, operator: TokenKind, value: &Expr) -> String {
+        let (prefix, value) = self.hoist(value);
+        let target_str = emit_expr(self, target);
+        let value_str = match operator {
+            // numbers are converted when they are added to a string
+            TokenKind::PlusEquals if self.is_string(target) && !self.is_string(&value) => {
+                format!("str({})", emit_expr(self, &value))
+            }
+            _ => emit_expr(self, &value),
+        };
+        return format!(
+            "{}{} {} {}\n",
+            prefix,
+            target_str,
+            operator_text(operator),
+            value_str
+        );
+    }
+    fn emit_expression_statement(&mut self, value: &Expr) -> String {
+        let (prefix, value) = self.hoist(value);
+        return format!("{}{}\n", prefix, emit_expr(self, &value));
+    }
+    fn emit_if(
+        &mut self,
+        condition: &Expr,
+        then: &[Statement],
+        otherwise: Option<&[Statement]>,
+    ) -> String {
+        let (prefix, condition) = self.hoist(condition);
+        let condition_str = emit_expr(self, &condition);
+        let then_str = emit_block(self, then);
+        let mut output = format!(
+            "{}if {}:\n{}",
+            prefix,
+            condition_str,
+            indent(self.style, &then_str)
+        );
+
+        match otherwise {
+            Some([else_if @ Statement::If { condition, .. }]) => {
+                let else_if_str = emit_statement(self, else_if);
+                // the values of `out` arguments have to be stored before the condition
+                if self.hoist(condition).0.is_empty() {
+                    output += &format!("el{}", else_if_str);
+                } else {
+                    output += &format!("else:\n{}", indent(self.style, &else_if_str));
+                }
+            }
+            Some(otherwise) => {
+                output += &format!(
+                    "else:\n{}",
+                    indent(self.style, &emit_block(self, otherwise))
+                );
+            }
+            None => {}
+        }
+        return output;
+    }
+    fn emit_while(&mut self, condition: &Expr, body: &[Statement]) -> String {
+        let (prefix, condition) = self.hoist(condition);
+        let condition_str = emit_expr(self, &condition);
+        let body_str = emit_block(self, body);
+        if prefix.is_empty() {
+            return format!(
+                "while {}:\n{}",
+                condition_str,
+                indent(self.style, &body_str)
+            );
+        }
+        // the `out` values are read again before every check
+        let exit_str = match &condition {
+            Expr::Unary {
+                operator: TokenKind::Not,
+                value,
+            } => emit_expr(self, value),
+            _ => format!("not ({})", condition_str),
+        };
+        let inside = format!(
+            "{}if {}:\n{}{}",
+            prefix,
+            exit_str,
+            indent(self.style, "break"),
+            body_str
+        );
+        return format!("while true:\n{}", indent(self.style, &inside));
+    }
+    fn emit_for_range(
+        &mut self,
+        variable: &str,
+        from: &Expr,
+        to: &Expr,
+        body: &[Statement],
+    ) -> String {
+        let (from_prefix, from) = self.hoist(from);
+        let (to_prefix, to) = self.hoist(to);
+        let loop_str = format!(
+            "for {} in range({}, {}):",
+            self.name(variable),
+            emit_expr(self, &from),
+            emit_expr(self, &to)
+        );
+        let body_str = emit_block(self, body);
+        return format!(
+            "{}{}{}\n{}",
+            from_prefix,
+            to_prefix,
+            loop_str,
+            indent(self.style, &body_str)
+        );
+    }
+    fn emit_for_each(&mut self, variable: &str, iterable: &Expr, body: &[Statement]) -> String {
+        let (prefix, iterable) = self.hoist(iterable);
+        let mut iterable_str = emit_expr(self, &iterable);
+        if let Some(Type::Array { dimensions, .. }) = self.value_type(&iterable) {
+            if dimensions > 0 {
+                let flatten = self.use_prelude("_flatten");
+                iterable_str = format!("{}({}, {})", flatten, iterable_str, dimensions);
+            }
+        }
+        let loop_str = format!("for {} in {}:", self.name(variable), iterable_str);
+        let body_str = emit_block(self, body);
+        return format!("{}{}\n{}", prefix, loop_str, indent(self.style, &body_str));
+    }
+    // the shadowed names are already renamed, the block doesn't need its own scope
+    fn emit_scope(&mut self, body: &[Statement]) -> String {
+        return emit_block(self, body);
+    }
+    fn emit_return(&mut self, value: Option<&Expr>) -> String {
+        let Some(value) = value else {
+            return "return\n".to_string();
+        };
+        let (prefix, value) = self.hoist(value);
+        return format!("{}return {}\n", prefix, emit_expr(self, &value));
+    }
+    fn emit_break(&mut self) -> String {
+        return "break\n".to_string();
+    }
+    fn emit_continue(&mut self) -> String {
+        return "continue\n".to_string();
+    }
+    fn emit_function(&mut self, function: &Function) -> String {
+        return self.handle_function(function);
+    }
+    // inner class, `_init` takes the fields in their order
+    fn emit_class(&mut self, class: &Class) -> String {
+        let mut body_str = String::new();
+        let mut parameters = Vec::new();
+        let mut init_str = String::new();
+        for field in &class.fields {
+            let name = self.name(&field.name);
+            let field_type = self.map_type(&field.var_type);
+            body_str += &format!("var {}: {}\n", name, field_type);
+            parameters.push(format!(
+                "p_{}: {} = {}",
+                field.name,
+                field_type,
+                self.default_value(&field.var_type)
+            ));
+            init_str += &format!("{} = p_{}\n", name, field.name);
+        }
+        if !class.fields.is_empty() {
+            body_str += &format!(
+                "\nfunc _init({}) -> void:\n{}",
+                parameters.join(", "),
+                indent(self.style, &init_str)
+            );
+        }
+        for method in &class.methods {
+            body_str += &format!("\n{}", self.handle_function(method));
+        }
+        return format!(
+            "class {}:\n{}",
+            self.name(&class.name),
+            indent(self.style, &body_str)
+        );
+    }
+
+    fn emit_string(&mut self, text: &str) -> String {
+        return text.to_string();
+    }
+    // $"[{x}]" -> "[%s]" % [x]
+    fn emit_interpolated(&mut self, parts: &[Interpolation]) -> String {
+        let mut format = String::new();
+        let mut values = Vec::new();
+        for part in parts {
+            match part {
+                Interpolation::Text(text) => format += &text.replace('%', "%%"),
+                Interpolation::Value(value) => {
+                    format += "%s";
+                    values.push(emit_expr(self, value));
+                }
+            }
+        }
+        if values.is_empty() {
+            return format!("\"{}\"", format);
+        }
+        return format!("(\"{}\" % [{}])", format, values.join(", "));
+    }
+    fn emit_bool(&mut self, value: bool) -> String {
+        return value.to_string();
+    }
+    fn emit_unary(&mut self, operator: TokenKind, value: &Expr) -> String {
+        let value_str = emit_expr(self, value);
+        match operator {
+            TokenKind::Not => return format!("not {}", value_str),
+            operator => return format!("{}{}", operator_text(operator), value_str),
+        }
+    }
+    fn emit_binary(&mut self, l: &Expr, operator: TokenKind, r: &Expr) -> String {
+        let mut left_str = emit_expr(self, l);
+        let mut right_str = emit_expr(self, r);
+        // numbers are converted when they are added to a string,
+        // `/` of two ints is already truncated like in c#
+        if operator == TokenKind::Plus && self.is_string(l) != self.is_string(r) {
+            if !self.is_string(l) {
+                left_str = format!("str({})", left_str);
+            }
+            if !self.is_string(r) {
+                right_str = format!("str({})", right_str);
+            }
+        }
+        return format!("({} {} {})", left_str, operator_text(operator), right_str);
+    }
+    // tuples are arrays
+    fn emit_tuple(&mut self, values: &[Expr]) -> String {
+        return format!("[{}]", emit_values(self, values));
+    }
+    fn emit_static_field(&mut self, value: &Expr, name: &str) -> String {
+        return format!("{}.{}", emit_expr(self, value), name);
+    }
+    // map[x, y] -> map[x][y]
+    fn emit_index(&mut self, value: &Expr, indexes: &[Expr]) -> String {
+        let mut output = emit_expr(self, value);
+        for index in indexes {
+            output += &format!("[{}]", emit_expr(self, index));
+        }
+        return output;
+    }
+    fn emit_call(&mut self, callee: &Callee, arguments: &[Expr]) -> String {
+        match callee {
+            Callee::Function(name) => {
+                let arguments_str = emit_values(self, arguments);
+                return format!("{}({})", self.name(name), arguments_str);
+            }
+            Callee::Method {
+                receiver,
+                name,
+                collection,
+            } => {
+                if let Expr::External(type_name) = receiver.as_ref() {
+                    if let Some(call) = handle_static_call(self, type_name, name, arguments) {
+                        return call;
+                    }
+                }
+                return handle_method_call(self, receiver, name, collection.as_deref(), arguments);
+            }
+            Callee::Static { type_name, name } => {
+                if let Some(call) = handle_static_call(self, type_name, name, arguments) {
+                    return call;
+                }
+                let arguments_str = emit_values(self, arguments);
+                return format!("{}.{}({})", type_name, name, arguments_str);
+            }
+            Callee::Value(value) => {
+                let value_str = emit_expr(self, value);
+                return format!("{}.call({})", value_str, emit_values(self, arguments));
+            }
+        }
+    }
+    // Point { y = 2; } -> Point.new(0, 2)
+    fn emit_new(&mut self, class: &str, fields: &[(String, Expr)]) -> String {
+        let declared = self.classes.get(class).cloned().unwrap_or_default();
+        let mut arguments = Vec::new();
+        for field in &declared {
+            arguments.push(match fields.iter().find(|(name, _)| name == &field.name) {
+                Some((_, value)) => emit_expr(self, value),
+                None => self.default_value(&field.var_type),
+            });
+        }
+        return format!("{}.new({})", self.name(class), arguments.join(", "));
+    }
+    fn emit_array(&mut self, values: &[Expr]) -> String {
+        return format!("[{}]", emit_values(self, values));
+    }
+    fn emit_list(&mut self, _element_type: Option<&Type>, values: &[Expr]) -> String {
+        return format!("[{}]", emit_values(self, values));
+    }
+    fn emit_new_collection(&mut self, collection: &str, _var_type: Option<&Type>) -> String {
+        match collection {
+            "HashMap" | "HashSet" => return "{}".to_string(),
+            _ => return "[]".to_string(),
+        }
+    }
+    fn emit_out(&mut self, name: &str, _var_type: Option<&Type>) -> String {
+        panic!(
+            "out argument: {} has to be hoisted before the statement that uses it",
+            name
+        );
+    }
+    // objects are passed by reference
+    fn emit_ref(&mut self, value: &Expr, _mutable: bool) -> String {
+        return emit_expr(self, value);
+    }
+}
+
+impl GDScript {
+    /// there is no destructuring, `let (a, b) = (1, 2)` assigns every name on its own line
+    fn handle_tuple(&mut self, names: &[String], value: &Expr, declare: bool) -> String {
+        let keyword = if declare { "var " } else { "" };
+        let (mut output, value) = self.hoist(value);
+        if let Expr::Tuple(values) = &value {
+            for (name, value) in names.iter().zip(values) {
+                let value_str = emit_expr(self, value);
+                output += &format!("{}{} = {}\n", keyword, self.name(name), value_str);
+            }
+            return output;
+        }
+        self.out_calls += 1;
+        let temporary = format!("__tuple{}", self.out_calls);
+        output += &format!("var {}: Array = {}\n", temporary, emit_expr(self, &value));
+        for (i, name) in names.iter().enumerate() {
+            output += &format!("{}{} = {}[{}]\n", keyword, self.name(name), temporary, i);
+        }
+        return output;
+    }
+}
+
+/// value that can initialize a member variable before `_ready`
+fn is_literal(value: &Expr) -> bool {
+    match value {
+        Expr::Number(_) | Expr::Float(_) | Expr::Bool(_) => true,
+        Expr::String(_) => true,
+        Expr::Unary { value, .. } => is_literal(value),
+        Expr::Array(values) | Expr::List { values, .. } => values.iter().all(is_literal),
+        _ => false,
+    }
+}
+
+/// Console.WriteLine(x) -> print(x), int.Parse(x) -> x.to_int() ...
+fn handle_static_call(
+    generator: &mut GDScript,
+    type_name: &str,
+    name: &str,
+    arguments: &[Expr],
+) -> Option<String> {
+    let arguments_str = emit_values(generator, arguments);
+    let call = match (type_name, name) {
+        ("Console", "WriteLine") => format!("print({})", arguments_str),
+        ("Console", "Write") => format!("printraw({})", arguments_str),
+        ("Console", "ReadLine") => "OS.read_string_from_stdin().strip_edges()".to_string(),
+        (number, "Parse") if INTEGERS.contains(&number) => {
+            format!("({}).to_int()", arguments_str)
+        }
+        (number, "Parse") if FLOATS.contains(&number) => {
+            format!("({}).to_float()", arguments_str)
+        }
+        (number, "TryParse") if UNSIGNED.contains(&number) => {
+            let parse = generator.use_prelude("_try_parse_int");
+            format!("{}({}, true)", parse, arguments_str)
+        }
+        (number, "TryParse") if INTEGERS.contains(&number) => {
+            let parse = generator.use_prelude("_try_parse_int");
+            format!("{}({}, false)", parse, arguments_str)
+        }
+        (number, "TryParse") if FLOATS.contains(&number) => {
+            let parse = generator.use_prelude("_try_parse_float");
+            format!("{}({})", parse, arguments_str)
+        }
+        _ => return None,
+    };
+    return Some(call);
+}
+/// v.push(1) -> v.append(1), map.insert(k, v) -> map[k] = v ...
+fn handle_method_call(
+    generator: &mut GDScript,
+    receiver: &Expr,
+    name: &str,
+    collection: Option<&str>,
+    arguments: &[Expr],
+) -> String {
+    let receiver_str = emit_expr(generator, receiver);
+    let values: Vec<String> = arguments
+        .iter()
+        .map(|argument| emit_expr(generator, argument))
+        .collect();
+    let arguments_str = values.join(", ");
+    let call = |method: &str| format!("{}.{}({})", receiver_str, method, arguments_str);
+    let output = match (collection.unwrap_or(""), name) {
+        (_, "iter") | (_, "iter_mut") | (_, "into_iter") => receiver_str.clone(),
+        (_, "to_string") => format!("str({})", receiver_str),
+
+        ("Vec", "push") => call("append"),
+        ("Vec", "remove") => call("pop_at"),
+        ("Vec" | "VecDeque", "pop" | "pop_back") => call("pop_back"),
+        ("VecDeque", "push_back" | "pop_front" | "front") => call(name),
+
+        ("HashMap", "insert") if values.len() == 2 => {
+            format!("{}[{}] = {}", receiver_str, values[0], values[1])
+        }
+        ("HashMap", "get") => format!("{}[{}]", receiver_str, arguments_str),
+        ("HashMap" | "HashSet", "remove") => call("erase"),
+        ("HashSet", "insert") => format!("{}[{}] = true", receiver_str, arguments_str),
+        ("HashMap", "contains_key") | (_, "contains") if collection.is_some() => call("has"),
+
+        ("String", "push_str") => format!("{} += {}", receiver_str, arguments_str),
+        ("String", "len") => format!("{}.length()", receiver_str),
+        ("Array", "len") => match generator.value_type(receiver) {
+            Some(Type::Array { dimensions, .. }) if dimensions > 0 => {
+                let flatten = generator.use_prelude("_flatten");
+                format!("{}({}, {}).size()", flatten, receiver_str, dimensions)
+            }
+            _ => format!("{}.size()", receiver_str),
+        },
+        (_, "len") if collection.is_some() => format!("{}.size()", receiver_str),
+
+        _ => call(name),
+    };
+    return output;
+}
+/// gdscript spelling of an operator
+fn operator_text(operator: TokenKind) -> &'static str {
+    match operator {
+        TokenKind::Plus => "+",
+        TokenKind::Minus => "-",
+        TokenKind::Star => "*",
+        TokenKind::Slash => "/",
+        TokenKind::Percent => "%",
+        TokenKind::Less => "<",
+        TokenKind::LessEquals => "<=",
+        TokenKind::Greater => ">",
+        TokenKind::GreaterEquals => ">=",
+        TokenKind::Equals => "==",
+        TokenKind::NotEquals => "!=",
+        TokenKind::Or => "or",
+        TokenKind::And => "and",
+        TokenKind::Not => "not ",
+        TokenKind::Assignment => "=",
+        TokenKind::PlusEquals => "+=",
+        TokenKind::MinusEquals => "-=",
+        default => panic!("operator: {:?} doesn't have a handler", default),
+    }
+}
+
+fn handle_type(generator: &GDScript, var_type: &Type) -> String {
+    match var_type {
+        Type::Symbol(symbol) => handle_symbol_type(generator, symbol),
+        // typed arrays can't be nested, int[,] -> Array[Array]
+        Type::Array {
+            left_type,
+            dimensions,
+        } => match dimensions {
+            0 => format!("Array[{}]", handle_type(generator, left_type)),
+            _ => "Array[Array]".to_string(),
+        },
+        Type::Generic { name, arguments } => match (name.as_str(), arguments.as_slice()) {
+            ("Vec" | "VecDeque", [element]) if is_scalar(element) => {
+                format!("Array[{}]", handle_type(generator, element))
+            }
+            ("HashMap" | "HashSet", _) => "Dictionary".to_string(),
+            _ => "Array".to_string(),
+        },
+        Type::Tuple(types) if types.is_empty() => "void".to_string(),
+        Type::Tuple(_) => "Array".to_string(),
+        // objects are passed by reference
+        Type::Reference { inner, .. } => handle_type(generator, inner),
+    }
+}
+fn handle_symbol_type(generator: &GDScript, symbol: &str) -> String {
+    if INTEGERS.contains(&symbol) {
+        return "int".to_string();
+    }
+    if FLOATS.contains(&symbol) {
+        return "float".to_string();
+    }
+    match symbol {
+        "bool" => return "bool".to_string(),
+        "str" | "String" | "string" | "char" => return "String".to_string(),
+        _ => return generator.name(symbol),
+    }
+}
+/// element type a typed array can hold
+fn is_scalar(var_type: &Type) -> bool {
+    return matches!(var_type, Type::Symbol(_));
+}
@@ -0,0 +1,841 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{
+    backend::{
+        emit_block, emit_expr, emit_statement, emit_values, hoist_out_calls, split_short_circuits,
+        Backend, OutputFile, FLOATS, INTEGERS, UNSIGNED,
+    },
+    ir::{Callee, Class, Expr, Function, Interpolation, Statement},
+    reserved::ReservedWords,
+    tokens::TokenKind,
+    types::Type,
+    writer::{CodeStyle, CodeWriter},
+};
+
+/// helper functions of the generated code, only the used ones are emitted.
+/// A tab at the start of a line is one level of indentation
+fn runtime(name: &str) -> &'static str {
+    match name {
+        // multi-dimensional arrays are nested tables, c# counts and iterates all of their elements
+        "rt_array_length" => {
+            "local function rt_array_length(array, dimensions)
+\tif dimensions == 0 then
+\t\treturn #array
+\tend
+\tlocal length = 0
+\tfor _, inner in ipairs(array) do
+\t\tlength = length + rt_array_length(inner, dimensions - 1)
+\tend
+\treturn length
+end
+"
+        }
+        "rt_array_values" => {
+            "local function rt_array_values(array, dimensions)
+\treturn coroutine.wrap(function()
+\t\tlocal function walk(inner, depth)
+\t\t\tfor _, value in ipairs(inner) do
+\t\t\t\tif depth == 0 then
+\t\t\t\t\tcoroutine.yield(value)
+\t\t\t\telse
+\t\t\t\t\twalk(value, depth - 1)
+\t\t\t\tend
+\t\t\tend
+\t\tend
+\t\twalk(array, dimensions)
+\tend)
+end
+"
+        }
+        // c# rounds the division of integers towards zero, `//` rounds down
+        "rt_div" => {
+            "local function rt_div(a, b)
+\tlocal quotient = a // b
+\tif quotient < 0 and quotient * b ~= a then
+\t\tquotient = quotient + 1
+\tend
+\treturn quotient
+end
+"
+        }
+        "rt_contains" => {
+            "local function rt_contains(list, value)
+\tfor _, item in ipairs(list) do
+\t\tif item == value then
+\t\t\treturn true
+\t\tend
+\tend
+\treturn false
+end
+"
+        }
+        "rt_count" => {
+            "local function rt_count(map)
+\tlocal count = 0
+\tfor _ in pairs(map) do
+\t\tcount = count + 1
+\tend
+\treturn count
+end
+"
+        }
+        "rt_try_parse_int" => {
+            "local function rt_try_parse_int(text, unsigned)
+\tlocal value = math.tointeger(tonumber(text or \"\"))
+\tif value == nil or (unsigned and value < 0) then
+\t\treturn false, 0
+\tend
+\treturn true, value
+end
+"
+        }
+        "rt_try_parse_float" => {
+            "local function rt_try_parse_float(text)
+\tlocal value = tonumber(text or \"\")
+\tif value == nil then
+\t\treturn false, 0.0
+\tend
+\treturn true, value + 0.0
+end
+"
+        }
+        name => panic!("runtime function: {} doesn't exist", name),
+    }
+}
+
+pub struct Lua {
+    pub reserved: ReservedWords,
+    /// runtime functions the generated code calls
+    used: BTreeSet<&'static str>,
+    /// (class, field) -> type of the field
+    field_types: HashMap<(String, String), Type>,
+    /// labels of the loops around the current statement, `continue` jumps to the innermost one
+    loops: Vec<(String, bool)>,
+    /// counter for the loop labels
+    labels: usize,
+    /// counter for the names of the values returned by calls with `out` arguments
+    out_calls: usize,
+    pub style: CodeStyle,
+}
+impl Lua {
+    pub fn new() -> Lua {
+        Lua {
+            reserved: ReservedWords::lua(),
+            used: BTreeSet::new(),
+            field_types: HashMap::new(),
+            loops: Vec::new(),
+            labels: 0,
+            out_calls: 0,
+            style: CodeStyle::new(),
+        }
+    }
+    fn use_runtime(&mut self, name: &'static str) -> &'static str {
+        self.used.insert(name);
+        return name;
+    }
+    /// calls with `out` arguments return the out values after their result,
+    /// they are stored before the statement that uses them
+    fn hoist(&mut self, value: &Expr) -> (String, Expr) {
+        let mut value = value.clone();
+        let mut prefix = String::new();
+        for statement in split_short_circuits(&mut value, &mut self.out_calls) {
+            prefix += &emit_statement(self, &statement);
+        }
+        let calls = hoist_out_calls(&mut value, &mut self.out_calls);
+        let temporaries: Vec<String> = calls.iter().map(|call| call.temporary.clone()).collect();
+        unwrap_results(&mut value, &temporaries);
+        for mut call in calls {
+            unwrap_results(&mut call.call, &temporaries);
+            let call_str = emit_expr(self, &call.call);
+            let mut declared = vec![call.temporary.clone()];
+            let mut targets = vec![call.temporary.clone()];
+            for (name, var_type) in &call.outs {
+                if var_type.is_some() {
+                    declared.push(self.name(name));
+                }
+                targets.push(self.name(name));
+            }
+            if declared.len() == targets.len() {
+                prefix += &format!("local {} = {}\n", targets.join(", "), call_str);
+            } else {
+                prefix += &format!("local {}\n", declared.join(", "));
+                prefix += &format!("{} = {}\n", targets.join(", "), call_str);
+            }
+        }
+        return (prefix, value);
+    }
+    /// body of a loop, `continue` is a `goto` to a label at the end of it
+    fn loop_body(&mut self, body: &[Statement]) -> String {
+        self.labels += 1;
+        self.loops
+            .push((format!("continue_{}", self.labels), false));
+        let mut body_str = emit_block(self, body);
+        let (label, used) = self.loops.pop().expect("pushed above");
+        if used {
+            body_str += &format!("::{}::\n", label);
+        }
+        return body_str;
+    }
+    /// `local x = 1` or `x = 1` for names that are declared at the top of the file
+    fn handle_let(&mut self, name: &str, value: Option<&Expr>, local: bool) -> String {
+        let local_str = if local { "local " } else { "" };
+        let name = self.name(name);
+        let Some(value) = value else {
+            return match local {
+                true => format!("local {}\n", name),
+                false => String::new(),
+            };
+        };
+        let (prefix, value) = self.hoist(value);
+        return format!(
+            "{}{}{} = {}\n",
+            prefix,
+            local_str,
+            name,
+            emit_expr(self, &value)
+        );
+    }
+    fn value_type(&self, value: &Expr) -> Option<Type> {
+        match value {
+            Expr::Variable { var_type, .. } => match var_type.as_ref()? {
+                Type::Reference { inner, .. } => Some(inner.as_ref().clone()),
+                var_type => Some(var_type.clone()),
+            },
+            Expr::Field { value, name } => {
+                let Some(Type::Symbol(class)) = self.value_type(value) else {
+                    return None;
+                };
+                return self.field_types.get(&(class, name.clone())).cloned();
+            }
+            Expr::Index { value, indexes } => match self.value_type(value)? {
+                Type::Array {
+                    left_type,
+                    dimensions,
+                } if indexes.len() > dimensions => Some(*left_type),
+                Type::Array {
+                    left_type,
+                    dimensions,
+                } => Some(Type::Array {
+                    left_type,
+                    dimensions: dimensions - indexes.len(),
+                }),
+                Type::Generic { mut arguments, .. } => arguments.pop(),
+                _ => None,
+            },
+            Expr::Number(_) => Some(Type::Symbol("i32".to_string())),
+            Expr::String(_) | Expr::Interpolated(_) => Some(Type::Symbol("str".to_string())),
+            Expr::Binary {
+                l,
+                operator: TokenKind::Plus | TokenKind::Minus | TokenKind::Star | TokenKind::Slash,
+                r,
+            } => {
+                let left = self.value_type(l);
+                if self.is_string(l) || self.is_string(r) {
+                    return Some(Type::Symbol("str".to_string()));
+                }
+                return left.or_else(|| self.value_type(r));
+            }
+            _ => None,
+        }
+    }
+    fn is_string(&self, value: &Expr) -> bool {
+        return matches!(
+            self.value_type(value),
+            Some(Type::Symbol(symbol)) if matches!(symbol.as_str(), "str" | "String" | "string")
+        );
+    }
+    fn is_integer(&self, value: &Expr) -> bool {
+        return matches!(
+            self.value_type(value),
+            Some(Type::Symbol(symbol)) if INTEGERS.contains(&symbol.as_str())
+        );
+    }
+    /// `tostring(x)` unless it is already a string
+    fn concat_operand(&mut self, value: &Expr) -> String {
+        let value_str = emit_expr(self, value);
+        if self.is_string(value) {
+            return value_str;
+        }
+        return format!("tostring({})", value_str);
+    }
+}
+
+impl Backend for Lua {
+    fn extension(&self) -> &'static str {
+        return "lua";
+    }
+    fn reserved(&self) -> &ReservedWords {
+        return &self.reserved;
+    }
+    /// lua values aren't typed, the name of the lua type is only used for comments
+    fn map_type(&self, var_type: &Type) -> String {
+        let name = match var_type {
+            Type::Symbol(symbol) if INTEGERS.contains(&symbol.as_str()) => "integer",
+            Type::Symbol(symbol) if FLOATS.contains(&symbol.as_str()) => "number",
+            Type::Symbol(symbol) if symbol == "bool" => "boolean",
+            Type::Symbol(symbol) if matches!(symbol.as_str(), "str" | "String" | "string") => {
+                "string"
+            }
+            Type::Symbol(symbol) => return self.name(symbol),
+            Type::Reference { inner, .. } => return self.map_type(inner),
+            _ => "table",
+        };
+        return name.to_string();
+    }
+    /// the names of the top level are declared first so functions can use the ones declared after them
+    fn generate(&mut self, program: &[Statement]) -> Vec<OutputFile> {
+        let mut names = Vec::new();
+        for statement in program {
+            match statement {
+                Statement::Let { name, .. } => names.push(self.name(name)),
+                Statement::LetTuple { names: tuple, .. } => {
+                    names.extend(tuple.iter().map(|name| self.name(name)))
+                }
+                Statement::Function(function) => names.push(self.name(&function.name)),
+                Statement::Class(class) => {
+                    names.push(self.name(&class.name));
+                    for field in &class.fields {
+                        self.field_types.insert(
+                            (class.name.clone(), field.name.clone()),
+                            field.var_type.clone(),
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut code = String::new();
+        if !names.is_empty() {
+            code += &format!("local {}\n", names.join(", "));
+        }
+        for statement in program {
+            code += &match statement {
+                Statement::Let { name, value, .. } => self.handle_let(name, value.as_ref(), false),
+                Statement::LetTuple { names, value, .. } => {
+                    let tuple = self.emit_let_tuple(names, None, value.as_ref());
+                    tuple.strip_prefix("local ").unwrap_or(&tuple).to_string()
+                }
+                statement => emit_statement(self, statement),
+            };
+        }
+
+        let mut writer = CodeWriter::new(self.style);
+        for name in &self.used {
+            writer.template(runtime(name));
+        }
+        let mut content = writer.finish();
+        content += &code;
+        return vec![OutputFile {
+            extension: self.extension(),
+            content,
+        }];
+    }
+
+    fn emit_let(
+        &mut self,
+        name: &str,
+        _var_type: Option<&Type>,
+        _inferred: Option<&Type>,
+        _mutable: bool,
+        _constant: bool,
+        value: Option<&Expr>,
+    ) -> String {
+        return self.handle_let(name, value, true);
+    }
+    // let (a, b) = (1, 2) -> local a, b = 1, 2
+    fn emit_let_tuple(
+        &mut self,
+        names: &[String],
+        _types: Option<&[Type]>,
+        value: Option<&Expr>,
+    ) -> String {
+        let names: Vec<String> = names.iter().map(|name| self.name(name)).collect();
+        let Some(value) = value else {
+            return format!("local {}\n", names.join(", "));
+        };
+        let (prefix, value) = self.hoist(value);
+        let value_str = match &value {
+            Expr::Tuple(values) => emit_values(self, values),
+            // tuples are tables
+            value => format!("table.unpack({})", emit_expr(self, value)),
+        };
+        return format!("{}local {} = {}\n", prefix, names.join(", "), value_str);
+    }
+    fn emit_assign(&mut self, target: &Expr, operator: TokenKind, value: &Expr) -> String {
+        let (prefix, value) = self.hoist(value);
+        let target_str = emit_expr(self, target);
+        // lua doesn't have compound assignments
+        let value_str = match operator {
+            TokenKind::Assignment => emit_expr(self, &value),
+            TokenKind::PlusEquals if self.is_string(target) => {
+                format!("{} .. {}", target_str, self.concat_operand(&value))
+            }
+            TokenKind::PlusEquals => format!("{} + {}", target_str, emit_expr(self, &value)),
+            TokenKind::MinusEquals => format!("{} - {}", target_str, emit_expr(self, &value)),
+            default => panic!("assignment: {:?} doesn't have a handler", default),
+        };
+        return format!("{}{} = {}\n", prefix, target_str, value_str);
+    }
+    fn emit_expression_statement(&mut self, value: &Expr) -> String {
+        let (prefix, value) = self.hoist(value);
+        return format!("{}{}\n", prefix, emit_expr(self, &value));
+    }
+    fn emit_if(
+        &mut self,
+        condition: &Expr,
+        then: &[Statement],
+        otherwise: Option<&[Statement]>,
+    ) -> String {
+        let (prefix, condition) = self.hoist(condition);
+        let condition_str = emit_expr(self, &condition);
+        let then_str = emit_block(self, then);
+        let mut writer = CodeWriter::new(self.style);
+        writer.lines(&prefix);
+        writer.line(&format!("if {} then", condition_str));
+        writer.nested(&then_str);
+
+        match otherwise {
+            Some([else_if @ Statement::If { condition, .. }])
+                if self.hoist(condition).0.is_empty() =>
+            {
+                // `if` of the else-if without its own `end`
+                let else_if_str = emit_statement(self, else_if);
+                let else_if_str = else_if_str.strip_suffix("end\n").unwrap_or(&else_if_str);
+                writer.lines(&format!("else{}", else_if_str));
+            }
+            Some(otherwise) => {
+                let else_str = emit_block(self, otherwise);
+                writer.line("else");
+                writer.nested(&else_str);
+            }
+            None => {}
+        }
+        writer.line("end");
+        return writer.finish();
+    }
+    fn emit_while(&mut self, condition: &Expr, body: &[Statement]) -> String {
+        let (prefix, condition) = self.hoist(condition);
+        let condition_str = emit_expr(self, &condition);
+        let body_str = self.loop_body(body);
+        let mut writer = CodeWriter::new(self.style);
+        if prefix.is_empty() {
+            writer.line(&format!("while {} do", condition_str));
+            writer.nested(&body_str);
+            writer.line("end");
+            return writer.finish();
+        }
+        // the `out` values are read again before every check
+        let exit_str = match &condition {
+            Expr::Unary {
+                operator: TokenKind::Not,
+                value,
+            } => emit_expr(self, value),
+            _ => format!("not ({})", condition_str),
+        };
+        writer.line("while true do");
+        writer.indent();
+        writer.lines(&prefix);
+        writer.line(&format!("if {} then", exit_str));
+        writer.nested("break");
+        writer.line("end");
+        writer.lines(&body_str);
+        writer.dedent();
+        writer.line("end");
+        return writer.finish();
+    }
+    // for i in a..b -> for i = a, b - 1, the end of a numeric for is inclusive
+    fn emit_for_range(
+        &mut self,
+        variable: &str,
+        from: &Expr,
+        to: &Expr,
+        body: &[Statement],
+    ) -> String {
+        let (from_prefix, from) = self.hoist(from);
+        let (to_prefix, to) = self.hoist(to);
+        let from_str = emit_expr(self, &from);
+        let to_str = match &to {
+            Expr::Number(number) => (number - 1).to_string(),
+            to => format!("{} - 1", emit_expr(self, to)),
+        };
+        let loop_str = format!("for {} = {}, {} do", self.name(variable), from_str, to_str);
+        let body_str = self.loop_body(body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.lines(&from_prefix);
+        writer.lines(&to_prefix);
+        writer.line(&loop_str);
+        writer.nested(&body_str);
+        writer.line("end");
+        return writer.finish();
+    }
+    fn emit_for_each(&mut self, variable: &str, iterable: &Expr, body: &[Statement]) -> String {
+        let (prefix, iterable) = self.hoist(iterable);
+        let iterable_str = emit_expr(self, &iterable);
+        let variable = self.name(variable);
+        let loop_str = match self.value_type(&iterable) {
+            Some(Type::Array { dimensions, .. }) if dimensions > 0 => {
+                let values = self.use_runtime("rt_array_values");
+                format!(
+                    "for {} in {}({}, {}) do",
+                    variable, values, iterable_str, dimensions
+                )
+            }
+            // the keys of a map and the values of a set
+            Some(Type::Generic { name, .. }) if name == "HashMap" || name == "HashSet" => {
+                format!("for {} in pairs({}) do", variable, iterable_str)
+            }
+            _ => format!("for _, {} in ipairs({}) do", variable, iterable_str),
+        };
+        let body_str = self.loop_body(body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.lines(&prefix);
+        writer.line(&loop_str);
+        writer.nested(&body_str);
+        writer.line("end");
+        return writer.finish();
+    }
+    fn emit_scope(&mut self, body: &[Statement]) -> String {
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.line("do");
+        writer.nested(&body_str);
+        writer.line("end");
+        return writer.finish();
+    }
+    fn emit_return(&mut self, value: Option<&Expr>) -> String {
+        let Some(value) = value else {
+            return "return\n".to_string();
+        };
+        let (prefix, value) = self.hoist(value);
+        return format!("{}return {}\n", prefix, emit_expr(self, &value));
+    }
+    fn emit_break(&mut self) -> String {
+        return "break\n".to_string();
+    }
+    fn emit_continue(&mut self) -> String {
+        let Some((label, used)) = self.loops.last_mut() else {
+            panic!("continue outside of a loop");
+        };
+        *used = true;
+        return format!("goto {}\n", label);
+    }
+    // the name is declared at the top of the file
+    fn emit_function(&mut self, function: &Function) -> String {
+        let parameters: Vec<String> = function
+            .parameters
+            .iter()
+            .map(|parameter| self.name(&parameter.name))
+            .collect();
+        let outer = std::mem::take(&mut self.loops);
+        let body_str = emit_block(self, &function.body);
+        self.loops = outer;
+        let mut writer = CodeWriter::new(self.style);
+        writer.line(&format!(
+            "function {}({})",
+            self.name(&function.name),
+            parameters.join(", ")
+        ));
+        writer.nested(&body_str);
+        writer.line("end");
+        return writer.finish();
+    }
+    // Point = {} with `Point.new` that sets the metatable of the fields
+    fn emit_class(&mut self, class: &Class) -> String {
+        let name = self.name(&class.name);
+        let mut writer = CodeWriter::new(self.style);
+        writer.line(&format!("{} = {{}}", name));
+        writer.line(&format!("{}.__index = {}", name, name));
+        writer.line(&format!("function {}.new(fields)", name));
+        writer.nested(&format!("return setmetatable(fields, {})", name));
+        writer.line("end");
+        for method in &class.methods {
+            let method_str = self.emit_function(method);
+            writer.lines(&method_str.replacen("function ", &format!("function {}:", name), 1));
+        }
+        return writer.finish();
+    }
+
+    fn emit_number(&mut self, value: i128) -> String {
+        return value.to_string();
+    }
+    fn emit_string(&mut self, text: &str) -> String {
+        return text.to_string();
+    }
+    // $"[{x}]" -> "[" .. tostring(x) .. "]"
+    fn emit_interpolated(&mut self, parts: &[Interpolation]) -> String {
+        let mut parts_str = Vec::new();
+        for part in parts {
+            match part {
+                Interpolation::Text(text) => parts_str.push(format!("\"{}\"", text)),
+                Interpolation::Value(value) => {
+                    parts_str.push(format!("tostring({})", emit_expr(self, value)))
+                }
+            }
+        }
+        if parts_str.is_empty() {
+            return "\"\"".to_string();
+        }
+        return format!("({})", parts_str.join(" .. "));
+    }
+    fn emit_bool(&mut self, value: bool) -> String {
+        return value.to_string();
+    }
+    fn emit_unary(&mut self, operator: TokenKind, value: &Expr) -> String {
+        let value_str = emit_expr(self, value);
+        match operator {
+            TokenKind::Not => return format!("not {}", value_str),
+            operator => return format!("{}{}", operator_text(operator), value_str),
+        }
+    }
+    fn emit_binary(&mut self, l: &Expr, operator: TokenKind, r: &Expr) -> String {
+        // strings are joined with `..`
+        if operator == TokenKind::Plus && (self.is_string(l) || self.is_string(r)) {
+            let left_str = self.concat_operand(l);
+            let right_str = self.concat_operand(r);
+            return format!("({} .. {})", left_str, right_str);
+        }
+        let integers = self.is_integer(l) && self.is_integer(r);
+        let left_str = emit_expr(self, l);
+        let right_str = emit_expr(self, r);
+        match operator {
+            TokenKind::Slash if integers => {
+                let div = self.use_runtime("rt_div");
+                return format!("{}({}, {})", div, left_str, right_str);
+            }
+            // `%` rounds down like `//`
+            TokenKind::Percent => return format!("math.fmod({}, {})", left_str, right_str),
+            operator => {
+                return format!("({} {} {})", left_str, operator_text(operator), right_str);
+            }
+        }
+    }
+    // tuples are tables
+    fn emit_tuple(&mut self, values: &[Expr]) -> String {
+        return format!("{{{}}}", emit_values(self, values));
+    }
+    fn emit_static_field(&mut self, value: &Expr, name: &str) -> String {
+        return format!("{}.{}", emit_expr(self, value), name);
+    }
+    // arrays and lists start at 1, map[x, y] -> map[x + 1][y + 1]
+    fn emit_index(&mut self, value: &Expr, indexes: &[Expr]) -> String {
+        let keyed = matches!(
+            self.value_type(value),
+            Some(Type::Generic { name, .. }) if name == "HashMap"
+        );
+        let mut output = emit_expr(self, value);
+        for index in indexes {
+            let index_str = match keyed {
+                true => emit_expr(self, index),
+                false => shifted(self, index),
+            };
+            output += &format!("[{}]", index_str);
+        }
+        return output;
+    }
+    fn emit_call(&mut self, callee: &Callee, arguments: &[Expr]) -> String {
+        match callee {
+            Callee::Function(name) => {
+                let arguments_str = emit_values(self, arguments);
+                return format!("{}({})", self.name(name), arguments_str);
+            }
+            Callee::Method {
+                receiver,
+                name,
+                collection,
+            } => {
+                if let Expr::External(type_name) = receiver.as_ref() {
+                    if let Some(call) = handle_static_call(self, type_name, name, arguments) {
+                        return call;
+                    }
+                }
+                return handle_method_call(self, receiver, name, collection.as_deref(), arguments);
+            }
+            Callee::Static { type_name, name } => {
+                if let Some(call) = handle_static_call(self, type_name, name, arguments) {
+                    return call;
+                }
+                let arguments_str = emit_values(self, arguments);
+                return format!("{}.{}({})", type_name, name, arguments_str);
+            }
+            Callee::Value(value) => {
+                let value_str = emit_expr(self, value);
+                return format!("{}({})", value_str, emit_values(self, arguments));
+            }
+        }
+    }
+    // Point { x = 1; y = 2; } -> Point.new({ x = 1, y = 2 })
+    fn emit_new(&mut self, class: &str, fields: &[(String, Expr)]) -> String {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(name, value)| format!("{} = {}", self.name(name), emit_expr(self, value)))
+            .collect();
+        return format!("{}.new({{ {} }})", self.name(class), fields.join(", "));
+    }
+    fn emit_array(&mut self, values: &[Expr]) -> String {
+        return format!("{{{}}}", emit_values(self, values));
+    }
+    fn emit_list(&mut self, _element_type: Option<&Type>, values: &[Expr]) -> String {
+        return format!("{{{}}}", emit_values(self, values));
+    }
+    fn emit_new_collection(&mut self, _collection: &str, _var_type: Option<&Type>) -> String {
+        return "{}".to_string();
+    }
+    fn emit_out(&mut self, name: &str, _var_type: Option<&Type>) -> String {
+        panic!(
+            "out argument: {} has to be hoisted before the statement that uses it",
+            name
+        );
+    }
+    // tables are passed by reference
+    fn emit_ref(&mut self, value: &Expr, _mutable: bool) -> String {
+        return emit_expr(self, value);
+    }
+}
+
+/// 0-based index of the source -> 1-based index of a lua table
+fn shifted(generator: &mut Lua, index: &Expr) -> String {
+    match index {
+        Expr::Number(number) => return (number + 1).to_string(),
+        index => return format!("{} + 1", emit_expr(generator, index)),
+    }
+}
+
+/// `__out1[0]` -> `__out1`, the result is the first of the returned values
+fn unwrap_results(value: &mut Expr, temporaries: &[String]) {
+    if let Expr::Index { value: inner, .. } = value {
+        if let Expr::External(name) = inner.as_ref() {
+            if temporaries.contains(name) {
+                *value = Expr::External(name.clone());
+                return;
+            }
+        }
+    }
+    for child in value.children_mut() {
+        unwrap_results(child, temporaries);
+    }
+}
+
+/// Console.WriteLine(x) -> print(x), uint.TryParse(s) -> rt_try_parse_int(s, true) ...
+fn handle_static_call(
+    generator: &mut Lua,
+    type_name: &str,
+    name: &str,
+    arguments: &[Expr],
+) -> Option<String> {
+    let arguments_str = emit_values(generator, arguments);
+    let call = match (type_name, name) {
+        ("Console", "WriteLine") => format!("print({})", arguments_str),
+        ("Console", "Write") => format!("io.write({})", arguments_str),
+        ("Console", "ReadLine") => "io.read(\"l\")".to_string(),
+        (number, "Parse") if INTEGERS.contains(&number) => {
+            format!("math.tointeger(tonumber({}))", arguments_str)
+        }
+        (number, "Parse") if FLOATS.contains(&number) => {
+            format!("tonumber({})", arguments_str)
+        }
+        (number, "TryParse") if UNSIGNED.contains(&number) => {
+            let parse = generator.use_runtime("rt_try_parse_int");
+            format!("{}({}, true)", parse, arguments_str)
+        }
+        (number, "TryParse") if INTEGERS.contains(&number) => {
+            let parse = generator.use_runtime("rt_try_parse_int");
+            format!("{}({}, false)", parse, arguments_str)
+        }
+        (number, "TryParse") if FLOATS.contains(&number) => {
+            let parse = generator.use_runtime("rt_try_parse_float");
+            format!("{}({})", parse, arguments_str)
+        }
+        _ => return None,
+    };
+    return Some(call);
+}
+/// v.push(1) -> table.insert(v, 1), map.insert(k, v) -> map[k] = v ...
+fn handle_method_call(
+    generator: &mut Lua,
+    receiver: &Expr,
+    name: &str,
+    collection: Option<&str>,
+    arguments: &[Expr],
+) -> String {
+    let receiver_str = emit_expr(generator, receiver);
+    let values: Vec<String> = arguments
+        .iter()
+        .map(|argument| emit_expr(generator, argument))
+        .collect();
+    let arguments_str = values.join(", ");
+    let output = match (collection.unwrap_or(""), name, values.as_slice()) {
+        (_, "iter" | "iter_mut" | "into_iter", _) => receiver_str,
+        (_, "to_string", _) => format!("tostring({})", receiver_str),
+
+        ("Vec" | "VecDeque", "push" | "push_back", [value]) => {
+            format!("table.insert({}, {})", receiver_str, value)
+        }
+        ("Vec", "insert", [_, value]) => {
+            let index = shifted(generator, &arguments[0]);
+            format!("table.insert({}, {}, {})", receiver_str, index, value)
+        }
+        ("Vec", "remove", [_]) => {
+            let index = shifted(generator, &arguments[0]);
+            format!("table.remove({}, {})", receiver_str, index)
+        }
+        ("Vec" | "VecDeque", "pop" | "pop_back", []) => format!("table.remove({})", receiver_str),
+        ("VecDeque", "pop_front", []) => format!("table.remove({}, 1)", receiver_str),
+        ("VecDeque", "front", []) => format!("{}[1]", receiver_str),
+        ("Vec" | "VecDeque" | "Array", "contains", [value]) => {
+            let contains = generator.use_runtime("rt_contains");
+            format!("{}({}, {})", contains, receiver_str, value)
+        }
+
+        ("HashMap", "insert", [key, value]) => format!("{}[{}] = {}", receiver_str, key, value),
+        ("HashMap", "get", [key]) => format!("{}[{}]", receiver_str, key),
+        ("HashMap" | "HashSet", "remove", [key]) => format!("{}[{}] = nil", receiver_str, key),
+        ("HashMap", "contains_key", [key]) | ("HashSet", "contains", [key]) => {
+            format!("({}[{}] ~= nil)", receiver_str, key)
+        }
+        ("HashSet", "insert", [value]) => format!("{}[{}] = true", receiver_str, value),
+        ("HashMap" | "HashSet", "len", []) => {
+            let count = generator.use_runtime("rt_count");
+            format!("{}({})", count, receiver_str)
+        }
+        ("HashMap" | "HashSet", "is_empty", []) => format!("(next({}) == nil)", receiver_str),
+
+        ("String", "push_str", [value]) => {
+            format!("{} = {} .. {}", receiver_str, receiver_str, value)
+        }
+        ("String", "contains", [value]) => {
+            format!("(string.find({}, {}, 1, true) ~= nil)", receiver_str, value)
+        }
+        ("Array", "len", []) => match generator.value_type(receiver) {
+            Some(Type::Array { dimensions, .. }) if dimensions > 0 => {
+                let length = generator.use_runtime("rt_array_length");
+                format!("{}({}, {})", length, receiver_str, dimensions)
+            }
+            _ => format!("#{}", receiver_str),
+        },
+        (_, "len", []) if collection.is_some() => format!("#{}", receiver_str),
+        (_, "is_empty", []) if collection.is_some() => format!("(#{} == 0)", receiver_str),
+
+        // methods of classes are called with the instance as `self`
+        _ => format!("{}:{}({})", receiver_str, name, arguments_str),
+    };
+    return output;
+}
+fn operator_text(operator: TokenKind) -> &'static str {
+    match operator {
+        TokenKind::Plus => "+",
+        TokenKind::Minus => "-",
+        TokenKind::Star => "*",
+        TokenKind::Slash => "/",
+        TokenKind::Less => "<",
+        TokenKind::LessEquals => "<=",
+        TokenKind::Greater => ">",
+        TokenKind::GreaterEquals => ">=",
+        TokenKind::Equals => "==",
+        TokenKind::NotEquals => "~=",
+        TokenKind::Or => "or",
+        TokenKind::And => "and",
+        TokenKind::Not => "not ",
+        default => panic!("operator: {:?} doesn't have a handler", default),
+    }
+}
@@ -0,0 +1,512 @@
+use std::{cell::RefCell, collections::VecDeque};
+
+use crate::{
+    expression::{Expression, InterpolationPart},
+    lexer,
+    parser::{self, Dialect},
+    tokens::{Span, Token, TokenKind},
+    visitor::{walk_expression_mut, VisitorMut},
+    writer::{BraceStyle, CodeStyle, CodeWriter},
+};
+
+/// Writes the ast back as source in one canonical layout, the declarations use the syntax of `dialect`.
+/// Comments are not part of the ast, they are put before the statement that follows them
+/// or at the end of the line of the statement they are written after. A comment at the end
+/// of a block moves after the block
+pub fn print_source(
+    expressions: &[Expression],
+    comments: Vec<Token>,
+    dialect: Dialect,
+    style: CodeStyle,
+) -> String {
+    let printer = Printer {
+        dialect,
+        style,
+        comments: RefCell::new(comments.into()),
+    };
+    let mut output = printer.block(expressions);
+    output += &printer.leading_comments(u16::MAX);
+    return output;
+}
+/// `// ...` comments of the source in the order they are written
+pub fn source_comments(source: &str) -> Vec<Token> {
+    let tokens = lexer::tokenize(
+        source.to_string(),
+        vec![TokenKind::Tab, TokenKind::WhiteSpace, TokenKind::NextLine],
+    );
+    return tokens
+        .into_iter()
+        .filter(|token| token.kind == TokenKind::Comment)
+        .collect();
+}
+
+/// Parses the printed source again and panics when the ast is not the one it was printed from
+pub fn check_round_trip(expressions: &[Expression], source: &str, dialect: Dialect) {
+    let tokens = lexer::tokenize(
+        source.to_string(),
+        vec![
+            TokenKind::Tab,
+            TokenKind::WhiteSpace,
+            TokenKind::Comment,
+            TokenKind::NextLine,
+        ],
+    );
+    let mut reparsed = parser::parse(tokens, dialect);
+    let mut expected = expressions.to_vec();
+    // the positions change with the layout
+    ClearSpans.visit_block_mut(&mut expected);
+    ClearSpans.visit_block_mut(&mut reparsed);
+
+    let expected = format!("{:?}", expected);
+    let reparsed = format!("{:?}", reparsed);
+    if expected != reparsed {
+        let position = expected
+            .chars()
+            .zip(reparsed.chars())
+            .position(|(a, b)| a != b)
+            .unwrap_or(expected.len().min(reparsed.len()));
+        let start = position.saturating_sub(40);
+        panic!(
+            "formatted source parses into a different ast\nexpected: ...{}\nfound:    ...{}",
+            &expected[start..(position + 40).min(expected.len())],
+            &reparsed[start..(position + 40).min(reparsed.len())]
+        );
+    }
+}
+
+struct ClearSpans;
+impl VisitorMut for ClearSpans {
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        match expression {
+            Expression::Identifier { span, .. }
+            | Expression::VariableDeclaration { span, .. }
+            | Expression::Borrow { span, .. }
+            | Expression::Class { span, .. }
+            | Expression::ClassProperty { span, .. }
+            | Expression::ClassInstantiation { span, .. }
+            | Expression::Function { span, .. }
+            | Expression::FunctionProperty { span, .. }
+            | Expression::MemberExpr { span, .. }
+            | Expression::Return { span, .. }
+            | Expression::If { span, .. }
+            | Expression::Else { span, .. }
+            | Expression::While { span, .. }
+            | Expression::For {
+                iterator_span: span,
+                ..
+            }
+            | Expression::Out { span, .. }
+            | Expression::Attribute { span, .. } => *span = Span::default(),
+            Expression::Prefix {
+                prefix: operator, ..
+            }
+            | Expression::Assignment { operator, .. }
+            | Expression::Binary { operator, .. } => {
+                operator.line = 0;
+                operator.column = 0;
+            }
+            _ => {}
+        }
+        walk_expression_mut(self, expression);
+    }
+}
+
+struct Printer {
+    dialect: Dialect,
+    style: CodeStyle,
+    /// comments that aren't printed yet
+    comments: RefCell<VecDeque<Token>>,
+}
+impl Printer {
+    /// comments written before `line`, each on its own line
+    fn leading_comments(&self, line: u16) -> String {
+        let mut comments = self.comments.borrow_mut();
+        let mut output = String::new();
+        while let Some(comment) = comments.pop_front_if(|comment| comment.line < line) {
+            output += &format!("{}\n", comment.value.trim_end());
+        }
+        return output;
+    }
+    /// comments at the end of `line`
+    fn trailing_comments(&self, line: Option<u16>) -> String {
+        let mut comments = self.comments.borrow_mut();
+        let mut output = String::new();
+        while let Some(comment) = comments.pop_front_if(|comment| Some(comment.line) == line) {
+            output += &format!(" {}", comment.value.trim_end());
+        }
+        return output;
+    }
+    /// statements on their own lines, `;` and `,` stay on the line of the statement before them.
+    /// The lines of a nested block are indented by the `CodeWriter` of the block around it
+    fn block(&self, expressions: &[Expression]) -> String {
+        let mut output = String::new();
+        let mut line_open = false;
+        // line of the source statement that is printed on the open line
+        let mut source_line = None;
+        for expression in expressions {
+            match expression {
+                Expression::Keyword(TokenKind::SemiColon | TokenKind::Comma) if line_open => {
+                    output += &self.expression(expression);
+                }
+                // } else {
+                Expression::Else { .. }
+                    if line_open
+                        && output.ends_with('}')
+                        && self.style.braces == BraceStyle::SameLine =>
+                {
+                    output += &format!(" {}", self.expression(expression));
+                }
+                _ => {
+                    if line_open {
+                        output += &self.trailing_comments(source_line);
+                        output.push('\n');
+                    }
+                    source_line = expression.span().map(|span| span.line);
+                    if let Some(line) = source_line {
+                        output += &self.leading_comments(line);
+                    }
+                    output += &self.expression(expression);
+                    line_open = true;
+                }
+            }
+        }
+        if line_open {
+            output += &self.trailing_comments(source_line);
+            output.push('\n');
+        }
+        return output;
+    }
+    /// `header {...}` of functions, loops and branches
+    fn body(&self, header: &str, expressions: &[Expression]) -> String {
+        if expressions.is_empty() {
+            return format!("{} {{}}", header);
+        }
+        let mut writer = CodeWriter::new(self.style);
+        writer.block(header, &self.block(expressions));
+        // the statement is on the open line of the block around it
+        return writer.finish().trim_end().to_string();
+    }
+    /// values of an array or a class instantiation on one line
+    fn inline(&self, expressions: &[Expression]) -> String {
+        let mut output = String::new();
+        for expression in expressions {
+            match expression {
+                Expression::Keyword(TokenKind::SemiColon | TokenKind::Comma) => {
+                    output += &self.expression(expression);
+                }
+                _ => {
+                    if !output.is_empty() {
+                        output.push(' ');
+                    }
+                    output += &self.expression(expression);
+                }
+            }
+        }
+        return output;
+    }
+    fn values(&self, expressions: &[Expression]) -> String {
+        return expressions
+            .iter()
+            .map(|expression| self.expression(expression))
+            .collect::<Vec<_>>()
+            .join(", ");
+    }
+
+    fn expression(&self, expression: &Expression) -> String {
+        match expression {
+            Expression::Number(value) => value.to_string(),
+            Expression::Float(value) => format!("{:?}", value),
+            Expression::String(text) => text.clone(),
+            Expression::Interpolated(parts) => {
+                let mut output = String::from("$\"");
+                for part in parts {
+                    match part {
+                        InterpolationPart::Text(text) => {
+                            output += &text.replace('{', "{{").replace('}', "}}")
+                        }
+                        InterpolationPart::Value(value) => {
+                            output += &format!("{{{}}}", self.expression(value))
+                        }
+                    }
+                }
+                output.push('"');
+                output
+            }
+            Expression::Identifier { name, .. } => name.clone(),
+            Expression::Keyword(kind) => keyword_text(*kind).to_string(),
+            Expression::Prefix { prefix, value } => {
+                join_tokens(&prefix.value, &self.expression(value))
+            }
+            Expression::Assignment {
+                target,
+                operator,
+                value,
+            } => format!(
+                "{} {} {}",
+                self.expression(target),
+                operator.value,
+                self.expression(value)
+            ),
+            Expression::Binary { l, operator, r } => format!(
+                "{} {} {}",
+                self.expression(l),
+                operator.value,
+                self.expression(r)
+            ),
+            Expression::VariableDeclaration {
+                var_type,
+                name,
+                mutable,
+                ..
+            } => {
+                let mut_str = if *mutable { "mut " } else { "" };
+                match (var_type, self.dialect) {
+                    (Some(var_type), Dialect::Legacy) => {
+                        format!("let {}{} {}", mut_str, var_type, name)
+                    }
+                    (Some(var_type), _) => format!("let {}{}: {}", mut_str, name, var_type),
+                    (None, _) => format!("let {}{}", mut_str, name),
+                }
+            }
+            Expression::TupleDeclaration { var_type, bindings } => {
+                let bindings: Vec<String> = bindings
+                    .iter()
+                    .map(|binding| match binding {
+                        Expression::VariableDeclaration { name, mutable, .. } => {
+                            format!("{}{}", if *mutable { "mut " } else { "" }, name)
+                        }
+                        binding => self.expression(binding),
+                    })
+                    .collect();
+                match var_type {
+                    Some(var_type) => format!("let ({}): {}", bindings.join(", "), var_type),
+                    None => format!("let ({})", bindings.join(", ")),
+                }
+            }
+            Expression::Grouping(value) => format!("({})", self.expression(value)),
+            // (a,) is a tuple, (a) a grouping
+            Expression::Tuple(values) if values.len() == 1 => {
+                format!("({},)", self.expression(&values[0]))
+            }
+            Expression::Tuple(values) => format!("({})", self.values(values)),
+            Expression::Borrow { value, mutable, .. } => {
+                let borrow = if *mutable { "&mut " } else { "&" };
+                join_tokens(borrow, &self.expression(value))
+            }
+            Expression::Class {
+                public,
+                name,
+                properties,
+                functions,
+                ..
+            } => {
+                let header = match (public, self.dialect) {
+                    (true, Dialect::Legacy) => format!("class pub {}", name),
+                    (true, _) => format!("pub class {}", name),
+                    (false, _) => format!("class {}", name),
+                };
+                let mut members = properties.clone();
+                members.extend(functions.iter().cloned());
+                self.body(&header, &members)
+            }
+            Expression::ClassProperty {
+                var_name, var_type, ..
+            } => format!("{} {};", var_type, var_name),
+            Expression::ClassInstantiation {
+                name, properties, ..
+            } => match properties.is_empty() {
+                true => format!("{} {{}}", name),
+                false => format!("{} {{ {} }}", name, self.inline(properties)),
+            },
+            Expression::ArrayInitialization { properties } => {
+                format!("{{{}}}", self.inline(properties))
+            }
+            Expression::Function {
+                name,
+                properties,
+                public,
+                output,
+                inside,
+                ..
+            } => {
+                let header = match (public, self.dialect) {
+                    (true, Dialect::Legacy) => format!("fn pub {}", name),
+                    (true, _) => format!("pub fn {}", name),
+                    (false, _) => format!("fn {}", name),
+                };
+                let output_str = match output {
+                    Some(output) => format!(" -> {}", output),
+                    None => String::new(),
+                };
+                let signature = format!("{}({}){}", header, self.values(properties), output_str);
+                self.body(&signature, inside)
+            }
+            Expression::FunctionProperty {
+                var_name,
+                var_type,
+                mutable,
+                ..
+            } => match self.dialect {
+                // legacy properties are always mutable
+                Dialect::Legacy => format!("{} {}", var_type, var_name),
+                _ if *mutable => format!("mut {}: {}", var_name, var_type),
+                _ => format!("{}: {}", var_name, var_type),
+            },
+            Expression::MemberExpr { member, name, .. } => {
+                format!("{}.{}", self.expression(member), name)
+            }
+            Expression::StaticMember { member, name } => {
+                format!("{}::{}", self.expression(member), name)
+            }
+            Expression::MacroCall { name, values } => {
+                format!("{}![{}]", name, self.values(values))
+            }
+            // `return;` parses the `;` as the value
+            Expression::Return { value, .. } => match value.as_ref() {
+                Expression::Keyword(TokenKind::SemiColon) => "return;".to_string(),
+                value => format!("return {}", self.expression(value)),
+            },
+            Expression::If {
+                condition, inside, ..
+            } => self.body(&format!("if {}", self.expression(condition)), inside),
+            Expression::Else {
+                condition, inside, ..
+            } => match condition {
+                Some(condition) => {
+                    self.body(&format!("else if {}", self.expression(condition)), inside)
+                }
+                None => self.body("else", inside),
+            },
+            Expression::IndexArray { left, indexes } => {
+                format!("{}[{}]", self.expression(left), self.values(indexes))
+            }
+            Expression::While {
+                condition, inside, ..
+            } => self.body(&format!("while {}", self.expression(condition)), inside),
+            Expression::For {
+                iterator_name,
+                iteration_target,
+                inside,
+                ..
+            } => {
+                let header = format!(
+                    "for {} in {}",
+                    iterator_name,
+                    self.expression(iteration_target)
+                );
+                self.body(&header, inside)
+            }
+            Expression::Range { from, to } => {
+                format!("{}..{}", self.expression(from), self.expression(to))
+            }
+            Expression::FunctionCall { left, values } => {
+                format!("{}({})", self.expression(left), self.values(values))
+            }
+            Expression::Out {
+                var_type, var_name, ..
+            } => match var_type {
+                Some(var_type) => format!("out {} {}", var_type, var_name),
+                None => format!("out {}", var_name),
+            },
+            Expression::Attribute {
+                name,
+                arguments,
+                inner,
+                ..
+            } => {
+                let inner_str = if *inner { "!" } else { "" };
+                match arguments.is_empty() {
+                    true => format!("#{}[{}]", inner_str, name),
+                    false => format!("#{}[{}({})]", inner_str, name, arguments.join(", ")),
+                }
+            }
+        }
+    }
+}
+
+/// `-` and `-x`, with a space when the two would be read as `--`
+fn join_tokens(operator: &str, value: &str) -> String {
+    let joined = format!("{}{}", operator, value);
+    let merged = ["--", "++", "-=", "+=", "!=", "->", "&&"];
+    if merged
+        .iter()
+        .any(|merged| joined[operator.len() - 1..].starts_with(merged))
+    {
+        return format!("{} {}", operator, value);
+    }
+    return joined;
+}
+fn keyword_text(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::SemiColon => ";",
+        TokenKind::Comma => ",",
+        TokenKind::Break => "break",
+        TokenKind::Continue => "continue",
+        default => panic!("keyword: {:?} doesn't have a handler", default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn parse(source: &str) -> Vec<Expression> {
+        let tokens = lexer::tokenize(
+            source.to_string(),
+            vec![
+                TokenKind::Tab,
+                TokenKind::WhiteSpace,
+                TokenKind::Comment,
+                TokenKind::NextLine,
+            ],
+        );
+        return parser::parse(tokens, Dialect::Mixed);
+    }
+
+    #[test]
+    fn samples_round_trip() {
+        for entry in fs::read_dir("CompileTargets").unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|extension| extension != "rs") {
+                continue;
+            }
+            let source = fs::read_to_string(&path).unwrap();
+            let expressions = parse(&source);
+            let comments = source_comments(&source);
+            let printed = print_source(
+                &expressions,
+                comments.clone(),
+                Dialect::Mixed,
+                CodeStyle::new(),
+            );
+            check_round_trip(&expressions, &printed, Dialect::Mixed);
+
+            let printed_comments: Vec<String> = source_comments(&printed)
+                .into_iter()
+                .map(|comment| comment.value)
+                .collect();
+            let comments: Vec<String> = comments
+                .into_iter()
+                .map(|comment| comment.value.trim_end().to_string())
+                .collect();
+            assert_eq!(printed_comments, comments, "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn comments_stay_next_to_their_statements() {
+        let source = "// header\nlet x = 1; // trailing\nfn f() {\n    // inside\n    let y = x;\n}\n// last\n";
+        let printed = print_source(
+            &parse(source),
+            source_comments(source),
+            Dialect::Mixed,
+            CodeStyle::new(),
+        );
+        assert_eq!(
+            printed,
+            "// header\nlet x = 1; // trailing\nfn f() {\n    // inside\n    let y = x;\n}\n// last\n"
+        );
+    }
+}
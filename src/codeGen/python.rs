@@ -0,0 +1,856 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{
+    backend::{
+        emit_block, emit_expr, emit_statement, emit_values, hoist_out_calls, split_short_circuits,
+        Backend, OutputFile, FLOATS, INTEGERS, UNSIGNED,
+    },
+    ir::{Callee, Class, Expr, Function, Interpolation, Statement},
+    reserved::ReservedWords,
+    tokens::TokenKind,
+    types::Type,
+    writer::{indent, CodeStyle, CodeWriter},
+};
+
+/// helper functions the rewritten c# calls use, only the used ones are emitted.
+/// A tab at the start of a line is one level of indentation
+fn prelude(name: &str) -> Option<&'static str> {
+    let definition = match name {
+        "_try_parse_int" => {
+            "def _try_parse_int(text: str, unsigned: bool = False) -> tuple[bool, int]:
+\ttry:
+\t\tvalue = int(text)
+\texcept ValueError:
+\t\treturn (False, 0)
+\treturn (not unsigned or value >= 0, value)
+"
+        }
+        // multi-dimensional arrays are nested, c# iterates all of their elements
+        "_flatten" => {
+            "def _flatten(array: list, dimensions: int) -> list:
+\tif dimensions == 0:
+\t\treturn array
+\treturn [value for inner in array for value in _flatten(inner, dimensions - 1)]
+"
+        }
+        "_try_parse_float" => {
+            "def _try_parse_float(text: str) -> tuple[bool, float]:
+\ttry:
+\t\treturn (True, float(text))
+\texcept ValueError:
+\t\treturn (False, 0.0)
+"
+        }
+        _ => return None,
+    };
+    return Some(definition);
+}
+
+pub struct Python {
+    pub reserved: ReservedWords,
+    /// `from x import y` lines the generated code needs
+    imports: BTreeSet<&'static str>,
+    /// helper functions from the prelude the generated code calls
+    used: BTreeSet<&'static str>,
+    /// names declared at the top of the module, a function that assigns them declares them `global`
+    globals: HashSet<String>,
+    /// (class, field) -> type of the field
+    field_types: HashMap<(String, String), Type>,
+    /// `return` outside of a function ends the script
+    in_function: bool,
+    /// counter for the names of the values returned by calls with `out` arguments
+    out_calls: usize,
+    pub style: CodeStyle,
+}
+impl Python {
+    pub fn new() -> Python {
+        Python {
+            reserved: ReservedWords::python(),
+            imports: BTreeSet::new(),
+            used: BTreeSet::new(),
+            globals: HashSet::new(),
+            field_types: HashMap::new(),
+            in_function: false,
+            out_calls: 0,
+            style: CodeStyle::new(),
+        }
+    }
+    /// calls with `out` arguments return a tuple, they are stored before the statement that uses them
+    fn hoist(&mut self, value: &Expr) -> (String, Expr) {
+        let mut value = value.clone();
+        let mut prefix = String::new();
+        for statement in split_short_circuits(&mut value, &mut self.out_calls) {
+            prefix += &emit_statement(self, &statement);
+        }
+        for call in hoist_out_calls(&mut value, &mut self.out_calls) {
+            prefix += &format!("{} = {}\n", call.temporary, emit_expr(self, &call.call));
+            for (i, (name, var_type)) in call.outs.iter().enumerate() {
+                let name = self.name(name);
+                prefix += &match var_type {
+                    Some(var_type) => format!(
+                        "{}: {} = {}[{}]\n",
+                        name,
+                        self.map_type(var_type),
+                        call.temporary,
+                        i + 1
+                    ),
+                    None => format!("{} = {}[{}]\n", name, call.temporary, i + 1),
+                };
+            }
+        }
+        return (prefix, value);
+    }
+    /// `name(a: int) -> bool:` and the indented body of functions and methods
+    fn handle_function(&mut self, function: &Function, receiver: Option<&str>) -> String {
+        let mut parameters: Vec<String> = receiver.into_iter().map(str::to_string).collect();
+        for parameter in &function.parameters {
+            parameters.push(format!(
+                "{}: {}",
+                self.name(&parameter.name),
+                self.map_type(&parameter.var_type)
+            ));
+        }
+        let output_str = match &function.output {
+            Some(var_type) => self.map_type(var_type),
+            None => "None".to_string(),
+        };
+
+        let mut assigned = BTreeSet::new();
+        assigned_names(&function.body, &mut assigned);
+        let globals: Vec<String> = assigned
+            .iter()
+            .filter(|name| self.globals.contains(*name))
+            .map(|name| self.name(name))
+            .collect();
+        let mut body_str = String::new();
+        if !globals.is_empty() {
+            body_str += &format!("global {}\n", globals.join(", "));
+        }
+        let outer = self.in_function;
+        self.in_function = true;
+        body_str += &emit_block(self, &function.body);
+        self.in_function = outer;
+
+        return format!(
+            "def {}({}) -> {}:\n{}",
+            self.name(&function.name),
+            parameters.join(", "),
+            output_str,
+            indent(self.style, &body_str)
+        );
+    }
+    /// value that is known to be a whole number, its division has to be truncated
+    fn is_integer(&self, value: &Expr) -> bool {
+        match value {
+            Expr::Number(_) => true,
+            Expr::Variable {
+                var_type: Some(Type::Symbol(symbol)),
+                ..
+            } => INTEGERS.contains(&symbol.as_str()),
+            Expr::Field { value, name } => {
+                let Expr::Variable {
+                    var_type: Some(Type::Symbol(class)),
+                    ..
+                } = value.as_ref()
+                else {
+                    return false;
+                };
+                matches!(
+                    self.field_types.get(&(class.clone(), name.clone())),
+                    Some(Type::Symbol(symbol)) if INTEGERS.contains(&symbol.as_str())
+                )
+            }
+            Expr::Unary { value, .. } => self.is_integer(value),
+            Expr::Binary {
+                l,
+                operator: TokenKind::Plus | TokenKind::Minus | TokenKind::Star | TokenKind::Slash,
+                r,
+            } => self.is_integer(l) && self.is_integer(r),
+            _ => false,
+        }
+    }
+}
+
+impl Backend for Python {
+    fn extension(&self) -> &'static str {
+        return "py";
+    }
+    fn reserved(&self) -> &ReservedWords {
+        return &self.reserved;
+    }
+    fn map_type(&self, var_type: &Type) -> String {
+        return handle_type(self, var_type);
+    }
+    fn generate(&mut self, program: &[Statement]) -> Vec<OutputFile> {
+        declared_names(program, &mut self.globals);
+        for statement in program {
+            if let Statement::Class(class) = statement {
+                for field in &class.fields {
+                    self.field_types.insert(
+                        (class.name.clone(), field.name.clone()),
+                        field.var_type.clone(),
+                    );
+                }
+            }
+        }
+        let code = emit_block(self, program);
+
+        let mut writer = CodeWriter::new(self.style);
+        for import in &self.imports {
+            writer.line(import);
+        }
+        for name in &self.used {
+            writer.template(prelude(name).expect("only names with a definition are used"));
+        }
+        let mut content = writer.finish();
+        content += &code;
+        return vec![OutputFile {
+            extension: self.extension(),
+            content,
+        }];
+    }
+
+    fn emit_let(
+        &mut self,
+        name: &str,
+        var_type: Option<&Type>,
+        inferred: Option<&Type>,
+        _mutable: bool,
+        _constant: bool,
+        value: Option<&Expr>,
+    ) -> String {
+        let name = self.name(name);
+        let Some(value) = value else {
+            // `let x;` is assigned later
+            return match inferred {
+                Some(inferred) => format!("{}: {}\n", name, self.map_type(inferred)),
+                None => format!("{} = None\n", name),
+            };
+        };
+        let (prefix, value) = self.hoist(value);
+        let annotation = match var_type {
+            Some(var_type) => format!(": {}", self.map_type(var_type)),
+            None => String::new(),
+        };
+        return format!(
+            "{}{}{} = {}\n",
+            prefix,
+            name,
+            annotation,
+            emit_expr(self, &value)
+        );
+    }
+    fn emit_let_tuple(
+        &mut self,
+        names: &[String],
+        _types: Option<&[Type]>,
+        value: Option<&Expr>,
+    ) -> String {
+        let names: Vec<String> = names.iter().map(|name| self.name(name)).collect();
+        let Some(value) = value else {
+            return format!(
+                "{} = {}\n",
+                names.join(", "),
+                vec!["None"; names.len()].join(", ")
+            );
+        };
+        let (prefix, value) = self.hoist(value);
+        return format!(
+            "{}{} = {}\n",
+            prefix,
+            names.join(", "),
+            emit_expr(self, &value)
+        );
+    }
+    fn emit_assign(&mut self, target: &Expr, operator: TokenKind, value: &Expr) -> String {
+        let (prefix, value) = self.hoist(value);
+        return format!(
+            "{}{} {} {}\n",
+            prefix,
+            emit_expr(self, target),
+            operator_text(operator),
+            emit_expr(self, &value)
+        );
+    }
+    fn emit_expression_statement(&mut self, value: &Expr) -> String {
+        let (prefix, value) = self.hoist(value);
+        return format!("{}{}\n", prefix, emit_expr(self, &value));
+    }
+    fn emit_if(
+        &mut self,
+        condition: &Expr,
+        then: &[Statement],
+        otherwise: Option<&[Statement]>,
+    ) -> String {
+        let (prefix, condition) = self.hoist(condition);
+        let condition_str = emit_expr(self, &condition);
+        let then_str = emit_block(self, then);
+        let mut output = format!(
+            "{}if {}:\n{}",
+            prefix,
+            condition_str,
+            indent(self.style, &then_str)
+        );
+
+        match otherwise {
+            Some([else_if @ Statement::If { condition, .. }]) => {
+                let else_if_str = emit_statement(self, else_if);
+                // the values of `out` arguments have to be stored before the condition
+                if self.hoist(condition).0.is_empty() {
+                    output += &format!("el{}", else_if_str);
+                } else {
+                    output += &format!("else:\n{}", indent(self.style, &else_if_str));
+                }
+            }
+            Some(otherwise) => {
+                output += &format!(
+                    "else:\n{}",
+                    indent(self.style, &emit_block(self, otherwise))
+                );
+            }
+            None => {}
+        }
+        return output;
+    }
+    fn emit_while(&mut self, condition: &Expr, body: &[Statement]) -> String {
+        let (prefix, condition) = self.hoist(condition);
+        let condition_str = emit_expr(self, &condition);
+        let body_str = emit_block(self, body);
+        if prefix.is_empty() {
+            return format!(
+                "while {}:\n{}",
+                condition_str,
+                indent(self.style, &body_str)
+            );
+        }
+        // the `out` values are read again before every check
+        let exit_str = match &condition {
+            Expr::Unary {
+                operator: TokenKind::Not,
+                value,
+            } => emit_expr(self, value),
+            _ => format!("not ({})", condition_str),
+        };
+        let inside = format!(
+            "{}if {}:\n{}{}",
+            prefix,
+            exit_str,
+            indent(self.style, "break"),
+            body_str
+        );
+        return format!("while True:\n{}", indent(self.style, &inside));
+    }
+    fn emit_for_range(
+        &mut self,
+        variable: &str,
+        from: &Expr,
+        to: &Expr,
+        body: &[Statement],
+    ) -> String {
+        let (from_prefix, from) = self.hoist(from);
+        let (to_prefix, to) = self.hoist(to);
+        let loop_str = format!(
+            "for {} in range({}, {}):",
+            self.name(variable),
+            emit_expr(self, &from),
+            emit_expr(self, &to)
+        );
+        let body_str = emit_block(self, body);
+        return format!(
+            "{}{}{}\n{}",
+            from_prefix,
+            to_prefix,
+            loop_str,
+            indent(self.style, &body_str)
+        );
+    }
+    fn emit_for_each(&mut self, variable: &str, iterable: &Expr, body: &[Statement]) -> String {
+        let (prefix, iterable) = self.hoist(iterable);
+        let mut iterable_str = emit_expr(self, &iterable);
+        if let Expr::Variable {
+            var_type: Some(var_type),
+            ..
+        } = &iterable
+        {
+            match var_type {
+                // a dictionary is iterated as key value pairs like in c#
+                Type::Generic { name, .. } if name == "HashMap" => iterable_str += ".items()",
+                Type::Array { dimensions, .. } if *dimensions > 0 => {
+                    self.used.insert("_flatten");
+                    iterable_str = format!("_flatten({}, {})", iterable_str, dimensions);
+                }
+                _ => {}
+            }
+        }
+        let loop_str = format!("for {} in {}:", self.name(variable), iterable_str);
+        let body_str = emit_block(self, body);
+        return format!("{}{}\n{}", prefix, loop_str, indent(self.style, &body_str));
+    }
+    // python doesn't have block scopes, the shadowed names are already renamed
+    fn emit_scope(&mut self, body: &[Statement]) -> String {
+        return emit_block(self, body);
+    }
+    fn emit_return(&mut self, value: Option<&Expr>) -> String {
+        if !self.in_function {
+            return "raise SystemExit\n".to_string();
+        }
+        let Some(value) = value else {
+            return "return\n".to_string();
+        };
+        let (prefix, value) = self.hoist(value);
+        return format!("{}return {}\n", prefix, emit_expr(self, &value));
+    }
+    fn emit_break(&mut self) -> String {
+        return "break\n".to_string();
+    }
+    fn emit_continue(&mut self) -> String {
+        return "continue\n".to_string();
+    }
+    fn emit_function(&mut self, function: &Function) -> String {
+        return self.handle_function(function, None);
+    }
+    fn emit_class(&mut self, class: &Class) -> String {
+        self.imports.insert("from dataclasses import dataclass");
+        let mut body_str = String::new();
+        for field in &class.fields {
+            body_str += &format!(
+                "{}: {}\n",
+                self.name(&field.name),
+                self.map_type(&field.var_type)
+            );
+        }
+        for method in &class.methods {
+            body_str += &self.handle_function(method, Some("self"));
+        }
+        return format!(
+            "@dataclass\nclass {}:\n{}",
+            self.name(&class.name),
+            indent(self.style, &body_str)
+        );
+    }
+
+    // $"[{x}]" -> f"[{x}]", braces are escaped the same way
+    fn emit_string(&mut self, text: &str) -> String {
+        return text.to_string();
+    }
+    // $"[{x}]" -> f"[{x}]", before python 3.12 the values of an f-string can't contain its quotes
+    // so they are joined with `+` instead
+    fn emit_interpolated(&mut self, parts: &[Interpolation]) -> String {
+        let parts: Vec<(bool, String)> = parts
+            .iter()
+            .map(|part| match part {
+                Interpolation::Text(text) => (false, text.clone()),
+                Interpolation::Value(value) => (true, emit_expr(self, value)),
+            })
+            .collect();
+        if parts
+            .iter()
+            .any(|(value, text)| *value && text.contains('"'))
+        {
+            let parts: Vec<String> = parts
+                .into_iter()
+                .map(|(value, text)| match value {
+                    true => format!("str({})", text),
+                    false => format!("\"{}\"", text),
+                })
+                .collect();
+            return format!("({})", parts.join(" + "));
+        }
+        let mut output = String::from("f\"");
+        for (value, text) in parts {
+            match value {
+                true => output += &format!("{{{}}}", text),
+                false => output += &text.replace('{', "{{").replace('}', "}}"),
+            }
+        }
+        output.push('"');
+        return output;
+    }
+    fn emit_bool(&mut self, value: bool) -> String {
+        if value {
+            return "True".to_string();
+        }
+        return "False".to_string();
+    }
+    fn emit_unary(&mut self, operator: TokenKind, value: &Expr) -> String {
+        let value_str = emit_expr(self, value);
+        match operator {
+            TokenKind::Not => return format!("not {}", value_str),
+            operator => return format!("{}{}", operator_text(operator), value_str),
+        }
+    }
+    fn emit_binary(&mut self, l: &Expr, operator: TokenKind, r: &Expr) -> String {
+        let mut left_str = emit_expr(self, l);
+        let mut right_str = emit_expr(self, r);
+        match operator {
+            // numbers are converted when they are added to a string
+            TokenKind::Plus if is_string(l) != is_string(r) => {
+                if !is_string(l) {
+                    left_str = format!("str({})", left_str);
+                }
+                if !is_string(r) {
+                    right_str = format!("str({})", right_str);
+                }
+            }
+            // c# truncates the division of integers
+            TokenKind::Slash if self.is_integer(l) && self.is_integer(r) => {
+                return format!("int({} / {})", left_str, right_str);
+            }
+            _ => {}
+        }
+        return format!("({} {} {})", left_str, operator_text(operator), right_str);
+    }
+    fn emit_tuple(&mut self, values: &[Expr]) -> String {
+        if values.len() == 1 {
+            return format!("({},)", emit_expr(self, &values[0]));
+        }
+        return format!("({})", emit_values(self, values));
+    }
+    fn emit_static_field(&mut self, value: &Expr, name: &str) -> String {
+        return format!("{}.{}", emit_expr(self, value), name);
+    }
+    // map[x, y] -> map[x][y]
+    fn emit_index(&mut self, value: &Expr, indexes: &[Expr]) -> String {
+        let mut output = emit_expr(self, value);
+        for index in indexes {
+            output += &format!("[{}]", emit_expr(self, index));
+        }
+        return output;
+    }
+    fn emit_call(&mut self, callee: &Callee, arguments: &[Expr]) -> String {
+        match callee {
+            Callee::Function(name) => {
+                let arguments_str = emit_values(self, arguments);
+                return format!("{}({})", self.name(name), arguments_str);
+            }
+            Callee::Method {
+                receiver,
+                name,
+                collection,
+            } => {
+                if let Expr::External(type_name) = receiver.as_ref() {
+                    if let Some(call) = handle_static_call(self, type_name, name, arguments) {
+                        return call;
+                    }
+                }
+                return handle_method_call(self, receiver, name, collection.as_deref(), arguments);
+            }
+            Callee::Static { type_name, name } => {
+                if let Some(call) = handle_static_call(self, type_name, name, arguments) {
+                    return call;
+                }
+                let arguments_str = emit_values(self, arguments);
+                return format!("{}.{}({})", type_name, name, arguments_str);
+            }
+            Callee::Value(value) => {
+                let value_str = emit_expr(self, value);
+                return format!("{}({})", value_str, emit_values(self, arguments));
+            }
+        }
+    }
+    // Point { x = 1; y = 2; } -> Point(x=1, y=2)
+    fn emit_new(&mut self, class: &str, fields: &[(String, Expr)]) -> String {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(name, value)| format!("{}={}", self.name(name), emit_expr(self, value)))
+            .collect();
+        return format!("{}({})", self.name(class), fields.join(", "));
+    }
+    fn emit_array(&mut self, values: &[Expr]) -> String {
+        return format!("[{}]", emit_values(self, values));
+    }
+    fn emit_list(&mut self, _element_type: Option<&Type>, values: &[Expr]) -> String {
+        return format!("[{}]", emit_values(self, values));
+    }
+    fn emit_new_collection(&mut self, collection: &str, _var_type: Option<&Type>) -> String {
+        match collection {
+            "HashMap" => return "{}".to_string(),
+            "HashSet" => return "set()".to_string(),
+            "VecDeque" => {
+                self.imports.insert("from collections import deque");
+                return "deque()".to_string();
+            }
+            _ => return "[]".to_string(),
+        }
+    }
+    fn emit_out(&mut self, name: &str, _var_type: Option<&Type>) -> String {
+        panic!(
+            "out argument: {} has to be hoisted before the statement that uses it",
+            name
+        );
+    }
+    // objects are passed by reference
+    fn emit_ref(&mut self, value: &Expr, _mutable: bool) -> String {
+        return emit_expr(self, value);
+    }
+}
+
+fn is_string(value: &Expr) -> bool {
+    match value {
+        Expr::String(_) | Expr::Interpolated(_) => true,
+        Expr::Variable {
+            var_type: Some(Type::Symbol(symbol)),
+            ..
+        } => matches!(symbol.as_str(), "str" | "String" | "string"),
+        Expr::Binary {
+            l,
+            operator: TokenKind::Plus,
+            r,
+        } => is_string(l) || is_string(r),
+        _ => false,
+    }
+}
+
+/// names declared in the statements, python has no block scope so the nested blocks count too
+fn declared_names(statements: &[Statement], names: &mut HashSet<String>) {
+    for statement in statements {
+        for value in statement_values(statement) {
+            out_names(value, true, &mut |name| {
+                names.insert(name.to_string());
+            });
+        }
+        match statement {
+            Statement::Let { name, .. } => {
+                names.insert(name.clone());
+            }
+            Statement::LetTuple { names: tuple, .. } => names.extend(tuple.iter().cloned()),
+            Statement::ForRange { variable, .. } | Statement::ForEach { variable, .. } => {
+                names.insert(variable.clone());
+            }
+            _ => {}
+        }
+        for body in statement_bodies(statement) {
+            declared_names(body, names);
+        }
+    }
+}
+/// variables that get a new value in the statements, setting an element or a field doesn't count
+fn assigned_names(statements: &[Statement], names: &mut BTreeSet<String>) {
+    for statement in statements {
+        for value in statement_values(statement) {
+            out_names(value, false, &mut |name| {
+                names.insert(name.to_string());
+            });
+        }
+        match statement {
+            Statement::Assign {
+                target: Expr::Variable { name, .. },
+                ..
+            } => {
+                names.insert(name.clone());
+            }
+            // s.push_str(x) -> s += x
+            Statement::Expression(Expr::Call {
+                callee:
+                    Callee::Method {
+                        receiver,
+                        collection: Some(collection),
+                        name: method,
+                    },
+                ..
+            }) if collection == "String" && method == "push_str" => {
+                if let Expr::Variable { name, .. } = receiver.as_ref() {
+                    names.insert(name.clone());
+                }
+            }
+            _ => {}
+        }
+        for body in statement_bodies(statement) {
+            assigned_names(body, names);
+        }
+    }
+}
+/// names of the `out` arguments that declare a variable or assign an existing one
+fn out_names(value: &Expr, declared: bool, found: &mut dyn FnMut(&str)) {
+    if let Expr::Out { name, var_type } = value {
+        if var_type.is_some() == declared {
+            found(name);
+        }
+    }
+    for child in value.children() {
+        out_names(child, declared, found);
+    }
+}
+fn statement_values(statement: &Statement) -> Vec<&Expr> {
+    match statement {
+        Statement::Let { value, .. } | Statement::LetTuple { value, .. } => value.iter().collect(),
+        Statement::Assign { target, value, .. } => vec![target, value],
+        Statement::Expression(value) => vec![value],
+        Statement::If { condition, .. } | Statement::While { condition, .. } => vec![condition],
+        Statement::ForRange { from, to, .. } => vec![from, to],
+        Statement::ForEach { iterable, .. } => vec![iterable],
+        Statement::Return(value) => value.iter().collect(),
+        _ => vec![],
+    }
+}
+/// blocks inside the statement that run in the same function
+fn statement_bodies(statement: &Statement) -> Vec<&[Statement]> {
+    match statement {
+        Statement::If {
+            then, otherwise, ..
+        } => {
+            let mut bodies = vec![then.as_slice()];
+            bodies.extend(otherwise.as_deref());
+            bodies
+        }
+        Statement::While { body, .. }
+        | Statement::ForRange { body, .. }
+        | Statement::ForEach { body, .. }
+        | Statement::Block(body) => vec![body],
+        _ => vec![],
+    }
+}
+
+/// Console.WriteLine(x) -> print(x), int.Parse(x) -> int(x) ...
+fn handle_static_call(
+    generator: &mut Python,
+    type_name: &str,
+    name: &str,
+    arguments: &[Expr],
+) -> Option<String> {
+    let arguments_str = emit_values(generator, arguments);
+    let call = match (type_name, name) {
+        ("Console", "WriteLine") => format!("print({})", arguments_str),
+        ("Console", "Write") => format!("print({}, end=\"\")", arguments_str),
+        ("Console", "ReadLine") => "input()".to_string(),
+        (number, "Parse") if INTEGERS.contains(&number) => format!("int({})", arguments_str),
+        (number, "Parse") if FLOATS.contains(&number) => format!("float({})", arguments_str),
+        (number, "TryParse") if UNSIGNED.contains(&number) => {
+            generator.used.insert("_try_parse_int");
+            format!("_try_parse_int({}, True)", arguments_str)
+        }
+        (number, "TryParse") if INTEGERS.contains(&number) => {
+            generator.used.insert("_try_parse_int");
+            format!("_try_parse_int({})", arguments_str)
+        }
+        (number, "TryParse") if FLOATS.contains(&number) => {
+            generator.used.insert("_try_parse_float");
+            format!("_try_parse_float({})", arguments_str)
+        }
+        _ => return None,
+    };
+    return Some(call);
+}
+/// v.push(1) -> v.append(1), map.insert(k, v) -> map[k] = v ...
+fn handle_method_call(
+    generator: &mut Python,
+    receiver: &Expr,
+    name: &str,
+    collection: Option<&str>,
+    arguments: &[Expr],
+) -> String {
+    let receiver_str = emit_expr(generator, receiver);
+    let values: Vec<String> = arguments
+        .iter()
+        .map(|argument| emit_expr(generator, argument))
+        .collect();
+    let arguments_str = values.join(", ");
+    let call = |method: &str| format!("{}.{}({})", receiver_str, method, arguments_str);
+    let output = match (collection.unwrap_or(""), name) {
+        (_, "iter") | (_, "iter_mut") | (_, "into_iter") => receiver_str.clone(),
+        (_, "to_string") => format!("str({})", receiver_str),
+
+        ("Vec" | "VecDeque", "push" | "push_back") => call("append"),
+        ("Vec", "remove") => call("pop"),
+        ("VecDeque", "pop_front") => call("popleft"),
+        ("Vec" | "VecDeque", "pop_back") => call("pop"),
+        ("VecDeque", "front") => format!("{}[0]", receiver_str),
+
+        ("HashMap", "insert") if values.len() == 2 => {
+            format!("{}[{}] = {}", receiver_str, values[0], values[1])
+        }
+        ("HashMap", "get") => format!("{}[{}]", receiver_str, arguments_str),
+        ("HashMap", "remove") => call("pop"),
+        ("HashMap", "keys") => format!("list({}.keys())", receiver_str),
+        ("HashMap", "values") => format!("list({}.values())", receiver_str),
+        ("HashSet", "insert") => call("add"),
+        ("HashSet", "remove") => call("discard"),
+        ("HashMap", "contains_key") | (_, "contains") if collection.is_some() => {
+            format!("({} in {})", arguments_str, receiver_str)
+        }
+
+        ("String", "push_str") => format!("{} += {}", receiver_str, arguments_str),
+        (_, "len") if collection.is_some() => format!("len({})", receiver_str),
+        (_, "is_empty") if collection.is_some() => format!("(len({}) == 0)", receiver_str),
+
+        _ => call(name),
+    };
+    return output;
+}
+/// python spelling of an operator
+fn operator_text(operator: TokenKind) -> &'static str {
+    match operator {
+        TokenKind::Plus => "+",
+        TokenKind::Minus => "-",
+        TokenKind::Star => "*",
+        TokenKind::Slash => "/",
+        TokenKind::Percent => "%",
+        TokenKind::Less => "<",
+        TokenKind::LessEquals => "<=",
+        TokenKind::Greater => ">",
+        TokenKind::GreaterEquals => ">=",
+        TokenKind::Equals => "==",
+        TokenKind::NotEquals => "!=",
+        TokenKind::Or => "or",
+        TokenKind::And => "and",
+        TokenKind::Not => "not ",
+        TokenKind::Assignment => "=",
+        TokenKind::PlusEquals => "+=",
+        TokenKind::MinusEquals => "-=",
+        default => panic!("operator: {:?} doesn't have a handler", default),
+    }
+}
+
+fn handle_type(generator: &Python, var_type: &Type) -> String {
+    match var_type {
+        Type::Symbol(symbol) => handle_symbol_type(generator, symbol),
+        // int[,] -> list[list[int]]
+        Type::Array {
+            left_type,
+            dimensions,
+        } => {
+            let mut output = handle_type(generator, left_type);
+            for _ in 0..=*dimensions {
+                output = format!("list[{}]", output);
+            }
+            output
+        }
+        Type::Generic { name, arguments } => {
+            let arguments: Vec<String> = arguments
+                .iter()
+                .map(|argument| handle_type(generator, argument))
+                .collect();
+            let name = match name.as_str() {
+                "Vec" => "list",
+                "VecDeque" => "deque",
+                "HashMap" => "dict",
+                "HashSet" => "set",
+                name => name,
+            };
+            format!("{}[{}]", name, arguments.join(", "))
+        }
+        Type::Tuple(types) if types.is_empty() => "None".to_string(),
+        Type::Tuple(types) => {
+            let types: Vec<String> = types
+                .iter()
+                .map(|var_type| handle_type(generator, var_type))
+                .collect();
+            format!("tuple[{}]", types.join(", "))
+        }
+        // objects are passed by reference
+        Type::Reference { inner, .. } => handle_type(generator, inner),
+    }
+}
+fn handle_symbol_type(generator: &Python, symbol: &str) -> String {
+    if INTEGERS.contains(&symbol) {
+        return "int".to_string();
+    }
+    if FLOATS.contains(&symbol) {
+        return "float".to_string();
+    }
+    match symbol {
+        "bool" => return "bool".to_string(),
+        "str" | "String" | "string" | "char" => return "str".to_string(),
+        _ => return generator.name(symbol),
+    }
+}
@@ -0,0 +1,429 @@
+use std::collections::HashSet;
+
+/// c# keywords, contextual keywords like `var` or `value` are valid names and are left out
+const CSHARP_KEYWORDS: &[&str] = &[
+    "abstract",
+    "as",
+    "base",
+    "bool",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "checked",
+    "class",
+    "const",
+    "continue",
+    "decimal",
+    "default",
+    "delegate",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "event",
+    "explicit",
+    "extern",
+    "false",
+    "finally",
+    "fixed",
+    "float",
+    "for",
+    "foreach",
+    "goto",
+    "if",
+    "implicit",
+    "in",
+    "int",
+    "interface",
+    "internal",
+    "is",
+    "lock",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "object",
+    "operator",
+    "out",
+    "override",
+    "params",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "ref",
+    "return",
+    "sbyte",
+    "sealed",
+    "short",
+    "sizeof",
+    "stackalloc",
+    "static",
+    "string",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "uint",
+    "ulong",
+    "unchecked",
+    "unsafe",
+    "ushort",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// typescript keywords and names that can't be declared in strict mode, type names like `number` are valid names
+const TYPESCRIPT_KEYWORDS: &[&str] = &[
+    "arguments",
+    "as",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// python keywords and the builtins the generated code calls
+const PYTHON_KEYWORDS: &[&str] = &[
+    "and",
+    "as",
+    "assert",
+    "async",
+    "await",
+    "bool",
+    "break",
+    "class",
+    "continue",
+    "dataclass",
+    "def",
+    "del",
+    "dict",
+    "elif",
+    "else",
+    "except",
+    "False",
+    "finally",
+    "float",
+    "for",
+    "from",
+    "global",
+    "if",
+    "import",
+    "in",
+    "input",
+    "int",
+    "is",
+    "lambda",
+    "len",
+    "list",
+    "None",
+    "nonlocal",
+    "not",
+    "or",
+    "pass",
+    "print",
+    "raise",
+    "range",
+    "return",
+    "set",
+    "str",
+    "True",
+    "try",
+    "tuple",
+    "while",
+    "with",
+    "yield",
+];
+
+/// c99 keywords and the names of the standard library the generated code uses
+const C_KEYWORDS: &[&str] = &[
+    "auto",
+    "_Bool",
+    "bool",
+    "break",
+    "case",
+    "char",
+    "_Complex",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "errno",
+    "extern",
+    "false",
+    "float",
+    "for",
+    "free",
+    "goto",
+    "if",
+    "_Imaginary",
+    "inline",
+    "int",
+    "long",
+    "main",
+    "malloc",
+    "memcpy",
+    "NULL",
+    "printf",
+    "register",
+    "restrict",
+    "return",
+    "self",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "stdin",
+    "strchr",
+    "strcmp",
+    "strlen",
+    "strstr",
+    "strtod",
+    "strtoll",
+    "strtoull",
+    "struct",
+    "switch",
+    "true",
+    "typedef",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// lua keywords and the globals the generated code uses
+const LUA_KEYWORDS: &[&str] = &[
+    "and",
+    "break",
+    "coroutine",
+    "do",
+    "else",
+    "elseif",
+    "end",
+    "false",
+    "for",
+    "function",
+    "goto",
+    "if",
+    "in",
+    "io",
+    "ipairs",
+    "local",
+    "math",
+    "next",
+    "nil",
+    "not",
+    "or",
+    "pairs",
+    "print",
+    "repeat",
+    "return",
+    "self",
+    "setmetatable",
+    "string",
+    "table",
+    "then",
+    "tonumber",
+    "tostring",
+    "true",
+    "until",
+    "while",
+];
+
+/// functions and globals of the generated module, wat names can't collide with instructions
+const WAT_NAMES: &[&str] = &[
+    "alloc",
+    "main",
+    "print_f64",
+    "print_i64",
+    "print_str",
+    "read_line",
+    "rt_concat",
+    "rt_int_to_string",
+    "rt_length",
+    "rt_parse_int",
+    "rt_str_eq",
+    "rt_try_parse_int",
+];
+
+/// gdscript keywords, builtins and the members of `Node` the script inherits
+const GDSCRIPT_KEYWORDS: &[&str] = &[
+    "_",
+    "and",
+    "Array",
+    "as",
+    "assert",
+    "await",
+    "bool",
+    "break",
+    "breakpoint",
+    "call",
+    "class",
+    "class_name",
+    "connect",
+    "const",
+    "continue",
+    "Dictionary",
+    "elif",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "float",
+    "for",
+    "free",
+    "func",
+    "get",
+    "if",
+    "in",
+    "INF",
+    "int",
+    "is",
+    "len",
+    "match",
+    "name",
+    "namespace",
+    "NAN",
+    "not",
+    "notification",
+    "null",
+    "or",
+    "OS",
+    "owner",
+    "pass",
+    "PI",
+    "preload",
+    "print",
+    "printraw",
+    "process_mode",
+    "range",
+    "return",
+    "scene_file_path",
+    "self",
+    "set",
+    "signal",
+    "static",
+    "str",
+    "String",
+    "super",
+    "TAU",
+    "trait",
+    "true",
+    "var",
+    "void",
+    "when",
+    "while",
+    "yield",
+];
+
+/// How a name that is a keyword of the target is written
+#[derive(Debug, Clone, Copy)]
+pub enum Escape {
+    /// base -> @base
+    Prefix(&'static str),
+    /// delete -> delete_
+    Suffix(&'static str),
+}
+
+/// Reserved words of a backend
+pub struct ReservedWords {
+    words: HashSet<&'static str>,
+    escape: Escape,
+}
+impl ReservedWords {
+    pub fn new(words: &[&'static str], escape: Escape) -> ReservedWords {
+        ReservedWords {
+            words: words.iter().copied().collect(),
+            escape,
+        }
+    }
+    pub fn csharp() -> ReservedWords {
+        ReservedWords::new(CSHARP_KEYWORDS, Escape::Prefix("@"))
+    }
+    pub fn typescript() -> ReservedWords {
+        ReservedWords::new(TYPESCRIPT_KEYWORDS, Escape::Suffix("_"))
+    }
+    pub fn python() -> ReservedWords {
+        ReservedWords::new(PYTHON_KEYWORDS, Escape::Suffix("_"))
+    }
+    pub fn c() -> ReservedWords {
+        ReservedWords::new(C_KEYWORDS, Escape::Suffix("_"))
+    }
+    pub fn lua() -> ReservedWords {
+        ReservedWords::new(LUA_KEYWORDS, Escape::Suffix("_"))
+    }
+    pub fn gdscript() -> ReservedWords {
+        ReservedWords::new(GDSCRIPT_KEYWORDS, Escape::Suffix("_"))
+    }
+    pub fn wat() -> ReservedWords {
+        ReservedWords::new(WAT_NAMES, Escape::Suffix("_"))
+    }
+    pub fn contains(&self, name: &str) -> bool {
+        return self.words.contains(name);
+    }
+    /// `name` written so the target doesn't read it as a keyword
+    pub fn escape(&self, name: &str) -> String {
+        if !self.contains(name) {
+            return name.to_string();
+        }
+        match self.escape {
+            Escape::Prefix(prefix) => format!("{}{}", prefix, name),
+            Escape::Suffix(suffix) => format!("{}{}", name, suffix),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::{collections::HashMap, fs};
+
+/// Maps source type names to c# type names.
+/// User aliases are checked first so a project can map its own names like `Vec2 = Vector2`
+/// or override the defaults like `usize = nuint`.
+#[derive(Clone)]
+pub struct TypeMap {
+    aliases: HashMap<String, String>,
+}
+impl TypeMap {
+    pub fn new() -> TypeMap {
+        TypeMap {
+            aliases: HashMap::new(),
+        }
+    }
+    pub fn insert(&mut self, source: String, target: String) {
+        self.aliases.insert(source, target);
+    }
+    /// Loads aliases from a file with one `source = target` pair per line, `//` starts a comment
+    pub fn load(&mut self, path: &str) {
+        let content = fs::read_to_string(path)
+            .unwrap_or_else(|_| panic!("Couldn't find type map file: {}", path));
+
+        for line in content.lines() {
+            let line = line.split("//").next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (source, target) = parse_type_alias(line);
+            self.insert(source, target);
+        }
+    }
+    pub fn get(&self, symbol: &str) -> Option<String> {
+        match self.aliases.get(symbol) {
+            // an alias can point at another source type like `Score = i32`
+            Some(target) => match default_type(target) {
+                Some(default) => Some(default.to_string()),
+                None => Some(target.to_owned()),
+            },
+            None => default_type(symbol).map(|default| default.to_string()),
+        }
+    }
+}
+
+/// `source=target` -> (source, target)
+pub fn parse_type_alias(text: &str) -> (String, String) {
+    match text.split_once('=') {
+        Some((source, target)) if !source.trim().is_empty() && !target.trim().is_empty() => {
+            (source.trim().to_string(), target.trim().to_string())
+        }
+        _ => panic!("type alias: {:?} has to look like `source = target`", text),
+    }
+}
+
+fn default_type(symbol: &str) -> Option<&'static str> {
+    let target = match symbol {
+        "i8" => "sbyte",
+        "i16" => "short",
+        "i32" => "int",
+        "i64" => "long",
+        "i128" => "Int128",
+        "u8" => "byte",
+        "u16" => "ushort",
+        "u32" => "uint",
+        "u64" => "ulong",
+        "u128" => "UInt128",
+        // c# collections and arrays are indexed with int, so sizes use it too
+        "usize" => "int",
+        "isize" => "int",
+        "f32" => "float",
+        "f64" => "double",
+        "char" => "char",
+        "bool" => "bool",
+        "str" => "string",
+        "String" => "string",
+
+        _ => return None,
+    };
+    return Some(target);
+}
@@ -0,0 +1,854 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{
+    backend::{
+        emit_block, emit_expr, emit_statement, emit_values, hoist_out_calls, split_short_circuits,
+        Backend, OutputFile, FLOATS,
+    },
+    ir::{Callee, Class, Expr, Function, Interpolation, Statement},
+    reserved::ReservedWords,
+    tokens::TokenKind,
+    types::Type,
+    writer::{CodeStyle, CodeWriter},
+};
+
+const NUMBERS: &[&str] = &[
+    "i8", "i16", "i32", "u8", "u16", "u32", "usize", "isize", "f32", "f64", "sbyte", "short",
+    "int", "byte", "ushort", "uint", "nint", "nuint", "float", "double", "decimal",
+];
+/// integers that don't fit into the 53 bits of a number
+const BIG_INTEGERS: &[&str] = &[
+    "i64", "u64", "i128", "u128", "long", "ulong", "Int128", "UInt128",
+];
+
+/// definitions of the c# names the source uses, only the used ones are emitted.
+/// A tab at the start of a line is one level of indentation
+fn prelude(name: &str) -> Option<&'static str> {
+    let definition = match name {
+        "Console" => {
+            "const Console = {
+	WriteLine: (value: unknown = \"\"): void => console.log(String(value)),
+	ReadLine: (): string => prompt() ?? \"\",
+};
+"
+        }
+        "int" => {
+            "const int = {
+	Parse: (text: string): number => Number.parseInt(text, 10),
+	TryParse: (text: string): [boolean, number] => {
+		const value = Number(text);
+		return [text.trim() !== \"\" && Number.isInteger(value), value];
+	},
+};
+"
+        }
+        "uint" => {
+            "const uint = {
+	Parse: (text: string): number => Number.parseInt(text, 10),
+	TryParse: (text: string): [boolean, number] => {
+		const value = Number(text);
+		return [text.trim() !== \"\" && Number.isInteger(value) && value >= 0, value];
+	},
+};
+"
+        }
+        _ => return None,
+    };
+    return Some(definition);
+}
+
+pub struct TypeScript {
+    pub reserved: ReservedWords,
+    /// classes with methods become ts classes, the others are interfaces of plain objects
+    classes: HashSet<String>,
+    /// (class, field) -> type of the field
+    field_types: HashMap<(String, String), Type>,
+    /// c# names that need a definition in the prelude
+    used: BTreeSet<String>,
+    /// numbers are written as bigint literals while a value of a bigint type is emitted
+    bigint: bool,
+    /// name -> (parameter types, output) of the functions, their arguments are converted
+    functions: HashMap<String, (Vec<Type>, Option<Type>)>,
+    /// output of the function that is emitted, the returned value is converted to it
+    output: Option<Type>,
+    /// counter for the names of the values returned by calls with `out` arguments
+    out_calls: usize,
+    pub style: CodeStyle,
+}
+impl TypeScript {
+    pub fn new() -> TypeScript {
+        TypeScript {
+            reserved: ReservedWords::typescript(),
+            classes: HashSet::new(),
+            field_types: HashMap::new(),
+            used: BTreeSet::new(),
+            bigint: false,
+            functions: HashMap::new(),
+            output: None,
+            out_calls: 0,
+            style: CodeStyle::new(),
+        }
+    }
+    /// calls with `out` arguments return a tuple, they are stored before the statement that uses them
+    fn hoist(&mut self, value: &Expr) -> (String, Expr) {
+        let mut value = value.clone();
+        let mut prefix = String::new();
+        for statement in split_short_circuits(&mut value, &mut self.out_calls) {
+            prefix += &emit_statement(self, &statement);
+        }
+        for call in hoist_out_calls(&mut value, &mut self.out_calls) {
+            prefix += &format!(
+                "const {} = {};\n",
+                call.temporary,
+                emit_expr(self, &call.call)
+            );
+            for (i, (name, var_type)) in call.outs.iter().enumerate() {
+                let name = self.name(name);
+                prefix += &match var_type {
+                    Some(var_type) => format!(
+                        "let {}: {} = {}[{}];\n",
+                        name,
+                        self.map_type(var_type),
+                        call.temporary,
+                        i + 1
+                    ),
+                    None => format!("{} = {}[{}];\n", name, call.temporary, i + 1),
+                };
+            }
+        }
+        return (prefix, value);
+    }
+    /// value that is known to be a whole number, its division has to be rounded
+    fn is_integer(&self, value: &Expr) -> bool {
+        match value {
+            Expr::Number(_) => true,
+            Expr::Variable {
+                var_type: Some(var_type),
+                ..
+            } => self.is_integer_type(var_type),
+            Expr::Field { value, name } => {
+                let Expr::Variable {
+                    var_type: Some(Type::Symbol(class)),
+                    ..
+                } = value.as_ref()
+                else {
+                    return false;
+                };
+                self.field_types
+                    .get(&(class.clone(), name.clone()))
+                    .is_some_and(|var_type| self.is_integer_type(var_type))
+            }
+            Expr::Unary { value, .. } => self.is_integer(value),
+            Expr::Binary {
+                l,
+                operator: TokenKind::Plus | TokenKind::Minus | TokenKind::Star | TokenKind::Slash,
+                r,
+            } => self.is_integer(l) && self.is_integer(r),
+            _ => false,
+        }
+    }
+    fn is_integer_type(&self, var_type: &Type) -> bool {
+        match var_type {
+            Type::Symbol(symbol) => {
+                NUMBERS.contains(&symbol.as_str()) && !FLOATS.contains(&symbol.as_str())
+            }
+            _ => false,
+        }
+    }
+    /// emits a value that is assigned to something of `var_type`,
+    /// c# widens ints to longs and longs to floats without a cast but ts has to convert them
+    fn emit_typed(&mut self, value: &Expr, var_type: Option<&Type>) -> String {
+        let target = var_type.map(|var_type| self.map_type(var_type));
+        let source = self.type_of(value).map(|var_type| self.map_type(&var_type));
+        let conversion = match (target.as_deref(), source.as_deref()) {
+            (Some("bigint"), Some("number")) => Some("BigInt"),
+            (Some("number"), Some("bigint")) => Some("Number"),
+            _ => None,
+        };
+        // the literals of the value are written in the type it has before the conversion
+        let literals = conversion.map_or(target, |_| source);
+        let outer = self.bigint;
+        self.bigint = literals.as_deref() == Some("bigint");
+        let value_str = emit_expr(self, value);
+        self.bigint = outer;
+        match conversion {
+            Some(conversion) => return format!("{}({})", conversion, value_str),
+            None => return value_str,
+        }
+    }
+    /// source type of a value as far as the backend can tell, int literals don't have one
+    /// because they are written as the type of the other side
+    fn type_of(&self, value: &Expr) -> Option<Type> {
+        match value {
+            Expr::Float(_) => Some(Type::Symbol("f64".to_string())),
+            Expr::Variable {
+                var_type: Some(var_type),
+                ..
+            } => match var_type {
+                Type::Reference { inner, .. } => Some(inner.as_ref().clone()),
+                var_type => Some(var_type.clone()),
+            },
+            Expr::Field { value, name } => {
+                let Some(Type::Symbol(class)) = self.type_of(value) else {
+                    return None;
+                };
+                return self.field_types.get(&(class, name.clone())).cloned();
+            }
+            Expr::Unary {
+                operator: TokenKind::Minus | TokenKind::Plus,
+                value,
+            } => self.type_of(value),
+            Expr::Binary {
+                l,
+                operator: TokenKind::Plus | TokenKind::Minus | TokenKind::Star | TokenKind::Slash,
+                r,
+            } => self.operand_type(l, r),
+            Expr::Index { value, indexes } => match self.type_of(value)? {
+                Type::Array {
+                    left_type,
+                    dimensions,
+                } if dimensions + 1 == indexes.len() => Some(*left_type),
+                Type::Generic { name, arguments } if name == "Vec" => arguments.first().cloned(),
+                _ => None,
+            },
+            Expr::Call {
+                callee: Callee::Function(name),
+                ..
+            } => self.functions.get(name)?.1.clone(),
+            _ => None,
+        }
+    }
+    /// type both sides of an arithmetic operator are converted to,
+    /// an int and a long give a long and a long and a float give a float like in c#
+    fn operand_type(&self, l: &Expr, r: &Expr) -> Option<Type> {
+        let (left, right) = (self.type_of(l), self.type_of(r));
+        let is_float = |var_type: &Option<Type>| matches!(var_type, Some(Type::Symbol(symbol)) if FLOATS.contains(&symbol.as_str()));
+        let is_bigint = |var_type: &Option<Type>| {
+            var_type
+                .as_ref()
+                .is_some_and(|var_type| self.map_type(var_type) == "bigint")
+        };
+        if is_float(&right) || (is_bigint(&right) && !is_float(&left)) {
+            return right;
+        }
+        return left.or(right);
+    }
+}
+
+impl Backend for TypeScript {
+    fn extension(&self) -> &'static str {
+        return "ts";
+    }
+    fn reserved(&self) -> &ReservedWords {
+        return &self.reserved;
+    }
+    fn map_type(&self, var_type: &Type) -> String {
+        return handle_type(self, var_type);
+    }
+    fn generate(&mut self, program: &[Statement]) -> Vec<OutputFile> {
+        for statement in program {
+            if let Statement::Function(function) = statement {
+                let parameters = function
+                    .parameters
+                    .iter()
+                    .map(|parameter| parameter.var_type.clone())
+                    .collect();
+                self.functions
+                    .insert(function.name.clone(), (parameters, function.output.clone()));
+            }
+            if let Statement::Class(class) = statement {
+                if !class.methods.is_empty() {
+                    self.classes.insert(class.name.clone());
+                }
+                for field in &class.fields {
+                    self.field_types.insert(
+                        (class.name.clone(), field.name.clone()),
+                        field.var_type.clone(),
+                    );
+                }
+            }
+        }
+        let code = emit_block(self, program);
+        let mut writer = CodeWriter::new(self.style);
+        for name in &self.used {
+            writer.template(prelude(name).expect("only names with a definition are used"));
+        }
+        let mut content = writer.finish();
+        content += &code;
+        // a file without exports is a script and its names would collide with the globals of the dom
+        if !program.iter().any(is_exported) {
+            content += "export {};\n";
+        }
+        return vec![OutputFile {
+            extension: self.extension(),
+            content,
+        }];
+    }
+
+    fn emit_let(
+        &mut self,
+        name: &str,
+        var_type: Option<&Type>,
+        inferred: Option<&Type>,
+        mutable: bool,
+        _constant: bool,
+        value: Option<&Expr>,
+    ) -> String {
+        let name = self.name(name);
+        let Some(value) = value else {
+            // `let x;` is assigned later
+            return match inferred {
+                Some(inferred) => format!("let {}: {};\n", name, self.map_type(inferred)),
+                None => format!("let {};\n", name),
+            };
+        };
+        let (prefix, value) = self.hoist(value);
+        let keyword = if mutable { "let" } else { "const" };
+        let annotation = match var_type {
+            Some(var_type) => format!(": {}", self.map_type(var_type)),
+            None => String::new(),
+        };
+        let value_str = self.emit_typed(&value, var_type.or(inferred));
+        return format!(
+            "{}{} {}{} = {};\n",
+            prefix, keyword, name, annotation, value_str
+        );
+    }
+    fn emit_let_tuple(
+        &mut self,
+        names: &[String],
+        types: Option<&[Type]>,
+        value: Option<&Expr>,
+    ) -> String {
+        let names: Vec<String> = names.iter().map(|name| self.name(name)).collect();
+        let annotation = match types {
+            Some(types) => format!(": {}", handle_tuple_type(self, types)),
+            None => String::new(),
+        };
+        let Some(value) = value else {
+            return format!("let [{}]{};\n", names.join(", "), annotation);
+        };
+        let (prefix, value) = self.hoist(value);
+        return format!(
+            "{}let [{}]{} = {};\n",
+            prefix,
+            names.join(", "),
+            annotation,
+            emit_expr(self, &value)
+        );
+    }
+    fn emit_assign(&mut self, target: &Expr, operator: TokenKind, value: &Expr) -> String {
+        let (prefix, value) = self.hoist(value);
+        let target_type = match target {
+            Expr::Variable { var_type, .. } => var_type.as_ref(),
+            _ => None,
+        };
+        let value_str = self.emit_typed(&value, target_type);
+        return format!(
+            "{}{} {} {};\n",
+            prefix,
+            emit_expr(self, target),
+            operator_text(operator),
+            value_str
+        );
+    }
+    fn emit_expression_statement(&mut self, value: &Expr) -> String {
+        let (prefix, value) = self.hoist(value);
+        return format!("{}{};\n", prefix, emit_expr(self, &value));
+    }
+    fn emit_if(
+        &mut self,
+        condition: &Expr,
+        then: &[Statement],
+        otherwise: Option<&[Statement]>,
+    ) -> String {
+        let (prefix, condition) = self.hoist(condition);
+        let condition_str = emit_expr(self, &condition);
+        let then_str = emit_block(self, then);
+        let mut writer = CodeWriter::new(self.style);
+        writer.lines(&prefix);
+        writer.open(&format!("if ({})", condition_str));
+        writer.lines(&then_str);
+
+        match otherwise {
+            Some([else_if @ Statement::If { condition, .. }]) => {
+                let else_if_str = emit_statement(self, else_if);
+                // the values of `out` arguments have to be stored before the condition
+                if self.hoist(condition).0.is_empty() {
+                    writer.close_with(&format!("else {}", else_if_str));
+                } else {
+                    writer.close_open("else");
+                    writer.lines(&else_if_str);
+                    writer.close("");
+                }
+            }
+            Some(otherwise) => {
+                let else_str = emit_block(self, otherwise);
+                writer.close_open("else");
+                writer.lines(&else_str);
+                writer.close("");
+            }
+            None => writer.close(""),
+        }
+        return writer.finish();
+    }
+    fn emit_while(&mut self, condition: &Expr, body: &[Statement]) -> String {
+        let (prefix, condition) = self.hoist(condition);
+        let condition_str = emit_expr(self, &condition);
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        if prefix.is_empty() {
+            writer.block(&format!("while ({})", condition_str), &body_str);
+            return writer.finish();
+        }
+        // the `out` values are read again before every check
+        let exit_str = match &condition {
+            Expr::Unary {
+                operator: TokenKind::Not,
+                value,
+            } => emit_expr(self, value),
+            _ => format!("!({})", condition_str),
+        };
+        writer.open("while (true)");
+        writer.lines(&prefix);
+        writer.block(&format!("if ({})", exit_str), "break;");
+        writer.lines(&body_str);
+        writer.close("");
+        return writer.finish();
+    }
+    fn emit_for_range(
+        &mut self,
+        variable: &str,
+        from: &Expr,
+        to: &Expr,
+        body: &[Statement],
+    ) -> String {
+        let (from_prefix, from) = self.hoist(from);
+        let (to_prefix, to) = self.hoist(to);
+        let variable = self.name(variable);
+        let loop_str = format!(
+            "for (let {} = {}; {} < {}; {}++)",
+            variable,
+            emit_expr(self, &from),
+            variable,
+            emit_expr(self, &to),
+            variable
+        );
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.lines(&from_prefix);
+        writer.lines(&to_prefix);
+        writer.block(&loop_str, &body_str);
+        return writer.finish();
+    }
+    fn emit_for_each(&mut self, variable: &str, iterable: &Expr, body: &[Statement]) -> String {
+        let (prefix, iterable) = self.hoist(iterable);
+        let mut iterable_str = emit_expr(self, &iterable);
+        // multi-dimensional arrays are nested, c# iterates all of their elements
+        if let Some(Type::Array { dimensions, .. }) = self.type_of(&iterable) {
+            if dimensions > 0 {
+                iterable_str = format!("{}.flat({})", iterable_str, dimensions);
+            }
+        }
+        let loop_str = format!("for (const {} of {})", self.name(variable), iterable_str);
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.lines(&prefix);
+        writer.block(&loop_str, &body_str);
+        return writer.finish();
+    }
+    fn emit_scope(&mut self, body: &[Statement]) -> String {
+        let body_str = emit_block(self, body);
+        let mut writer = CodeWriter::new(self.style);
+        writer.block("", &body_str);
+        return writer.finish();
+    }
+    fn emit_return(&mut self, value: Option<&Expr>) -> String {
+        let Some(value) = value else {
+            return "return;\n".to_string();
+        };
+        let (prefix, value) = self.hoist(value);
+        let output = self.output.clone();
+        let value_str = self.emit_typed(&value, output.as_ref());
+        return format!("{}return {};\n", prefix, value_str);
+    }
+    fn emit_break(&mut self) -> String {
+        return "break;\n".to_string();
+    }
+    fn emit_continue(&mut self) -> String {
+        return "continue;\n".to_string();
+    }
+    fn emit_function(&mut self, function: &Function) -> String {
+        let export_str = if function.public { "export " } else { "" };
+        return format!(
+            "{}function {}",
+            export_str,
+            handle_signature(self, function)
+        );
+    }
+    fn emit_class(&mut self, class: &Class) -> String {
+        let export_str = if class.public { "export " } else { "" };
+        let name = self.name(&class.name);
+        if !self.classes.contains(&class.name) {
+            let mut fields_text = String::new();
+            for field in &class.fields {
+                fields_text += &format!(
+                    "{}: {};\n",
+                    self.name(&field.name),
+                    self.map_type(&field.var_type)
+                );
+            }
+            let mut writer = CodeWriter::new(self.style);
+            writer.block(&format!("{}interface {}", export_str, name), &fields_text);
+            return writer.finish();
+        }
+
+        // fields are set by `Object.assign` after the instance is created
+        let mut fields_text = String::new();
+        for field in &class.fields {
+            fields_text += &format!(
+                "{}!: {};\n",
+                self.name(&field.name),
+                self.map_type(&field.var_type)
+            );
+        }
+        let mut methods_text = String::new();
+        for method in &class.methods {
+            methods_text += &handle_signature(self, method);
+        }
+        let mut writer = CodeWriter::new(self.style);
+        writer.open(&format!("{}class {}", export_str, name));
+        writer.lines(&fields_text);
+        writer.lines(&methods_text);
+        writer.close("");
+        return writer.finish();
+    }
+
+    fn emit_number(&mut self, value: i128) -> String {
+        if self.bigint {
+            return format!("{}n", value);
+        }
+        return value.to_string();
+    }
+    fn emit_string(&mut self, text: &str) -> String {
+        return text.to_string();
+    }
+    // $"[{x}]" -> `[${x}]`
+    fn emit_interpolated(&mut self, parts: &[Interpolation]) -> String {
+        let mut output = String::from("`");
+        for part in parts {
+            match part {
+                Interpolation::Text(text) => {
+                    output += &text.replace('`', "\\`").replace("${", "\\${");
+                }
+                Interpolation::Value(value) => {
+                    output += &format!("${{{}}}", emit_expr(self, value))
+                }
+            }
+        }
+        output.push('`');
+        return output;
+    }
+    fn emit_bool(&mut self, value: bool) -> String {
+        return value.to_string();
+    }
+    fn emit_external(&mut self, name: &str) -> String {
+        if prelude(name).is_some() {
+            self.used.insert(name.to_string());
+        }
+        return name.to_string();
+    }
+    fn emit_unary(&mut self, operator: TokenKind, value: &Expr) -> String {
+        return format!("{}{}", operator_text(operator), emit_expr(self, value));
+    }
+    fn emit_binary(&mut self, l: &Expr, operator: TokenKind, r: &Expr) -> String {
+        // bigints and numbers can't be mixed, the smaller side is converted
+        let operand_type = match operator {
+            TokenKind::And | TokenKind::Or => None,
+            _ => self.operand_type(l, r),
+        };
+        let bigint = operand_type
+            .as_ref()
+            .is_some_and(|var_type| self.map_type(var_type) == "bigint");
+        let (left_str, right_str) = match operand_type {
+            Some(operand_type) => (
+                self.emit_typed(l, Some(&operand_type)),
+                self.emit_typed(r, Some(&operand_type)),
+            ),
+            None => (emit_expr(self, l), emit_expr(self, r)),
+        };
+        let binary = format!("({} {} {})", left_str, operator_text(operator), right_str);
+        // numbers divide without rounding
+        if operator == TokenKind::Slash
+            && !self.bigint
+            && !bigint
+            && self.is_integer(l)
+            && self.is_integer(r)
+        {
+            return format!("Math.trunc{}", binary);
+        }
+        return binary;
+    }
+    fn emit_tuple(&mut self, values: &[Expr]) -> String {
+        return format!("[{}]", emit_values(self, values));
+    }
+    fn emit_static_field(&mut self, value: &Expr, name: &str) -> String {
+        return format!("{}.{}", emit_expr(self, value), name);
+    }
+    // map[x, y] -> map[x][y]
+    // a bigint can't index an array, c# accepts a long index
+    fn emit_index(&mut self, value: &Expr, indexes: &[Expr]) -> String {
+        let mut output = emit_expr(self, value);
+        let index_type = Type::Symbol("i32".to_string());
+        for index in indexes {
+            output += &format!("[{}]", self.emit_typed(index, Some(&index_type)));
+        }
+        return output;
+    }
+    fn emit_call(&mut self, callee: &Callee, arguments: &[Expr]) -> String {
+        match callee {
+            Callee::Function(name) => {
+                let parameters = match self.functions.get(name) {
+                    Some((parameters, _)) => parameters.clone(),
+                    None => Vec::new(),
+                };
+                let arguments: Vec<String> = arguments
+                    .iter()
+                    .enumerate()
+                    .map(|(i, argument)| self.emit_typed(argument, parameters.get(i)))
+                    .collect();
+                return format!("{}({})", self.name(name), arguments.join(", "));
+            }
+            Callee::Method {
+                receiver,
+                name,
+                collection,
+            } => handle_method_call(self, receiver, name, collection.as_deref(), arguments),
+            Callee::Static { type_name, name } => {
+                let type_name = self.emit_external(type_name);
+                let arguments_str = emit_values(self, arguments);
+                return format!("{}.{}({})", type_name, name, arguments_str);
+            }
+            Callee::Value(value) => {
+                let value_str = emit_expr(self, value);
+                return format!("{}({})", value_str, emit_values(self, arguments));
+            }
+        }
+    }
+    fn emit_new(&mut self, class: &str, fields: &[(String, Expr)]) -> String {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(name, value)| {
+                let var_type = self
+                    .field_types
+                    .get(&(class.to_string(), name.clone()))
+                    .cloned();
+                let value_str = self.emit_typed(value, var_type.as_ref());
+                format!("{}: {}", self.name(name), value_str)
+            })
+            .collect();
+        let object = format!("{{ {} }}", fields.join(", "));
+        if self.classes.contains(class) {
+            return format!("Object.assign(new {}(), {})", self.name(class), object);
+        }
+        return object;
+    }
+    fn emit_array(&mut self, values: &[Expr]) -> String {
+        return format!("[{}]", emit_values(self, values));
+    }
+    fn emit_list(&mut self, _element_type: Option<&Type>, values: &[Expr]) -> String {
+        return format!("[{}]", emit_values(self, values));
+    }
+    fn emit_new_collection(&mut self, collection: &str, var_type: Option<&Type>) -> String {
+        let arguments = match var_type {
+            Some(Type::Generic { arguments, .. }) => {
+                let arguments: Vec<String> = arguments
+                    .iter()
+                    .map(|argument| self.map_type(argument))
+                    .collect();
+                format!("<{}>", arguments.join(", "))
+            }
+            _ => String::new(),
+        };
+        match collection {
+            "HashMap" => return format!("new Map{}()", arguments),
+            "HashSet" => return format!("new Set{}()", arguments),
+            _ => return "[]".to_string(),
+        }
+    }
+    fn emit_out(&mut self, name: &str, _var_type: Option<&Type>) -> String {
+        panic!(
+            "out argument: {} has to be hoisted before the statement that uses it",
+            name
+        );
+    }
+    // objects are passed by reference
+    fn emit_ref(&mut self, value: &Expr, _mutable: bool) -> String {
+        return emit_expr(self, value);
+    }
+}
+
+/// `name(a: number): boolean {...}` of functions and methods
+fn handle_signature(generator: &mut TypeScript, function: &Function) -> String {
+    let parameters: Vec<String> = function
+        .parameters
+        .iter()
+        .map(|parameter| {
+            format!(
+                "{}: {}",
+                generator.name(&parameter.name),
+                generator.map_type(&parameter.var_type)
+            )
+        })
+        .collect();
+    let output_str = match &function.output {
+        Some(var_type) => generator.map_type(var_type),
+        None => "void".to_string(),
+    };
+    let outer = std::mem::replace(&mut generator.output, function.output.clone());
+    let inside_str = emit_block(generator, &function.body);
+    generator.output = outer;
+    let signature = format!(
+        "{}({}): {}",
+        generator.name(&function.name),
+        parameters.join(", "),
+        output_str
+    );
+    let mut writer = CodeWriter::new(generator.style);
+    writer.block(&signature, &inside_str);
+    return writer.finish();
+}
+fn is_exported(statement: &Statement) -> bool {
+    match statement {
+        Statement::Function(function) => function.public,
+        Statement::Class(class) => class.public,
+        _ => false,
+    }
+}
+/// v.push_back(1) -> v.push(1), map.insert(k, v) -> map.set(k, v) ...
+fn handle_method_call(
+    generator: &mut TypeScript,
+    receiver: &Expr,
+    name: &str,
+    collection: Option<&str>,
+    arguments: &[Expr],
+) -> String {
+    let receiver_str = emit_expr(generator, receiver);
+    let values: Vec<String> = arguments
+        .iter()
+        .map(|argument| emit_expr(generator, argument))
+        .collect();
+    let arguments_str = values.join(", ");
+    let call = |method: &str| format!("{}.{}({})", receiver_str, method, arguments_str);
+    let output = match (collection.unwrap_or(""), name) {
+        (_, "iter") | (_, "iter_mut") | (_, "into_iter") => receiver_str.clone(),
+        (_, "to_string") => format!("String({})", receiver_str),
+
+        ("Vec" | "VecDeque", "push" | "push_back") => call("push"),
+        ("Vec", "insert") if values.len() == 2 => {
+            format!("{}.splice({}, 0, {})", receiver_str, values[0], values[1])
+        }
+        ("Vec", "remove") => format!("{}.splice({}, 1)[0]", receiver_str, arguments_str),
+        // c# throws on an empty collection instead of returning undefined
+        ("VecDeque", "pop_front") => format!("{}!", call("shift")),
+        ("Vec" | "VecDeque", "pop" | "pop_back") => format!("{}!", call("pop")),
+        ("VecDeque", "front") => format!("{}[0]", receiver_str),
+        ("Vec" | "VecDeque" | "String" | "Array", "contains") => call("includes"),
+        ("Vec" | "VecDeque", "clear") => format!("{}.length = 0", receiver_str),
+
+        ("HashMap", "insert") => call("set"),
+        ("HashMap", "get") => call("get"),
+        ("HashMap", "contains_key") | ("HashSet", "contains") => call("has"),
+        ("HashMap" | "HashSet", "remove") => call("delete"),
+        ("HashMap" | "HashSet", "clear") => call("clear"),
+        ("HashMap", "keys") => format!("[...{}.keys()]", receiver_str),
+        ("HashMap", "values") => format!("[...{}.values()]", receiver_str),
+        ("HashSet", "insert") => call("add"),
+
+        ("String", "push_str") => format!("{} += {}", receiver_str, arguments_str),
+        ("HashMap" | "HashSet", "len") => format!("{}.size", receiver_str),
+        ("HashMap" | "HashSet", "is_empty") => format!("({}.size === 0)", receiver_str),
+        (_, "len") if collection.is_some() => format!("{}.length", receiver_str),
+        (_, "is_empty") if collection.is_some() => format!("({}.length === 0)", receiver_str),
+
+        _ => call(name),
+    };
+    return output;
+}
+/// ts spelling of an operator, `==` compares without conversions
+fn operator_text(operator: TokenKind) -> &'static str {
+    match operator {
+        TokenKind::Plus => "+",
+        TokenKind::Minus => "-",
+        TokenKind::Star => "*",
+        TokenKind::Slash => "/",
+        TokenKind::Percent => "%",
+        TokenKind::Less => "<",
+        TokenKind::LessEquals => "<=",
+        TokenKind::Greater => ">",
+        TokenKind::GreaterEquals => ">=",
+        TokenKind::Equals => "===",
+        TokenKind::NotEquals => "!==",
+        TokenKind::Or => "||",
+        TokenKind::And => "&&",
+        TokenKind::Not => "!",
+        TokenKind::Assignment => "=",
+        TokenKind::PlusEquals => "+=",
+        TokenKind::MinusEquals => "-=",
+        default => panic!("operator: {:?} doesn't have a handler", default),
+    }
+}
+
+fn handle_type(generator: &TypeScript, var_type: &Type) -> String {
+    match var_type {
+        Type::Symbol(symbol) => handle_symbol_type(generator, symbol),
+        // int[,] -> number[][]
+        Type::Array {
+            left_type,
+            dimensions,
+        } => format!(
+            "{}{}",
+            handle_type(generator, left_type),
+            "[]".repeat(dimensions + 1)
+        ),
+        Type::Generic { name, arguments } => handle_generic_type(generator, name, arguments),
+        Type::Tuple(types) => handle_tuple_type(generator, types),
+        // objects are passed by reference
+        Type::Reference { inner, .. } => handle_type(generator, inner),
+    }
+}
+fn handle_generic_type(generator: &TypeScript, name: &str, arguments: &[Type]) -> String {
+    let arguments: Vec<String> = arguments
+        .iter()
+        .map(|argument| handle_type(generator, argument))
+        .collect();
+    match (name, arguments.as_slice()) {
+        ("Vec" | "VecDeque", [element]) => return format!("{}[]", element),
+        ("HashMap", _) => return format!("Map<{}>", arguments.join(", ")),
+        ("HashSet", _) => return format!("Set<{}>", arguments.join(", ")),
+        _ => return format!("{}<{}>", name, arguments.join(", ")),
+    }
+}
+fn handle_tuple_type(generator: &TypeScript, types: &[Type]) -> String {
+    if types.is_empty() {
+        return "void".to_string();
+    }
+    let types: Vec<String> = types
+        .iter()
+        .map(|var_type| handle_type(generator, var_type))
+        .collect();
+    return format!("[{}]", types.join(", "));
+}
+fn handle_symbol_type(generator: &TypeScript, symbol: &str) -> String {
+    if NUMBERS.contains(&symbol) {
+        return "number".to_string();
+    }
+    if BIG_INTEGERS.contains(&symbol) {
+        return "bigint".to_string();
+    }
+    match symbol {
+        "bool" => return "boolean".to_string(),
+        "str" | "String" | "string" | "char" => return "string".to_string(),
+        _ => return generator.name(symbol),
+    }
+}
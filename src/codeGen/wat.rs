@@ -0,0 +1,1743 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{
+    backend::{
+        emit_block, emit_expr, emit_statement, hoist_out_calls, split_short_circuits, Backend,
+        OutputFile,
+    },
+    ir::{Callee, Class, Expr, Field, Function, Interpolation, Statement},
+    reserved::ReservedWords,
+    tokens::TokenKind,
+    types::Type,
+    wat_check::{self, ValueType},
+    writer::{CodeStyle, CodeWriter},
+};
+
+const INTEGERS_32: &[&str] = &[
+    "i8", "i16", "i32", "isize", "u8", "u16", "u32", "usize", "sbyte", "short", "int", "byte",
+    "ushort", "uint", "nint", "nuint", "char",
+];
+const INTEGERS_64: &[&str] = &["i64", "u64", "long", "ulong"];
+const UNSIGNED: &[&str] = &[
+    "u8", "u16", "u32", "u64", "usize", "byte", "ushort", "uint", "ulong", "nuint", "char",
+];
+const FLOATS_32: &[&str] = &["f32", "float"];
+const FLOATS_64: &[&str] = &["f64", "double", "decimal"];
+/// address of the first string, 0 stays the null pointer
+const DATA_START: usize = 8;
+
+/// functions of the host the module imports, only the used ones are declared
+fn import(name: &str) -> &'static str {
+    match name {
+        "print_i64" => "(import \"console\" \"print_i64\" (func $print_i64 (param i64)))\n",
+        "print_f64" => "(import \"console\" \"print_f64\" (func $print_f64 (param f64)))\n",
+        "print_str" => "(import \"console\" \"print_str\" (func $print_str (param i32)))\n",
+        // the host writes the line with `alloc`, 0 at the end of the input
+        "read_line" => "(import \"console\" \"read_line\" (func $read_line (result i32)))\n",
+        name => panic!("import: {} doesn't exist", name),
+    }
+}
+
+/// bump allocator, the memory is never freed and grows when the heap reaches its end
+const ALLOC: &str = "(func $alloc (export \"alloc\") (param $size i32) (result i32)
+	(local $pointer i32)
+	(local $end i32)
+	(local.set $pointer (global.get $__heap))
+	(local.set $end (i32.and (i32.add (i32.add (local.get $pointer) (local.get $size)) (i32.const 7)) (i32.const -8)))
+	(if (i32.gt_u (local.get $end) (i32.shl (memory.size) (i32.const 16)))
+		(then
+			(if (i32.eq (memory.grow (i32.shr_u (i32.sub (i32.add (local.get $end) (i32.const 65535)) (i32.shl (memory.size) (i32.const 16))) (i32.const 16))) (i32.const -1))
+				(then
+					(unreachable)
+				)
+			)
+		)
+	)
+	(global.set $__heap (local.get $end))
+	(local.get $pointer)
+)
+";
+
+/// helper functions of the generated code, only the used ones are emitted.
+/// Strings are pointers to their length followed by the utf-8 bytes,
+/// arrays are pointers to the length of every dimension followed by the elements
+fn runtime(name: &str) -> String {
+    if let Some(rank) = name.strip_prefix("rt_element_") {
+        let rank: usize = rank.parse().expect("rt_element_ ends with the rank");
+        return element_function(rank);
+    }
+    let definition = match name {
+        "rt_concat" => {
+            "(func $rt_concat (param $a i32) (param $b i32) (result i32)
+	(local $length_a i32)
+	(local $length_b i32)
+	(local $result i32)
+	(local.set $length_a (i32.load (local.get $a)))
+	(local.set $length_b (i32.load (local.get $b)))
+	(local.set $result (call $alloc (i32.add (i32.const 4) (i32.add (local.get $length_a) (local.get $length_b)))))
+	(i32.store (local.get $result) (i32.add (local.get $length_a) (local.get $length_b)))
+	(memory.copy (i32.add (local.get $result) (i32.const 4)) (i32.add (local.get $a) (i32.const 4)) (local.get $length_a))
+	(memory.copy (i32.add (i32.add (local.get $result) (i32.const 4)) (local.get $length_a)) (i32.add (local.get $b) (i32.const 4)) (local.get $length_b))
+	(local.get $result)
+)
+"
+        }
+        "rt_int_to_string" => {
+            "(func $rt_int_to_string (param $value i64) (param $unsigned i32) (result i32)
+	(local $buffer i32)
+	(local $position i32)
+	(local $negative i32)
+	(local $magnitude i64)
+	(local $result i32)
+	(local.set $buffer (call $alloc (i32.const 24)))
+	(local.set $position (i32.add (local.get $buffer) (i32.const 24)))
+	(local.set $negative (i32.and (i32.eqz (local.get $unsigned)) (i64.lt_s (local.get $value) (i64.const 0))))
+	(local.set $magnitude (local.get $value))
+	(if (local.get $negative)
+		(then
+			(local.set $magnitude (i64.sub (i64.const 0) (local.get $value)))
+		)
+	)
+	(loop $digits
+		(local.set $position (i32.sub (local.get $position) (i32.const 1)))
+		(i32.store8 (local.get $position) (i32.add (i32.const 48) (i32.wrap_i64 (i64.rem_u (local.get $magnitude) (i64.const 10)))))
+		(local.set $magnitude (i64.div_u (local.get $magnitude) (i64.const 10)))
+		(br_if $digits (i64.ne (local.get $magnitude) (i64.const 0)))
+	)
+	(if (local.get $negative)
+		(then
+			(local.set $position (i32.sub (local.get $position) (i32.const 1)))
+			(i32.store8 (local.get $position) (i32.const 45))
+		)
+	)
+	(local.set $result (call $alloc (i32.sub (i32.add (local.get $buffer) (i32.const 28)) (local.get $position))))
+	(i32.store (local.get $result) (i32.sub (i32.add (local.get $buffer) (i32.const 24)) (local.get $position)))
+	(memory.copy (i32.add (local.get $result) (i32.const 4)) (local.get $position) (i32.load (local.get $result)))
+	(local.get $result)
+)
+"
+        }
+        "rt_str_eq" => {
+            "(func $rt_str_eq (param $a i32) (param $b i32) (result i32)
+	(local $index i32)
+	(if (i32.eq (local.get $a) (local.get $b))
+		(then
+			(return (i32.const 1))
+		)
+	)
+	(if (i32.or (i32.eqz (local.get $a)) (i32.eqz (local.get $b)))
+		(then
+			(return (i32.const 0))
+		)
+	)
+	(if (i32.ne (i32.load (local.get $a)) (i32.load (local.get $b)))
+		(then
+			(return (i32.const 0))
+		)
+	)
+	(block $done
+		(loop $bytes
+			(br_if $done (i32.ge_u (local.get $index) (i32.load (local.get $a))))
+			(if (i32.ne (i32.load8_u offset=4 (i32.add (local.get $a) (local.get $index))) (i32.load8_u offset=4 (i32.add (local.get $b) (local.get $index))))
+				(then
+					(return (i32.const 0))
+				)
+			)
+			(local.set $index (i32.add (local.get $index) (i32.const 1)))
+			(br $bytes)
+		)
+	)
+	(i32.const 1)
+)
+"
+        }
+        // returns whether the text is a number and the number
+        "rt_try_parse_int" => {
+            "(func $rt_try_parse_int (param $text i32) (param $unsigned i32) (result i32 i64)
+	(local $index i32)
+	(local $length i32)
+	(local $negative i32)
+	(local $digit i32)
+	(local $value i64)
+	(if (i32.eqz (local.get $text))
+		(then
+			(return (i32.const 0) (i64.const 0))
+		)
+	)
+	(local.set $length (i32.load (local.get $text)))
+	(if (i32.gt_u (local.get $length) (i32.const 0))
+		(then
+			(local.set $digit (i32.load8_u offset=4 (local.get $text)))
+			(if (i32.or (i32.eq (local.get $digit) (i32.const 45)) (i32.eq (local.get $digit) (i32.const 43)))
+				(then
+					(local.set $negative (i32.eq (local.get $digit) (i32.const 45)))
+					(local.set $index (i32.const 1))
+				)
+			)
+		)
+	)
+	(if (i32.ge_u (local.get $index) (local.get $length))
+		(then
+			(return (i32.const 0) (i64.const 0))
+		)
+	)
+	(block $done
+		(loop $digits
+			(br_if $done (i32.ge_u (local.get $index) (local.get $length)))
+			(local.set $digit (i32.sub (i32.load8_u offset=4 (i32.add (local.get $text) (local.get $index))) (i32.const 48)))
+			(if (i32.gt_u (local.get $digit) (i32.const 9))
+				(then
+					(return (i32.const 0) (i64.const 0))
+				)
+			)
+			(local.set $value (i64.add (i64.mul (local.get $value) (i64.const 10)) (i64.extend_i32_u (local.get $digit))))
+			(local.set $index (i32.add (local.get $index) (i32.const 1)))
+			(br $digits)
+		)
+	)
+	(if (local.get $negative)
+		(then
+			(if (local.get $unsigned)
+				(then
+					(return (i64.eqz (local.get $value)) (i64.const 0))
+				)
+			)
+			(local.set $value (i64.sub (i64.const 0) (local.get $value)))
+		)
+	)
+	(i32.const 1)
+	(local.get $value)
+)
+"
+        }
+        // traps like the exception of c#
+        "rt_parse_int" => {
+            "(func $rt_parse_int (param $text i32) (param $unsigned i32) (result i64)
+	(local $value i64)
+	(call $rt_try_parse_int (local.get $text) (local.get $unsigned))
+	(local.set $value)
+	(if (i32.eqz)
+		(then
+			(unreachable)
+		)
+	)
+	(local.get $value)
+)
+"
+        }
+        // c# counts the elements of every dimension
+        "rt_length" => {
+            "(func $rt_length (param $array i32) (param $rank i32) (result i32)
+	(local $length i32)
+	(local.set $length (i32.const 1))
+	(block $done
+		(loop $dimensions
+			(br_if $done (i32.eqz (local.get $rank)))
+			(local.set $rank (i32.sub (local.get $rank) (i32.const 1)))
+			(local.set $length (i32.mul (local.get $length) (i32.load (i32.add (local.get $array) (i32.shl (local.get $rank) (i32.const 2))))))
+			(br $dimensions)
+		)
+	)
+	(local.get $length)
+)
+"
+        }
+        name => panic!("runtime function: {} doesn't exist", name),
+    };
+    return definition.to_string();
+}
+/// runtime functions the runtime function calls
+fn runtime_dependencies(name: &str) -> &'static [&'static str] {
+    match name {
+        "rt_parse_int" => &["rt_try_parse_int"],
+        _ => &[],
+    }
+}
+/// address of map[x, y], traps when an index is outside of its dimension
+fn element_function(rank: usize) -> String {
+    let mut parameters = String::new();
+    let mut body = String::new();
+    for dimension in 0..rank {
+        let length = format!("(i32.load offset={} (local.get $array))", dimension * 4);
+        parameters += &format!(" (param $i{} i32)", dimension);
+        body += &format!(
+            "\t(if (i32.ge_u (local.get $i{}) {})\n\t\t(then\n\t\t\t(unreachable)\n\t\t)\n\t)\n",
+            dimension, length
+        );
+        body += &match dimension {
+            0 => "\t(local.set $index (local.get $i0))\n".to_string(),
+            _ => format!(
+                "\t(local.set $index (i32.add (i32.mul (local.get $index) {}) (local.get $i{})))\n",
+                length, dimension
+            ),
+        };
+    }
+    return format!(
+        "(func $rt_element_{} (param $array i32){} (param $size i32) (result i32)\n\t(local $index i32)\n{}\t(i32.add (i32.add (local.get $array) (i32.const {})) (i32.mul (local.get $index) (local.get $size)))\n)\n",
+        rank,
+        parameters,
+        body,
+        header_size(rank)
+    );
+}
+
+/// `(header`, the body one level deeper and the closing paren on its own line
+fn list(writer: &mut CodeWriter, header: &str, body: &str) {
+    writer.line(header);
+    writer.nested(body);
+    writer.line(")");
+}
+
+pub struct Wat {
+    pub reserved: ReservedWords,
+    pub style: CodeStyle,
+    /// functions of the host the generated code calls
+    imports: BTreeSet<&'static str>,
+    /// runtime functions the generated code calls
+    used: BTreeSet<String>,
+    /// `(data ...)` of the string literals
+    data: String,
+    /// bytes of a string literal -> its address
+    strings: HashMap<Vec<u8>, usize>,
+    /// first free address after the strings, the heap starts there
+    data_end: usize,
+    /// fields of the classes in declaration order
+    classes: HashMap<String, Vec<Field>>,
+    /// name -> (types of the parameters, output)
+    functions: HashMap<String, (Vec<Type>, Option<Type>)>,
+    /// top level variables
+    globals: HashMap<String, Type>,
+    /// locals of the function being generated, they are declared at its start
+    locals: Vec<(String, ValueType)>,
+    /// parameters and locals of the function being generated
+    variables: HashMap<String, Type>,
+    /// output of the function being generated
+    output: Option<Type>,
+    /// names of the results of calls with `out` arguments
+    results: HashSet<String>,
+    /// loops around the current statement, `break` and `continue` branch to their labels
+    loops: Vec<usize>,
+    /// counter for the labels and the temporary locals
+    labels: usize,
+}
+impl Wat {
+    pub fn new() -> Wat {
+        Wat {
+            reserved: ReservedWords::wat(),
+            style: CodeStyle::new(),
+            imports: BTreeSet::new(),
+            used: BTreeSet::new(),
+            data: String::new(),
+            strings: HashMap::new(),
+            data_end: DATA_START,
+            classes: HashMap::new(),
+            functions: HashMap::new(),
+            globals: HashMap::new(),
+            locals: Vec::new(),
+            variables: HashMap::new(),
+            output: None,
+            results: HashSet::new(),
+            loops: Vec::new(),
+            labels: 0,
+        }
+    }
+    fn use_runtime(&mut self, name: &str) -> String {
+        for dependency in runtime_dependencies(name) {
+            self.used.insert(dependency.to_string());
+        }
+        self.used.insert(name.to_string());
+        return format!("${}", name);
+    }
+    fn use_import(&mut self, name: &'static str) -> String {
+        self.imports.insert(name);
+        return format!("${}", name);
+    }
+    /// locals are declared for the whole function, shadowed names are already renamed
+    fn declare_local(&mut self, name: &str, var_type: &Type) {
+        self.variables.insert(name.to_string(), var_type.clone());
+        self.declare_temporary(&self.name(name), value_type(var_type));
+    }
+    fn declare_temporary(&mut self, name: &str, value_type: ValueType) {
+        match self.locals.iter().find(|(local, _)| local == name) {
+            Some((_, declared)) if *declared != value_type => {
+                panic!(
+                    "local: {} is declared as {:?} and {:?}",
+                    name, declared, value_type
+                )
+            }
+            Some(_) => {}
+            None => self.locals.push((name.to_string(), value_type)),
+        }
+    }
+    fn begin_loop(&mut self) -> usize {
+        self.labels += 1;
+        self.loops.push(self.labels);
+        return self.labels;
+    }
+    /// `start` and the blocks of a loop, `break` leaves `$exit_id`
+    fn emit_loop(&self, id: usize, start: &str, iteration: &str) -> String {
+        let mut inner = CodeWriter::new(self.style);
+        list(&mut inner, &format!("(loop $loop_{}", id), iteration);
+        let mut writer = CodeWriter::new(self.style);
+        writer.lines(start);
+        list(
+            &mut writer,
+            &format!("(block $exit_{}", id),
+            &inner.finish(),
+        );
+        return writer.finish();
+    }
+    fn variable_type(&self, name: &str) -> Option<Type> {
+        return self
+            .variables
+            .get(name)
+            .or_else(|| self.globals.get(name))
+            .cloned();
+    }
+    /// `(local.get $x)` or `(global.get $x)` for the top level variables
+    fn get(&self, name: &str) -> String {
+        if !self.variables.contains_key(name) && self.globals.contains_key(name) {
+            return format!("(global.get ${})", self.name(name));
+        }
+        return format!("(local.get ${})", self.name(name));
+    }
+    /// without a value it takes the one on the stack
+    fn set(&self, name: &str, value_str: &str) -> String {
+        let space = if value_str.is_empty() { "" } else { " " };
+        if !self.variables.contains_key(name) && self.globals.contains_key(name) {
+            return format!("(global.set ${}{}{})", self.name(name), space, value_str);
+        }
+        return format!("(local.set ${}{}{})", self.name(name), space, value_str);
+    }
+    /// calls with `out` arguments return the out values after their result,
+    /// they are stored before the statement that uses them
+    fn hoist(&mut self, value: &Expr) -> (String, Expr) {
+        let mut value = value.clone();
+        let mut prefix = String::new();
+        for statement in split_short_circuits(&mut value, &mut self.labels) {
+            prefix += &emit_statement(self, &statement);
+        }
+        let calls = hoist_out_calls(&mut value, &mut self.labels);
+        let temporaries: Vec<String> = calls.iter().map(|call| call.temporary.clone()).collect();
+        unwrap_results(&mut value, &temporaries);
+        for mut call in calls {
+            unwrap_results(&mut call.call, &temporaries);
+            let [(name, var_type)] = call.outs.as_slice() else {
+                panic!("only calls with one out argument are supported by the wat backend");
+            };
+            let out_type = match var_type {
+                Some(var_type) => {
+                    self.declare_local(name, var_type);
+                    var_type.clone()
+                }
+                None => self
+                    .variable_type(name)
+                    .unwrap_or_else(|| panic!("type of: {} isn't known", name)),
+            };
+            self.declare_temporary(&call.temporary, ValueType::I32);
+            self.results.insert(call.temporary.clone());
+
+            prefix += &format!("{}\n", emit_expr(self, &call.call));
+            // the parsed number is an i64 on top of the stack
+            match value_type(&out_type) {
+                ValueType::I64 => {}
+                ValueType::I32 => prefix += "(i32.wrap_i64)\n",
+                _ => panic!("out argument: {} has to be an integer", name),
+            }
+            prefix += &format!("{}\n", self.set(name, ""));
+            prefix += &format!("(local.set ${})\n", call.temporary);
+        }
+        return (prefix, value);
+    }
+
+    fn type_of(&self, value: &Expr) -> Option<Type> {
+        match value {
+            Expr::Number(_) => Some(symbol("i32")),
+            Expr::Float(_) => Some(symbol("f64")),
+            Expr::String(_) | Expr::Interpolated(_) => Some(symbol("str")),
+            Expr::Bool(_) => Some(symbol("bool")),
+            Expr::Variable { name, var_type } => {
+                match var_type.clone().or_else(|| self.variable_type(name))? {
+                    Type::Reference { inner, .. } => Some(*inner),
+                    var_type => Some(var_type),
+                }
+            }
+            Expr::External(name) if self.results.contains(name) => Some(symbol("bool")),
+            Expr::Unary {
+                operator: TokenKind::Not,
+                ..
+            } => Some(symbol("bool")),
+            Expr::Unary { value, .. } => self.type_of(value),
+            Expr::Binary { l, operator, r } => match operator {
+                TokenKind::Plus if self.is_string(l) || self.is_string(r) => Some(symbol("str")),
+                TokenKind::Plus
+                | TokenKind::Minus
+                | TokenKind::Star
+                | TokenKind::Slash
+                | TokenKind::Percent => Some(self.operand_type(l, r)),
+                _ => Some(symbol("bool")),
+            },
+            Expr::Field { value, name } => {
+                let (_, field_type) = self.field(value, name);
+                Some(field_type)
+            }
+            Expr::Index { value, .. } => match self.type_of(value)? {
+                Type::Array { left_type, .. } => Some(*left_type),
+                _ => None,
+            },
+            Expr::Call { callee, .. } => self.call_type(callee),
+            Expr::New { class, .. } => Some(symbol(class)),
+            Expr::Ref { value, .. } => self.type_of(value),
+            _ => None,
+        }
+    }
+    fn call_type(&self, callee: &Callee) -> Option<Type> {
+        let (type_name, name) = match callee {
+            Callee::Function(name) => return self.functions.get(name)?.1.clone(),
+            Callee::Method { receiver, name, .. } => match receiver.as_ref() {
+                Expr::External(type_name) => (type_name.as_str(), name.as_str()),
+                _ if name == "len" => return Some(symbol("i32")),
+                _ => return None,
+            },
+            Callee::Static { type_name, name } => (type_name.as_str(), name.as_str()),
+            Callee::Value(_) => return None,
+        };
+        match (type_name, name) {
+            ("Console", "ReadLine") => return Some(symbol("str")),
+            (number, "Parse") => return Some(symbol(number)),
+            (_, "TryParse") => return Some(symbol("bool")),
+            _ => return None,
+        }
+    }
+    fn is_string(&self, value: &Expr) -> bool {
+        return self
+            .type_of(value)
+            .is_some_and(|var_type| is_string_type(&var_type));
+    }
+    /// type both sides of an operator are converted to, a literal takes the type of the other side
+    fn operand_type(&self, l: &Expr, r: &Expr) -> Type {
+        let left = self.type_of(l);
+        let right = self.type_of(r);
+        match (l, r, &left, &right) {
+            (Expr::Float(_), _, _, Some(other)) | (_, Expr::Float(_), Some(other), _)
+                if is_number(other) && !value_type(other).is_float() =>
+            {
+                return symbol("f64");
+            }
+            (Expr::Number(_) | Expr::Float(_), _, _, Some(other))
+            | (_, Expr::Number(_) | Expr::Float(_), Some(other), _)
+                if is_number(other) =>
+            {
+                return other.clone();
+            }
+            (_, _, Some(left), Some(right)) => {
+                if rank(value_type(right)) > rank(value_type(left)) {
+                    return right.clone();
+                }
+                return left.clone();
+            }
+            _ => return left.or(right).unwrap_or_else(|| symbol("i32")),
+        }
+    }
+    /// (offset, type) of a field of the class of `value`
+    fn field(&self, value: &Expr, name: &str) -> (usize, Type) {
+        let Some(Type::Symbol(class)) = self.type_of(value) else {
+            panic!("field: {} of a value of unknown type", name);
+        };
+        let Some(fields) = self.classes.get(&class) else {
+            panic!("class: {} isn't declared", class);
+        };
+        let (offsets, _) = layout(fields);
+        let Some(index) = fields.iter().position(|field| field.name == name) else {
+            panic!("class: {} doesn't have the field: {}", class, name);
+        };
+        return (offsets[index], fields[index].var_type.clone());
+    }
+    /// `value` converted to `target`, literals are written in the type they are used as
+    fn emit_as(&mut self, value: &Expr, target: &Type) -> String {
+        match value {
+            Expr::Number(number) => return constant(value_type(target), *number),
+            Expr::Float(number) => return float_constant(value_type(target), *number),
+            Expr::Array(values) => return self.array_literal(values, Some(target)),
+            _ => {}
+        }
+        let value_str = emit_expr(self, value);
+        match self.type_of(value) {
+            Some(from) => return convert(value_str, &from, target),
+            None => return value_str,
+        }
+    }
+    /// pointer to the text of a value, for `+` and interpolation
+    fn emit_text(&mut self, value: &Expr) -> String {
+        let Some(var_type) = self.type_of(value) else {
+            panic!("type of the value: {:?} isn't known", value);
+        };
+        if is_string_type(&var_type) {
+            return emit_expr(self, value);
+        }
+        if matches!(&var_type, Type::Symbol(name) if name == "bool") {
+            // c# writes booleans capitalized
+            let true_str = self.string_literal(b"True");
+            let false_str = self.string_literal(b"False");
+            return format!(
+                "(select {} {} {})",
+                true_str,
+                false_str,
+                emit_expr(self, value)
+            );
+        }
+        if !is_number(&var_type) || value_type(&var_type).is_float() {
+            panic!(
+                "values of type: {:?} can't be written as text by the wat backend",
+                var_type
+            );
+        }
+        let to_string = self.use_runtime("rt_int_to_string");
+        let value_str = convert(emit_expr(self, value), &var_type, &symbol("i64"));
+        return format!(
+            "(call {} {} (i32.const {}))",
+            to_string,
+            value_str,
+            is_unsigned(&var_type) as i32
+        );
+    }
+    /// pointer to a string in the data segment, the same text is stored once
+    fn string_literal(&mut self, bytes: &[u8]) -> String {
+        if let Some(address) = self.strings.get(bytes) {
+            return format!("(i32.const {})", address);
+        }
+        let address = self.data_end;
+        let mut content = String::new();
+        for byte in (bytes.len() as u32).to_le_bytes().iter().chain(bytes) {
+            match byte {
+                b' '..=b'~' if *byte != b'"' && *byte != b'\\' => content.push(*byte as char),
+                byte => content += &format!("\\{:02x}", byte),
+            }
+        }
+        self.data += &format!("(data (i32.const {}) \"{}\")\n", address, content);
+        self.strings.insert(bytes.to_vec(), address);
+        self.data_end = (address + 4 + bytes.len()).next_multiple_of(4);
+        return format!("(i32.const {})", address);
+    }
+    /// `{{1, 2}, {3, 4}}` -> allocated array with its dimensions and the elements in row order
+    fn array_literal(&mut self, values: &[Expr], target: Option<&Type>) -> String {
+        let mut dimensions = Vec::new();
+        let mut elements = Vec::new();
+        array_shape(values, 0, &mut dimensions, &mut elements);
+        let element_type = match target {
+            Some(Type::Array { left_type, .. }) => left_type.as_ref().clone(),
+            _ => elements
+                .first()
+                .and_then(|element| self.type_of(element))
+                .unwrap_or_else(|| symbol("i32")),
+        };
+        let element_value = value_type(&element_type);
+        let size = size_of(element_value);
+        let header = header_size(dimensions.len());
+
+        self.labels += 1;
+        let temporary = format!("__array{}", self.labels);
+        self.declare_temporary(&temporary, ValueType::I32);
+        let mut output = format!(
+            "(block (result i32) (local.set ${} (call $alloc (i32.const {})))",
+            temporary,
+            header + elements.len() * size
+        );
+        for (index, length) in dimensions.iter().enumerate() {
+            output += &format!(
+                " (i32.store offset={} (local.get ${}) (i32.const {}))",
+                index * 4,
+                temporary,
+                length
+            );
+        }
+        for (index, element) in elements.iter().enumerate() {
+            let element_str = self.emit_as(element, &element_type);
+            output += &format!(
+                " ({}.store offset={} (local.get ${}) {})",
+                element_value.name(),
+                header + index * size,
+                temporary,
+                element_str
+            );
+        }
+        output += &format!(" (local.get ${}))", temporary);
+        return output;
+    }
+    /// address of `map[x, y]`
+    fn element_address(&mut self, value: &Expr, indexes: &[Expr]) -> (String, ValueType) {
+        let Some(Type::Array {
+            left_type,
+            dimensions,
+        }) = self.type_of(value)
+        else {
+            panic!("only arrays can be indexed by the wat backend");
+        };
+        let rank = dimensions + 1;
+        if indexes.len() != rank {
+            panic!("array with {} dimensions needs {} indexes", rank, rank);
+        }
+        let element = value_type(&left_type);
+        let function = self.use_runtime(&format!("rt_element_{}", rank));
+        let mut arguments = vec![emit_expr(self, value)];
+        for index in indexes {
+            arguments.push(self.emit_as(index, &symbol("i32")));
+        }
+        let address = format!(
+            "(call {} {} (i32.const {}))",
+            function,
+            arguments.join(" "),
+            size_of(element)
+        );
+        return (address, element);
+    }
+    fn handle_function(&mut self, function: &Function, export: &str) -> String {
+        self.locals.clear();
+        self.variables.clear();
+        self.output = function.output.clone();
+        let mut parameters = String::new();
+        for parameter in &function.parameters {
+            check_reference(&parameter.var_type, &parameter.name);
+            self.variables
+                .insert(parameter.name.clone(), parameter.var_type.clone());
+            parameters += &format!(
+                " (param ${} {})",
+                self.name(&parameter.name),
+                value_type(&parameter.var_type).name()
+            );
+        }
+        let body_str = emit_block(self, &function.body);
+
+        let mut output = format!(
+            "(func ${}{}{}",
+            self.name(&function.name),
+            export,
+            parameters
+        );
+        if let Some(var_type) = &function.output {
+            output += &format!(" (result {})", value_type(var_type).name());
+        }
+        let mut body = String::new();
+        for (name, value_type) in &self.locals {
+            body += &format!("(local ${} {})\n", name, value_type.name());
+        }
+        body += &body_str;
+        // every path returned already, the end of the function is never reached
+        if function.output.is_some() {
+            body += "(unreachable)\n";
+        }
+        self.variables.clear();
+        let mut writer = CodeWriter::new(self.style);
+        list(&mut writer, &output, &body);
+        return writer.finish();
+    }
+    /// `Point_new` allocates an instance and stores the fields in it
+    fn handle_constructor(&self, class: &Class) -> String {
+        let (offsets, size) = layout(&class.fields);
+        let mut parameters = String::new();
+        let mut stores = String::new();
+        for (field, offset) in class.fields.iter().zip(offsets) {
+            let field_value = value_type(&field.var_type);
+            parameters += &format!(
+                " (param ${} {})",
+                self.name(&field.name),
+                field_value.name()
+            );
+            stores += &format!(
+                "({}.store offset={} (local.get $__pointer) (local.get ${}))\n",
+                field_value.name(),
+                offset,
+                self.name(&field.name)
+            );
+        }
+        let body = format!(
+            "(local $__pointer i32)\n(local.set $__pointer (call $alloc (i32.const {})))\n{}(local.get $__pointer)\n",
+            size, stores
+        );
+        let mut writer = CodeWriter::new(self.style);
+        list(
+            &mut writer,
+            &format!(
+                "(func ${}_new{} (result i32)",
+                self.name(&class.name),
+                parameters
+            ),
+            &body,
+        );
+        return writer.finish();
+    }
+    /// `(global $x (mut i32) ...)`, a value that isn't a constant is stored at the start of `main`
+    fn handle_global(
+        &mut self,
+        name: &str,
+        var_type: &Type,
+        mutable: bool,
+        value: Option<&Expr>,
+    ) -> (String, String) {
+        let global_value = value_type(var_type);
+        let global_type = match mutable {
+            true => format!("(mut {})", global_value.name()),
+            false => global_value.name().to_string(),
+        };
+        let (prefix, value_str) = match value {
+            Some(value) => {
+                let (prefix, value) = self.hoist(value);
+                (prefix, self.emit_as(&value, var_type))
+            }
+            None => (String::new(), constant(global_value, 0)),
+        };
+        // a single constant like a number or the address of a string
+        let constant_prefix = format!("({}.const ", global_value.name());
+        if prefix.is_empty()
+            && value_str.starts_with(&constant_prefix)
+            && value_str.matches('(').count() == 1
+        {
+            let global = format!(
+                "(global ${} {} {})\n",
+                self.name(name),
+                global_type,
+                value_str
+            );
+            return (global, String::new());
+        }
+        let global = format!(
+            "(global ${} (mut {}) {})\n",
+            self.name(name),
+            global_value.name(),
+            constant(global_value, 0)
+        );
+        return (
+            global,
+            format!("{}{}\n", prefix, self.set(name, &value_str)),
+        );
+    }
+}
+
+impl Backend for Wat {
+    fn extension(&self) -> &'static str {
+        return "wat";
+    }
+    fn reserved(&self) -> &ReservedWords {
+        return &self.reserved;
+    }
+    fn map_type(&self, var_type: &Type) -> String {
+        return value_type(var_type).name().to_string();
+    }
+    /// the top level lets become globals and the top level statements the exported `main`
+    fn generate(&mut self, program: &[Statement]) -> Vec<OutputFile> {
+        for statement in program {
+            match statement {
+                Statement::Class(class) => {
+                    if !class.methods.is_empty() {
+                        panic!("methods aren't supported by the wat backend");
+                    }
+                    self.classes
+                        .insert(class.name.clone(), class.fields.clone());
+                }
+                Statement::Function(function) => {
+                    let parameters = function
+                        .parameters
+                        .iter()
+                        .map(|parameter| parameter.var_type.clone())
+                        .collect();
+                    self.functions
+                        .insert(function.name.clone(), (parameters, function.output.clone()));
+                }
+                Statement::Let {
+                    name,
+                    var_type,
+                    inferred,
+                    value,
+                    ..
+                } => {
+                    let var_type = var_type
+                        .clone()
+                        .or(inferred.clone())
+                        .or_else(|| value.as_ref().and_then(|value| self.type_of(value)))
+                        .unwrap_or_else(|| panic!("type of: {} isn't known", name));
+                    self.globals.insert(name.clone(), var_type);
+                }
+                Statement::LetTuple { .. } => panic!("tuples aren't supported by the wat backend"),
+                _ => {}
+            }
+        }
+
+        let mut definitions = String::new();
+        for statement in program {
+            match statement {
+                Statement::Class(class) => definitions += &self.handle_constructor(class),
+                Statement::Function(function) => {
+                    let export = match function.public {
+                        true => format!(" (export \"{}\")", function.name),
+                        false => String::new(),
+                    };
+                    definitions += &self.handle_function(function, &export);
+                }
+                _ => {}
+            }
+        }
+
+        // `main` is generated like a function without parameters
+        self.locals.clear();
+        self.variables.clear();
+        self.output = None;
+        let mut globals = String::new();
+        let mut main_body = String::new();
+        for statement in program {
+            match statement {
+                Statement::Class(_) | Statement::Function(_) => {}
+                Statement::Let {
+                    name,
+                    mutable,
+                    value,
+                    ..
+                } => {
+                    let var_type = self.globals[name].clone();
+                    let (global, init) =
+                        self.handle_global(name, &var_type, *mutable, value.as_ref());
+                    globals += &global;
+                    main_body += &init;
+                }
+                statement => main_body += &emit_statement(self, statement),
+            }
+        }
+        let mut main = String::new();
+        for (name, value_type) in &self.locals {
+            main += &format!("(local ${} {})\n", name, value_type.name());
+        }
+        main += &main_body;
+
+        let mut writer = CodeWriter::new(self.style);
+        writer.line("(module");
+        writer.indent();
+        for name in &self.imports {
+            writer.lines(import(name));
+        }
+        writer.line("(memory (export \"memory\") 1)");
+        writer.lines(&self.data);
+        writer.line(&format!(
+            "(global $__heap (mut i32) (i32.const {}))",
+            self.data_end.next_multiple_of(8)
+        ));
+        writer.lines(&globals);
+        writer.template(ALLOC);
+        for name in &self.used {
+            writer.template(&runtime(name));
+        }
+        writer.lines(&definitions);
+        list(&mut writer, "(func $main (export \"main\")", &main);
+        writer.dedent();
+        writer.line(")");
+        let content = writer.finish();
+
+        if let Err(message) = wat_check::validate(&content) {
+            panic!("generated webassembly is invalid: {}", message);
+        }
+        return vec![OutputFile {
+            extension: self.extension(),
+            content,
+        }];
+    }
+
+    // locals start at zero, a `let` without a value doesn't write anything
+    fn emit_let(
+        &mut self,
+        name: &str,
+        var_type: Option<&Type>,
+        inferred: Option<&Type>,
+        _mutable: bool,
+        _constant: bool,
+        value: Option<&Expr>,
+    ) -> String {
+        let var_type = var_type
+            .or(inferred)
+            .cloned()
+            .or_else(|| value.and_then(|value| self.type_of(value)))
+            .unwrap_or_else(|| panic!("type of: {} isn't known", name));
+        check_reference(&var_type, name);
+        self.declare_local(name, &var_type);
+        let Some(value) = value else {
+            return String::new();
+        };
+        let (prefix, value) = self.hoist(value);
+        let value_str = self.emit_as(&value, &var_type);
+        return format!("{}{}\n", prefix, self.set(name, &value_str));
+    }
+    fn emit_let_tuple(
+        &mut self,
+        _names: &[String],
+        _types: Option<&[Type]>,
+        _value: Option<&Expr>,
+    ) -> String {
+        panic!("tuples aren't supported by the wat backend");
+    }
+    // x += 1 -> x = x + 1
+    fn emit_assign(&mut self, target: &Expr, operator: TokenKind, value: &Expr) -> String {
+        let Some(target_type) = self.type_of(target) else {
+            panic!("type of the assigned value: {:?} isn't known", target);
+        };
+        let (prefix, value) = self.hoist(value);
+        let value = match operator {
+            TokenKind::Assignment => value,
+            TokenKind::PlusEquals | TokenKind::MinusEquals => Expr::Binary {
+                l: Box::new(target.clone()),
+                operator: match operator {
+                    TokenKind::PlusEquals => TokenKind::Plus,
+                    _ => TokenKind::Minus,
+                },
+                r: Box::new(value),
+            },
+            default => panic!("assignment: {:?} doesn't have a handler", default),
+        };
+        let value_str = self.emit_as(&value, &target_type);
+        let store = match target {
+            Expr::Variable { name, .. } => self.set(name, &value_str),
+            Expr::Field { value, name } => {
+                let (offset, field_type) = self.field(value, name);
+                format!(
+                    "({}.store offset={} {} {})",
+                    value_type(&field_type).name(),
+                    offset,
+                    emit_expr(self, value),
+                    value_str
+                )
+            }
+            Expr::Index { value, indexes } => {
+                let (address, element) = self.element_address(value, indexes);
+                format!("({}.store {} {})", element.name(), address, value_str)
+            }
+            target => panic!("{:?} can't be assigned by the wat backend", target),
+        };
+        return format!("{}{}\n", prefix, store);
+    }
+    // the result of a call that isn't used is dropped
+    fn emit_expression_statement(&mut self, value: &Expr) -> String {
+        let (prefix, value) = self.hoist(value);
+        let value_str = emit_expr(self, &value);
+        if self.type_of(&value).is_some() {
+            return format!("{}(drop {})\n", prefix, value_str);
+        }
+        return format!("{}{}\n", prefix, value_str);
+    }
+    fn emit_if(
+        &mut self,
+        condition: &Expr,
+        then: &[Statement],
+        otherwise: Option<&[Statement]>,
+    ) -> String {
+        let (prefix, condition) = self.hoist(condition);
+        let condition_str = self.emit_as(&condition, &symbol("bool"));
+        let then_str = emit_block(self, then);
+        let mut branches = CodeWriter::new(self.style);
+        list(&mut branches, "(then", &then_str);
+        if let Some(otherwise) = otherwise {
+            list(&mut branches, "(else", &emit_block(self, otherwise));
+        }
+        let mut writer = CodeWriter::new(self.style);
+        writer.lines(&prefix);
+        list(
+            &mut writer,
+            &format!("(if {}", condition_str),
+            &branches.finish(),
+        );
+        return writer.finish();
+    }
+    // `continue` leaves the inner block, `break` the outer one
+    fn emit_while(&mut self, condition: &Expr, body: &[Statement]) -> String {
+        let id = self.begin_loop();
+        // the `out` values are read again before every check
+        let (prefix, condition) = self.hoist(condition);
+        let condition_str = self.emit_as(&condition, &symbol("bool"));
+        let body_str = emit_block(self, body);
+        self.loops.pop();
+        let mut iteration = CodeWriter::new(self.style);
+        iteration.lines(&prefix);
+        iteration.line(&format!("(br_if $exit_{} (i32.eqz {}))", id, condition_str));
+        list(&mut iteration, &format!("(block $next_{}", id), &body_str);
+        iteration.line(&format!("(br $loop_{})", id));
+        return self.emit_loop(id, "", &iteration.finish());
+    }
+    // the end is checked before every iteration like the condition of a c# for
+    fn emit_for_range(
+        &mut self,
+        variable: &str,
+        from: &Expr,
+        to: &Expr,
+        body: &[Statement],
+    ) -> String {
+        let var_type = self.operand_type(from, to);
+        self.declare_local(variable, &var_type);
+        let id = self.begin_loop();
+        let (from_prefix, from) = self.hoist(from);
+        let (to_prefix, to) = self.hoist(to);
+        let from_str = self.emit_as(&from, &var_type);
+        let to_str = self.emit_as(&to, &var_type);
+        let variable_value = value_type(&var_type);
+        let compare = match (variable_value.is_float(), is_unsigned(&var_type)) {
+            (true, _) => "ge",
+            (false, true) => "ge_u",
+            (false, false) => "ge_s",
+        };
+        let next_str = format!(
+            "({}.add {} {})",
+            variable_value.name(),
+            self.get(variable),
+            constant(variable_value, 1)
+        );
+        let body_str = emit_block(self, body);
+        self.loops.pop();
+        let mut iteration = CodeWriter::new(self.style);
+        iteration.lines(&to_prefix);
+        iteration.line(&format!(
+            "(br_if $exit_{} ({}.{} {} {}))",
+            id,
+            variable_value.name(),
+            compare,
+            self.get(variable),
+            to_str
+        ));
+        list(&mut iteration, &format!("(block $next_{}", id), &body_str);
+        iteration.line(&self.set(variable, &next_str));
+        iteration.line(&format!("(br $loop_{})", id));
+        let start = format!("{}{}\n", from_prefix, self.set(variable, &from_str));
+        return self.emit_loop(id, &start, &iteration.finish());
+    }
+    // the elements of every dimension follow each other
+    fn emit_for_each(&mut self, variable: &str, iterable: &Expr, body: &[Statement]) -> String {
+        let Some(Type::Array {
+            left_type,
+            dimensions,
+        }) = self.type_of(iterable)
+        else {
+            panic!("only arrays can be iterated by the wat backend");
+        };
+        self.declare_local(variable, &left_type);
+        let id = self.begin_loop();
+        let array = format!("__array{}", id);
+        let index = format!("__index{}", id);
+        self.declare_temporary(&array, ValueType::I32);
+        self.declare_temporary(&index, ValueType::I32);
+        let (prefix, iterable) = self.hoist(iterable);
+        let iterable_str = emit_expr(self, &iterable);
+        let length = self.use_runtime("rt_length");
+        let element = value_type(&left_type);
+        let element_str = format!(
+            "({}.load offset={} (i32.add (local.get ${}) (i32.mul (local.get ${}) (i32.const {}))))",
+            element.name(),
+            header_size(dimensions + 1),
+            array,
+            index,
+            size_of(element)
+        );
+        let body_str = emit_block(self, body);
+        self.loops.pop();
+        let mut iteration = CodeWriter::new(self.style);
+        iteration.line(&format!(
+            "(br_if $exit_{} (i32.ge_u (local.get ${}) (call {} (local.get ${}) (i32.const {}))))",
+            id,
+            index,
+            length,
+            array,
+            dimensions + 1
+        ));
+        iteration.line(&self.set(variable, &element_str));
+        list(&mut iteration, &format!("(block $next_{}", id), &body_str);
+        iteration.line(&format!(
+            "(local.set ${} (i32.add (local.get ${}) (i32.const 1)))",
+            index, index
+        ));
+        iteration.line(&format!("(br $loop_{})", id));
+        let start = format!(
+            "{}(local.set ${} {})\n(local.set ${} (i32.const 0))\n",
+            prefix, array, iterable_str, index
+        );
+        return self.emit_loop(id, &start, &iteration.finish());
+    }
+    // the locals belong to the function, the shadowed names are already renamed
+    fn emit_scope(&mut self, body: &[Statement]) -> String {
+        return emit_block(self, body);
+    }
+    fn emit_return(&mut self, value: Option<&Expr>) -> String {
+        let Some(value) = value else {
+            return "(return)\n".to_string();
+        };
+        let Some(output) = self.output.clone() else {
+            panic!("return with a value outside of a function with an output");
+        };
+        let (prefix, value) = self.hoist(value);
+        return format!("{}(return {})\n", prefix, self.emit_as(&value, &output));
+    }
+    fn emit_break(&mut self) -> String {
+        let Some(id) = self.loops.last() else {
+            panic!("break outside of a loop");
+        };
+        return format!("(br $exit_{})\n", id);
+    }
+    fn emit_continue(&mut self) -> String {
+        let Some(id) = self.loops.last() else {
+            panic!("continue outside of a loop");
+        };
+        return format!("(br $next_{})\n", id);
+    }
+    fn emit_function(&mut self, function: &Function) -> String {
+        panic!(
+            "function: {} has to be declared at the top level for the wat backend",
+            function.name
+        );
+    }
+    fn emit_class(&mut self, class: &Class) -> String {
+        panic!(
+            "class: {} has to be declared at the top level for the wat backend",
+            class.name
+        );
+    }
+
+    fn emit_number(&mut self, value: i128) -> String {
+        return constant(ValueType::I32, value);
+    }
+    fn emit_float(&mut self, value: f32) -> String {
+        return float_constant(ValueType::F64, value);
+    }
+    fn emit_string(&mut self, text: &str) -> String {
+        let inside = &text[1..text.len() - 1];
+        return self.string_literal(&unescape(inside));
+    }
+    // $"[{x}]" -> "[" + x + "]"
+    fn emit_interpolated(&mut self, parts: &[Interpolation]) -> String {
+        let mut output: Option<String> = None;
+        for part in parts {
+            let part_str = match part {
+                Interpolation::Text(text) => self.string_literal(&unescape(text)),
+                Interpolation::Value(value) => self.emit_text(value),
+            };
+            output = Some(match output {
+                Some(output) => {
+                    let concat = self.use_runtime("rt_concat");
+                    format!("(call {} {} {})", concat, output, part_str)
+                }
+                None => part_str,
+            });
+        }
+        return output.unwrap_or_else(|| self.string_literal(b""));
+    }
+    fn emit_bool(&mut self, value: bool) -> String {
+        return format!("(i32.const {})", value as i32);
+    }
+    fn emit_variable(&mut self, name: &str, _var_type: Option<&Type>) -> String {
+        return self.get(name);
+    }
+    fn emit_external(&mut self, name: &str) -> String {
+        if self.results.contains(name) {
+            return format!("(local.get ${})", name);
+        }
+        panic!("{} isn't supported by the wat backend", name);
+    }
+    fn emit_unary(&mut self, operator: TokenKind, value: &Expr) -> String {
+        match operator {
+            TokenKind::Not => {
+                return format!("(i32.eqz {})", self.emit_as(value, &symbol("bool")));
+            }
+            TokenKind::Minus => {
+                let var_type = self.type_of(value).unwrap_or_else(|| symbol("i32"));
+                let value_value = value_type(&var_type);
+                let value_str = emit_expr(self, value);
+                if value_value.is_float() {
+                    return format!("({}.neg {})", value_value.name(), value_str);
+                }
+                return format!(
+                    "({}.sub {} {})",
+                    value_value.name(),
+                    constant(value_value, 0),
+                    value_str
+                );
+            }
+            default => panic!("unary operator: {:?} doesn't have a handler", default),
+        }
+    }
+    fn emit_binary(&mut self, l: &Expr, operator: TokenKind, r: &Expr) -> String {
+        if self.is_string(l) || self.is_string(r) {
+            match operator {
+                TokenKind::Plus => {
+                    let concat = self.use_runtime("rt_concat");
+                    let left_str = self.emit_text(l);
+                    let right_str = self.emit_text(r);
+                    return format!("(call {} {} {})", concat, left_str, right_str);
+                }
+                TokenKind::Equals | TokenKind::NotEquals => {
+                    let equals = self.use_runtime("rt_str_eq");
+                    let call = format!(
+                        "(call {} {} {})",
+                        equals,
+                        emit_expr(self, l),
+                        emit_expr(self, r)
+                    );
+                    if operator == TokenKind::NotEquals {
+                        return format!("(i32.eqz {})", call);
+                    }
+                    return call;
+                }
+                default => panic!("operator: {:?} can't be used on strings in wat", default),
+            }
+        }
+        // && and || only evaluate the right side when it is needed
+        let bool_type = symbol("bool");
+        match operator {
+            TokenKind::And => {
+                return format!(
+                    "(if (result i32) {} (then {}) (else (i32.const 0)))",
+                    self.emit_as(l, &bool_type),
+                    self.emit_as(r, &bool_type)
+                );
+            }
+            TokenKind::Or => {
+                return format!(
+                    "(if (result i32) {} (then (i32.const 1)) (else {}))",
+                    self.emit_as(l, &bool_type),
+                    self.emit_as(r, &bool_type)
+                );
+            }
+            _ => {}
+        }
+
+        let operand = self.operand_type(l, r);
+        let left_str = self.emit_as(l, &operand);
+        let right_str = self.emit_as(r, &operand);
+        let operand_value = value_type(&operand);
+        let float = operand_value.is_float();
+        let sign = if is_unsigned(&operand) { "_u" } else { "_s" };
+        let sign = if float { "" } else { sign };
+        let instruction = match operator {
+            TokenKind::Plus => "add".to_string(),
+            TokenKind::Minus => "sub".to_string(),
+            TokenKind::Star => "mul".to_string(),
+            TokenKind::Slash => format!("div{}", sign),
+            TokenKind::Percent if !float => format!("rem{}", sign),
+            TokenKind::Less => format!("lt{}", sign),
+            TokenKind::LessEquals => format!("le{}", sign),
+            TokenKind::Greater => format!("gt{}", sign),
+            TokenKind::GreaterEquals => format!("ge{}", sign),
+            TokenKind::Equals => "eq".to_string(),
+            TokenKind::NotEquals => "ne".to_string(),
+            default => panic!("operator: {:?} doesn't have a handler", default),
+        };
+        return format!(
+            "({}.{} {} {})",
+            operand_value.name(),
+            instruction,
+            left_str,
+            right_str
+        );
+    }
+    fn emit_tuple(&mut self, _values: &[Expr]) -> String {
+        panic!("tuples aren't supported by the wat backend");
+    }
+    fn emit_field(&mut self, value: &Expr, name: &str) -> String {
+        let (offset, field_type) = self.field(value, name);
+        return format!(
+            "({}.load offset={} {})",
+            value_type(&field_type).name(),
+            offset,
+            emit_expr(self, value)
+        );
+    }
+    fn emit_static_field(&mut self, value: &Expr, name: &str) -> String {
+        panic!(
+            "static field: {}.{} isn't supported by the wat backend",
+            emit_expr(self, value),
+            name
+        );
+    }
+    fn emit_index(&mut self, value: &Expr, indexes: &[Expr]) -> String {
+        let (address, element) = self.element_address(value, indexes);
+        return format!("({}.load {})", element.name(), address);
+    }
+    fn emit_call(&mut self, callee: &Callee, arguments: &[Expr]) -> String {
+        match callee {
+            Callee::Function(name) => {
+                let Some((parameters, _)) = self.functions.get(name).cloned() else {
+                    panic!("function: {} isn't declared", name);
+                };
+                let mut call = format!("(call ${}", self.name(name));
+                for (argument, parameter) in arguments.iter().zip(&parameters) {
+                    call += &format!(" {}", self.emit_as(argument, parameter));
+                }
+                return call + ")";
+            }
+            Callee::Method { receiver, name, .. } => {
+                if let Expr::External(type_name) = receiver.as_ref() {
+                    return handle_static_call(self, type_name, name, arguments);
+                }
+                return handle_method_call(self, receiver, name);
+            }
+            Callee::Static { type_name, name } => {
+                return handle_static_call(self, type_name, name, arguments);
+            }
+            Callee::Value(_) => panic!("calls of values aren't supported by the wat backend"),
+        }
+    }
+    // Point { x = 1; } -> (call $Point_new (i32.const 1) (i32.const 0))
+    fn emit_new(&mut self, class: &str, fields: &[(String, Expr)]) -> String {
+        let Some(declared) = self.classes.get(class).cloned() else {
+            panic!("class: {} isn't declared", class);
+        };
+        let mut call = format!("(call ${}_new", self.name(class));
+        for field in &declared {
+            call += &format!(
+                " {}",
+                match fields.iter().find(|(name, _)| name == &field.name) {
+                    Some((_, value)) => self.emit_as(value, &field.var_type),
+                    None => constant(value_type(&field.var_type), 0),
+                }
+            );
+        }
+        return call + ")";
+    }
+    fn emit_array(&mut self, values: &[Expr]) -> String {
+        return self.array_literal(values, None);
+    }
+    fn emit_list(&mut self, _element_type: Option<&Type>, _values: &[Expr]) -> String {
+        panic!("collections aren't supported by the wat backend, use an array");
+    }
+    fn emit_new_collection(&mut self, collection: &str, _var_type: Option<&Type>) -> String {
+        panic!(
+            "collection: {} isn't supported by the wat backend, use an array",
+            collection
+        );
+    }
+    fn emit_out(&mut self, name: &str, _var_type: Option<&Type>) -> String {
+        panic!(
+            "out argument: {} has to be hoisted before the statement that uses it",
+            name
+        );
+    }
+    // classes and arrays are pointers already
+    fn emit_ref(&mut self, value: &Expr, _mutable: bool) -> String {
+        if let Some(var_type) = self.type_of(value) {
+            check_reference(
+                &Type::Reference {
+                    inner: Box::new(var_type),
+                    mutable: true,
+                },
+                "reference",
+            );
+        }
+        return emit_expr(self, value);
+    }
+}
+
+/// Console.WriteLine(x) -> (call $print_i64 x), int.Parse(x) -> (call $rt_parse_int x) ...
+fn handle_static_call(
+    generator: &mut Wat,
+    type_name: &str,
+    name: &str,
+    arguments: &[Expr],
+) -> String {
+    match (type_name, name, arguments) {
+        ("Console", "WriteLine", []) => {
+            let print = generator.use_import("print_str");
+            let empty = generator.string_literal(b"");
+            return format!("(call {} {})", print, empty);
+        }
+        ("Console", "WriteLine", [value]) => {
+            let var_type = generator.type_of(value);
+            match var_type.as_ref().map(value_type) {
+                Some(ValueType::I32 | ValueType::I64)
+                    if var_type.as_ref().is_some_and(is_number) =>
+                {
+                    let print = generator.use_import("print_i64");
+                    return format!(
+                        "(call {} {})",
+                        print,
+                        generator.emit_as(value, &symbol("i64"))
+                    );
+                }
+                Some(ValueType::F32 | ValueType::F64) => {
+                    let print = generator.use_import("print_f64");
+                    return format!(
+                        "(call {} {})",
+                        print,
+                        generator.emit_as(value, &symbol("f64"))
+                    );
+                }
+                _ => {
+                    let print = generator.use_import("print_str");
+                    return format!("(call {} {})", print, generator.emit_text(value));
+                }
+            }
+        }
+        ("Console", "ReadLine", []) => {
+            return format!("(call {})", generator.use_import("read_line"))
+        }
+        (number, "Parse" | "TryParse", [text])
+            if INTEGERS_32.contains(&number) || INTEGERS_64.contains(&number) =>
+        {
+            let text_str = emit_expr(generator, text);
+            let unsigned = UNSIGNED.contains(&number) as i32;
+            if name == "TryParse" {
+                // the result and the number, see `hoist`
+                let parse = generator.use_runtime("rt_try_parse_int");
+                return format!("(call {} {} (i32.const {}))", parse, text_str, unsigned);
+            }
+            let parse = generator.use_runtime("rt_parse_int");
+            let call = format!("(call {} {} (i32.const {}))", parse, text_str, unsigned);
+            return convert(call, &symbol("i64"), &symbol(number));
+        }
+        _ => panic!("{}.{} isn't supported by the wat backend", type_name, name),
+    }
+}
+/// map.len() -> number of elements, s.len() -> number of bytes
+fn handle_method_call(generator: &mut Wat, receiver: &Expr, name: &str) -> String {
+    let receiver_type = generator.type_of(receiver);
+    match (receiver_type, name) {
+        (Some(Type::Array { dimensions, .. }), "len") => {
+            let length = generator.use_runtime("rt_length");
+            let receiver_str = emit_expr(generator, receiver);
+            return format!(
+                "(call {} {} (i32.const {}))",
+                length,
+                receiver_str,
+                dimensions + 1
+            );
+        }
+        (Some(var_type), "len") if is_string_type(&var_type) => {
+            return format!("(i32.load {})", emit_expr(generator, receiver));
+        }
+        _ => panic!("method: {} isn't supported by the wat backend", name),
+    }
+}
+
+/// `__out1[0]` -> `__out1`, the result is the first of the returned values
+fn unwrap_results(value: &mut Expr, temporaries: &[String]) {
+    if let Expr::Index { value: inner, .. } = value {
+        if let Expr::External(name) = inner.as_ref() {
+            if temporaries.contains(name) {
+                *value = Expr::External(name.clone());
+                return;
+            }
+        }
+    }
+    for child in value.children_mut() {
+        unwrap_results(child, temporaries);
+    }
+}
+/// lengths of the dimensions and the elements of a nested array literal
+fn array_shape<'a>(
+    values: &'a [Expr],
+    depth: usize,
+    dimensions: &mut Vec<usize>,
+    elements: &mut Vec<&'a Expr>,
+) {
+    match dimensions.get(depth) {
+        Some(length) if *length != values.len() => {
+            panic!("arrays with rows of different lengths aren't supported by the wat backend")
+        }
+        Some(_) => {}
+        None => dimensions.push(values.len()),
+    }
+    for value in values {
+        match value {
+            Expr::Array(inner) => array_shape(inner, depth + 1, dimensions, elements),
+            value => elements.push(value),
+        }
+    }
+}
+/// (offset of every field, size of the instance)
+fn layout(fields: &[Field]) -> (Vec<usize>, usize) {
+    let mut offsets = Vec::new();
+    let mut size: usize = 0;
+    for field in fields {
+        let field_size = size_of(value_type(&field.var_type));
+        size = size.next_multiple_of(field_size);
+        offsets.push(size);
+        size += field_size;
+    }
+    return (offsets, size.max(1));
+}
+/// the lengths of the dimensions, 8 byte elements stay aligned
+fn header_size(rank: usize) -> usize {
+    return (rank * 4).next_multiple_of(8);
+}
+fn size_of(value_type: ValueType) -> usize {
+    match value_type {
+        ValueType::I32 | ValueType::F32 => return 4,
+        ValueType::I64 | ValueType::F64 => return 8,
+    }
+}
+/// order of the number types when both sides of an operator have a type
+fn rank(value_type: ValueType) -> usize {
+    match value_type {
+        ValueType::I32 => return 0,
+        ValueType::I64 => return 1,
+        ValueType::F32 => return 2,
+        ValueType::F64 => return 3,
+    }
+}
+fn symbol(name: &str) -> Type {
+    return Type::Symbol(name.to_string());
+}
+fn is_string_type(var_type: &Type) -> bool {
+    return matches!(var_type, Type::Symbol(symbol) if matches!(symbol.as_str(), "str" | "String" | "string"));
+}
+fn is_number(var_type: &Type) -> bool {
+    let Type::Symbol(symbol) = var_type else {
+        return false;
+    };
+    let symbol = symbol.as_str();
+    return INTEGERS_32.contains(&symbol)
+        || INTEGERS_64.contains(&symbol)
+        || FLOATS_32.contains(&symbol)
+        || FLOATS_64.contains(&symbol);
+}
+fn is_unsigned(var_type: &Type) -> bool {
+    return matches!(var_type, Type::Symbol(symbol) if UNSIGNED.contains(&symbol.as_str()));
+}
+/// classes, arrays and strings are addresses in the memory, bools are i32
+fn value_type(var_type: &Type) -> ValueType {
+    match var_type {
+        Type::Symbol(symbol) if INTEGERS_64.contains(&symbol.as_str()) => ValueType::I64,
+        Type::Symbol(symbol) if FLOATS_32.contains(&symbol.as_str()) => ValueType::F32,
+        Type::Symbol(symbol) if FLOATS_64.contains(&symbol.as_str()) => ValueType::F64,
+        Type::Symbol(symbol) if symbol == "i128" || symbol == "u128" => {
+            panic!("128 bit integers aren't supported by the wat backend")
+        }
+        Type::Symbol(_) | Type::Array { .. } => ValueType::I32,
+        Type::Reference { inner, .. } => value_type(inner),
+        Type::Generic { name, .. } => {
+            panic!("collection: {} isn't supported by the wat backend", name)
+        }
+        Type::Tuple(_) => panic!("tuples aren't supported by the wat backend"),
+    }
+}
+/// numbers and bools live in locals that don't have an address
+fn check_reference(var_type: &Type, name: &str) {
+    if let Type::Reference { inner, .. } = var_type {
+        let scalar =
+            is_number(inner) || matches!(inner.as_ref(), Type::Symbol(symbol) if symbol == "bool");
+        if scalar {
+            panic!(
+                "{}: references to numbers aren't supported by the wat backend",
+                name
+            );
+        }
+    }
+}
+fn constant(value_type: ValueType, value: i128) -> String {
+    return format!("({}.const {})", value_type.name(), value);
+}
+fn float_constant(value_type: ValueType, value: f32) -> String {
+    match value_type {
+        ValueType::I32 | ValueType::I64 => return constant(value_type, value as i128),
+        ValueType::F32 | ValueType::F64 => {
+            return format!("({}.const {})", value_type.name(), value);
+        }
+    }
+}
+/// conversion of a number to another number type, the sign of the value picks the instruction
+fn convert(value_str: String, from: &Type, to: &Type) -> String {
+    let (from_value, to_value) = (value_type(from), value_type(to));
+    if from_value == to_value {
+        return value_str;
+    }
+    let sign = if is_unsigned(from) { "u" } else { "s" };
+    let instruction = match (from_value, to_value) {
+        (ValueType::I32, ValueType::I64) => format!("i64.extend_i32_{}", sign),
+        (ValueType::I64, ValueType::I32) => "i32.wrap_i64".to_string(),
+        (ValueType::F32, ValueType::F64) => "f64.promote_f32".to_string(),
+        (ValueType::F64, ValueType::F32) => "f32.demote_f64".to_string(),
+        (from_value, to_value) if !from_value.is_float() => {
+            format!("{}.convert_{}_{}", to_value.name(), from_value.name(), sign)
+        }
+        (from_value, to_value) => {
+            let sign = if is_unsigned(to) { "u" } else { "s" };
+            format!("{}.trunc_{}_{}", to_value.name(), from_value.name(), sign)
+        }
+    };
+    return format!("({} {})", instruction, value_str);
+}
+/// bytes of the text of a c# string literal
+fn unescape(text: &str) -> Vec<u8> {
+    let mut output = String::new();
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            output.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('t') => output.push('\t'),
+            Some('r') => output.push('\r'),
+            Some('0') => output.push('\0'),
+            Some(char) => output.push(char),
+            None => output.push('\\'),
+        }
+    }
+    return output.into_bytes();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{
+        const_fold, control_flow, lexer, lower, parser, resolver, shadowing, type_checker,
+        type_map::TypeMap,
+    };
+
+    /// the steps of `main` from the source to the generated module
+    fn generate(source: &str) -> String {
+        let tokens = lexer::tokenize(
+            source.to_string(),
+            vec![
+                TokenKind::Tab,
+                TokenKind::WhiteSpace,
+                TokenKind::Comment,
+                TokenKind::NextLine,
+            ],
+        );
+        let expressions = parser::parse(tokens, parser::Dialect::Mixed);
+        let mut expressions = control_flow::implicit_returns(expressions);
+        let resolution = resolver::resolve(&expressions, &[]);
+        let type_map = TypeMap::new();
+        let typing = type_checker::check_types(&expressions, &resolution, &type_map);
+        shadowing::rename_shadowed(&mut expressions, &resolution);
+        let program = lower::lower(expressions, &resolution, &typing.inferred, &type_map);
+        let program = const_fold::fold_constants(program);
+        let mut files = Wat::new().generate(&program);
+        return files.remove(0).content;
+    }
+
+    // collections.rs and rust_syntax.rs use collections and tuples that the backend doesn't
+    // support, simple.rs has errors that stop the compilation before the backend
+    #[test]
+    fn tic_tac_toe_is_valid() {
+        let source = fs::read_to_string("CompileTargets/tic_tac_toe.rs").unwrap();
+        if let Err(message) = wat_check::validate(&generate(&source)) {
+            panic!("{}", message);
+        }
+    }
+
+    #[test]
+    fn programs_are_valid() {
+        let programs = [
+            // floats next to ints
+            "let f = 2.5;\nlet q: f32 = 1.0 / 3.0;\nlet i = 7;\nConsole.WriteLine(f + q);\nConsole.WriteLine(i / 2);\n",
+            // interpolated values are converted to text
+            "let mut name = \"joe\";\nlet n = 5;\nConsole.WriteLine($\"{name}: {n + 1} {n > 2}\");\n",
+            // out arguments behind a short circuit
+            "let mut tries = 4;\nlet ok = tries > 3 && uint.TryParse(Console.ReadLine(), out uint m);\nif ok {\n    Console.WriteLine(m);\n}\n",
+        ];
+        for program in programs {
+            if let Err(message) = wat_check::validate(&generate(program)) {
+                panic!("{}\n{}", program, message);
+            }
+        }
+    }
+
+    #[test]
+    fn mismatched_module_is_invalid() {
+        let module = "(module (func $f (result i32) (f64.const 1)))";
+        assert!(wat_check::validate(module).is_err());
+    }
+}
@@ -0,0 +1,825 @@
+use std::collections::{HashMap, HashSet};
+
+/// Number type of webassembly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    I32,
+    I64,
+    F32,
+    F64,
+}
+impl ValueType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueType::I32 => "i32",
+            ValueType::I64 => "i64",
+            ValueType::F32 => "f32",
+            ValueType::F64 => "f64",
+        }
+    }
+    fn from_name(name: &str) -> Option<ValueType> {
+        let value_type = match name {
+            "i32" => ValueType::I32,
+            "i64" => ValueType::I64,
+            "f32" => ValueType::F32,
+            "f64" => ValueType::F64,
+            _ => return None,
+        };
+        return Some(value_type);
+    }
+    pub fn is_float(&self) -> bool {
+        return matches!(self, ValueType::F32 | ValueType::F64);
+    }
+}
+
+/// Parses the text of a module and checks it the way a webassembly engine validates its binary:
+/// every name is declared and every instruction gets the values it expects from the stack.
+/// Instructions have to be in the folded `(op immediates operands)` form the backend writes
+pub fn validate(source: &str) -> Result<(), String> {
+    let nodes = parse(source)?;
+    let [Node::List(module)] = nodes.as_slice() else {
+        return Err("the text has to be a single `(module ...)`".to_string());
+    };
+    if atom(module.first()) != Some("module") {
+        return Err("the text has to be a single `(module ...)`".to_string());
+    }
+    let fields = &module[1..];
+
+    let mut declarations = Declarations::default();
+    for field in fields {
+        declarations.declare(field)?;
+    }
+    for field in fields {
+        let Node::List(items) = field else {
+            return Err(format!("unexpected `{}` in the module", field));
+        };
+        match atom(items.first()) {
+            Some("func") => check_function(&declarations, items)?,
+            Some("global") => {
+                let init = items.last().ok_or("global without a value")?;
+                check_constant(init)?;
+            }
+            Some("data") => {
+                let Some(Node::List(offset)) = items.get(1) else {
+                    return Err("data without an offset".to_string());
+                };
+                if atom(offset.first()) != Some("i32.const") {
+                    return Err("the offset of data has to be an `i32.const`".to_string());
+                }
+                for item in &items[2..] {
+                    if !matches!(item, Node::Atom(text) if text.starts_with('"')) {
+                        return Err(format!("data has to be strings, found `{}`", item));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    return Ok(());
+}
+
+/// S-expression of the text format
+#[derive(Debug, Clone)]
+enum Node {
+    /// keyword, number, `$name` or a string with its quotes
+    Atom(String),
+    List(Vec<Node>),
+}
+impl std::fmt::Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Node::Atom(text) => write!(f, "{}", text),
+            Node::List(items) => {
+                let items: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+                write!(f, "({})", items.join(" "))
+            }
+        }
+    }
+}
+fn atom(node: Option<&Node>) -> Option<&str> {
+    match node {
+        Some(Node::Atom(text)) => Some(text.as_str()),
+        _ => None,
+    }
+}
+
+fn parse(source: &str) -> Result<Vec<Node>, String> {
+    let mut stack: Vec<Vec<Node>> = vec![Vec::new()];
+    let mut chars = source.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            // (; block comment ;)
+            '(' if chars.peek() == Some(&';') => {
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    let Some(char) = chars.next() else {
+                        return Err("block comment isn't closed".to_string());
+                    };
+                    if last == ';' && char == ')' {
+                        break;
+                    }
+                    last = char;
+                }
+            }
+            '(' => stack.push(Vec::new()),
+            ')' => {
+                let list = stack.pop().expect("the outer list is never popped");
+                let Some(parent) = stack.last_mut() else {
+                    return Err("`)` without a `(`".to_string());
+                };
+                parent.push(Node::List(list));
+            }
+            // ;; line comment
+            ';' if chars.peek() == Some(&';') => {
+                for char in chars.by_ref() {
+                    if char == '\n' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let mut text = String::from('"');
+                loop {
+                    let Some(char) = chars.next() else {
+                        return Err("string isn't closed".to_string());
+                    };
+                    text.push(char);
+                    match char {
+                        '\\' => text.push(chars.next().ok_or("string isn't closed")?),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+                stack
+                    .last_mut()
+                    .expect("checked above")
+                    .push(Node::Atom(text));
+            }
+            char if char.is_whitespace() => {}
+            char => {
+                let mut text = String::from(char);
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || next == '(' || next == ')' || next == '"' {
+                        break;
+                    }
+                    text.push(next);
+                    chars.next();
+                }
+                stack
+                    .last_mut()
+                    .expect("checked above")
+                    .push(Node::Atom(text));
+            }
+        }
+    }
+    if stack.len() != 1 {
+        return Err(format!("{} `(` aren't closed", stack.len() - 1));
+    }
+    return Ok(stack.pop().expect("checked above"));
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Signature {
+    params: Vec<ValueType>,
+    results: Vec<ValueType>,
+}
+
+/// Names the module declares, functions and globals can be used before their declaration
+#[derive(Default)]
+struct Declarations {
+    functions: HashMap<String, Signature>,
+    /// name -> (type, mutable)
+    globals: HashMap<String, (ValueType, bool)>,
+    memory: bool,
+    exports: HashSet<String>,
+}
+impl Declarations {
+    fn declare(&mut self, field: &Node) -> Result<(), String> {
+        let Node::List(items) = field else {
+            return Err(format!("unexpected `{}` in the module", field));
+        };
+        match atom(items.first()) {
+            Some("import") => {
+                let (Some(module), Some(name)) = (atom(items.get(1)), atom(items.get(2))) else {
+                    return Err("import without a module and a name".to_string());
+                };
+                if !module.starts_with('"') || !name.starts_with('"') {
+                    return Err("the names of an import have to be strings".to_string());
+                }
+                let Some(Node::List(function)) = items.get(3) else {
+                    return Err(format!(
+                        "import {} {} doesn't declare anything",
+                        module, name
+                    ));
+                };
+                if atom(function.first()) != Some("func") {
+                    return Err(format!("import {} {} has to be a function", module, name));
+                }
+                let name = identifier(function.get(1))?;
+                let signature = self.signature(&function[2..], false)?;
+                self.add_function(name, signature)?;
+            }
+            Some("func") => {
+                let name = identifier(items.get(1))?;
+                let signature = self.signature(&items[2..], true)?;
+                self.add_function(name, signature)?;
+            }
+            Some("global") => {
+                let name = identifier(items.get(1))?;
+                let global_type = match items.get(2) {
+                    Some(Node::List(mutable)) if atom(mutable.first()) == Some("mut") => {
+                        (value_type(mutable.get(1))?, true)
+                    }
+                    node => (value_type(node)?, false),
+                };
+                if self.globals.insert(name.to_string(), global_type).is_some() {
+                    return Err(format!("global {} is declared twice", name));
+                }
+            }
+            Some("memory") => {
+                if self.memory {
+                    return Err("there can only be one memory".to_string());
+                }
+                self.memory = true;
+                let mut limits = 0;
+                for item in &items[1..] {
+                    match item {
+                        Node::List(export) => self.export(export)?,
+                        Node::Atom(limit) if limit.parse::<u32>().is_ok() => limits += 1,
+                        item => return Err(format!("unexpected `{}` in the memory", item)),
+                    }
+                }
+                if limits == 0 || limits > 2 {
+                    return Err("memory needs a minimum and an optional maximum size".to_string());
+                }
+            }
+            Some("data") | Some("start") => {}
+            Some(other) => return Err(format!("`{}` isn't a field of a module", other)),
+            None => return Err(format!("unexpected `{}` in the module", field)),
+        }
+        return Ok(());
+    }
+    fn add_function(&mut self, name: &str, signature: Signature) -> Result<(), String> {
+        if self.functions.insert(name.to_string(), signature).is_some() {
+            return Err(format!("function {} is declared twice", name));
+        }
+        return Ok(());
+    }
+    fn export(&mut self, export: &[Node]) -> Result<(), String> {
+        let Some(name) = atom(export.get(1)).filter(|_| atom(export.first()) == Some("export"))
+        else {
+            return Err(format!("expected `(export \"name\")`, found {:?}", export));
+        };
+        if !self.exports.insert(name.to_string()) {
+            return Err(format!("{} is exported twice", name));
+        }
+        return Ok(());
+    }
+    /// `(export "f")? (param $a i32)* (result i32)*` at the start of a function
+    fn signature(&mut self, items: &[Node], exported: bool) -> Result<Signature, String> {
+        let mut signature = Signature {
+            params: Vec::new(),
+            results: Vec::new(),
+        };
+        for item in items {
+            let Node::List(parts) = item else { break };
+            match atom(parts.first()) {
+                Some("export") if exported => self.export(parts)?,
+                Some("param") => signature.params.extend(declared_types(parts)?),
+                Some("result") => signature.results.extend(declared_types(parts)?),
+                _ => break,
+            }
+        }
+        return Ok(signature);
+    }
+}
+fn identifier(node: Option<&Node>) -> Result<&str, String> {
+    match atom(node) {
+        Some(name) if name.len() > 1 && name.starts_with('$') => return Ok(name),
+        _ => return Err(format!("expected a `$name`, found {:?}", node)),
+    }
+}
+fn value_type(node: Option<&Node>) -> Result<ValueType, String> {
+    return atom(node)
+        .and_then(ValueType::from_name)
+        .ok_or_else(|| format!("expected a number type, found {:?}", node));
+}
+/// types of `(param i32 i64)`, `(param $a i32)` or `(result i32)`
+fn declared_types(parts: &[Node]) -> Result<Vec<ValueType>, String> {
+    match parts {
+        [_, Node::Atom(name), declared] if name.starts_with('$') => {
+            return Ok(vec![value_type(Some(declared))?])
+        }
+        [_, types @ ..] => types.iter().map(|node| value_type(Some(node))).collect(),
+        [] => return Ok(Vec::new()),
+    }
+}
+/// value of a global, a single constant
+fn check_constant(init: &Node) -> Result<(), String> {
+    let Node::List(items) = init else {
+        return Err(format!("`{}` isn't a constant", init));
+    };
+    let op = atom(items.first()).unwrap_or_default();
+    let Some((prefix, "const")) = op.split_once('.') else {
+        return Err(format!("`{}` isn't a constant", init));
+    };
+    let value_type = ValueType::from_name(prefix).ok_or(format!("`{}` isn't a constant", op))?;
+    check_number(value_type, atom(items.get(1)))?;
+    if items.len() != 2 {
+        return Err(format!("`{}` isn't a constant", init));
+    }
+    return Ok(());
+}
+fn check_number(value_type: ValueType, text: Option<&str>) -> Result<(), String> {
+    let Some(text) = text else {
+        return Err(format!("{}.const without a value", value_type.name()));
+    };
+    let digits = text.replace('_', "");
+    let valid = match value_type {
+        ValueType::I32 => digits.parse::<i32>().is_ok() || digits.parse::<u32>().is_ok(),
+        ValueType::I64 => digits.parse::<i64>().is_ok() || digits.parse::<u64>().is_ok(),
+        ValueType::F32 | ValueType::F64 => {
+            let digits = digits.trim_start_matches(['-', '+']);
+            digits.parse::<f64>().is_ok() || digits == "inf" || digits.starts_with("nan")
+        }
+    };
+    if !valid {
+        return Err(format!("`{}` isn't a valid {}", text, value_type.name()));
+    }
+    return Ok(());
+}
+
+/// label, results and the instructions of a block, loop or if
+type BlockType<'n> = (Option<String>, Vec<ValueType>, &'n [Node]);
+
+/// Block, loop, if or the body of a function
+struct Frame {
+    label: Option<String>,
+    /// values a branch to the label takes, the results of a block and nothing for a loop
+    branch: Vec<ValueType>,
+    /// size of the stack when the frame started
+    height: usize,
+    /// after `br`, `return` or `unreachable` the stack can give any value
+    unreachable: bool,
+}
+
+struct Checker<'a> {
+    declarations: &'a Declarations,
+    function: String,
+    locals: HashMap<String, ValueType>,
+    results: Vec<ValueType>,
+    /// `None` is a value of the unreachable code that matches every type
+    stack: Vec<Option<ValueType>>,
+    frames: Vec<Frame>,
+}
+
+fn check_function(declarations: &Declarations, items: &[Node]) -> Result<(), String> {
+    let name = identifier(items.get(1))?;
+    let mut locals = HashMap::new();
+    let mut rest = &items[2..];
+    while let Some(Node::List(parts)) = rest.first() {
+        match atom(parts.first()) {
+            Some("export") | Some("result") => {}
+            Some("param") | Some("local") => {
+                let Some(Node::Atom(local)) = parts.get(1) else {
+                    return Err(format!("{}: {} without a name", name, parts[0]));
+                };
+                if !local.starts_with('$') {
+                    return Err(format!("{}: the locals need names", name));
+                }
+                let local_type = value_type(parts.get(2))?;
+                if locals.insert(local.clone(), local_type).is_some() {
+                    return Err(format!("{}: {} is declared twice", name, local));
+                }
+            }
+            _ => break,
+        }
+        rest = &rest[1..];
+    }
+
+    let results = declarations.functions[name].results.clone();
+    let mut checker = Checker {
+        declarations,
+        function: name.to_string(),
+        locals,
+        results: results.clone(),
+        stack: Vec::new(),
+        frames: Vec::new(),
+    };
+    checker.block(None, results.clone(), results, rest)?;
+    return Ok(());
+}
+
+impl Checker<'_> {
+    fn error(&self, message: String) -> String {
+        return format!("{}: {}", self.function, message);
+    }
+    fn frame(&self) -> &Frame {
+        return self.frames.last().expect("instructions are inside a frame");
+    }
+    fn push(&mut self, value_type: ValueType) {
+        self.stack.push(Some(value_type));
+    }
+    fn pop(&mut self, expected: Option<ValueType>, op: &str) -> Result<Option<ValueType>, String> {
+        if self.stack.len() == self.frame().height {
+            if self.frame().unreachable {
+                return Ok(expected);
+            }
+            let expected = expected.map_or("a value", |expected| expected.name());
+            return Err(self.error(format!("`{}` expects {} on the stack", op, expected)));
+        }
+        let found = self.stack.pop().expect("checked above");
+        match (found, expected) {
+            (Some(found), Some(expected)) if found != expected => {
+                return Err(self.error(format!(
+                    "`{}` expects {}, found {}",
+                    op,
+                    expected.name(),
+                    found.name()
+                )));
+            }
+            (Some(found), _) => return Ok(Some(found)),
+            (None, expected) => return Ok(expected),
+        }
+    }
+    fn pop_all(&mut self, types: &[ValueType], op: &str) -> Result<(), String> {
+        for value_type in types.iter().rev() {
+            self.pop(Some(*value_type), op)?;
+        }
+        return Ok(());
+    }
+    fn set_unreachable(&mut self) {
+        let height = self.frame().height;
+        self.stack.truncate(height);
+        self.frames.last_mut().expect("checked above").unreachable = true;
+    }
+    /// runs the instructions of a frame and leaves its results on the stack
+    fn block(
+        &mut self,
+        label: Option<String>,
+        branch: Vec<ValueType>,
+        results: Vec<ValueType>,
+        instructions: &[Node],
+    ) -> Result<(), String> {
+        self.frames.push(Frame {
+            label,
+            branch,
+            height: self.stack.len(),
+            unreachable: false,
+        });
+        for instruction in instructions {
+            self.instruction(instruction)?;
+        }
+        self.pop_all(&results, "end")?;
+        let frame = self.frames.pop().expect("pushed above");
+        if self.stack.len() != frame.height {
+            return Err(self.error(format!(
+                "{} values are left on the stack at the end of a block",
+                self.stack.len() - frame.height
+            )));
+        }
+        for value_type in results {
+            self.push(value_type);
+        }
+        return Ok(());
+    }
+    /// `$label? (result t)*` at the start of a block, loop or if
+    fn block_type<'n>(&self, items: &'n [Node]) -> Result<BlockType<'n>, String> {
+        let mut rest = items;
+        let mut label = None;
+        if let Some(Node::Atom(name)) = rest.first() {
+            if name.starts_with('$') {
+                label = Some(name.clone());
+                rest = &rest[1..];
+            }
+        }
+        let mut results = Vec::new();
+        while let Some(Node::List(parts)) = rest.first() {
+            if atom(parts.first()) != Some("result") {
+                break;
+            }
+            results.extend(declared_types(parts).map_err(|message| self.error(message))?);
+            rest = &rest[1..];
+        }
+        return Ok((label, results, rest));
+    }
+    fn instruction(&mut self, node: &Node) -> Result<(), String> {
+        let Node::List(items) = node else {
+            return Err(self.error(format!("expected a folded instruction, found `{}`", node)));
+        };
+        let Some(op) = atom(items.first()) else {
+            return Err(self.error(format!("instruction without a name: {}", node)));
+        };
+        match op {
+            "block" | "loop" => {
+                let (label, results, body) = self.block_type(&items[1..])?;
+                let branch = if op == "loop" {
+                    Vec::new()
+                } else {
+                    results.clone()
+                };
+                return self.block(label, branch, results, body);
+            }
+            "if" => return self.if_instruction(&items[1..]),
+            _ => {}
+        }
+
+        // immediates come before the folded operands
+        let immediates: Vec<&str> = items[1..]
+            .iter()
+            .map_while(|item| atom(Some(item)))
+            .collect();
+        let operands = &items[1 + immediates.len()..];
+        for operand in operands {
+            if let Node::Atom(text) = operand {
+                return Err(self.error(format!("`{}` after the operands of `{}`", text, op)));
+            }
+            self.instruction(operand)?;
+        }
+        return self.apply(op, &immediates);
+    }
+    fn if_instruction(&mut self, items: &[Node]) -> Result<(), String> {
+        let (label, results, rest) = self.block_type(items)?;
+        let mut then = None;
+        let mut otherwise = None;
+        for item in rest {
+            match item {
+                Node::List(parts) if atom(parts.first()) == Some("then") => then = Some(parts),
+                Node::List(parts) if atom(parts.first()) == Some("else") => otherwise = Some(parts),
+                _ if then.is_some() => {
+                    return Err(self.error(format!("`{}` after the branches of `if`", item)))
+                }
+                condition => self.instruction(condition)?,
+            }
+        }
+        self.pop(Some(ValueType::I32), "if")?;
+        let Some(then) = then else {
+            return Err(self.error("`if` without `then`".to_string()));
+        };
+        if otherwise.is_none() && !results.is_empty() {
+            return Err(self.error("`if` with a result needs an `else`".to_string()));
+        }
+        self.block(label.clone(), results.clone(), results.clone(), &then[1..])?;
+        for _ in &results {
+            self.stack.pop();
+        }
+        let otherwise = otherwise.map_or(&[][..], |otherwise| &otherwise[1..]);
+        return self.block(label, results.clone(), results, otherwise);
+    }
+    fn local(&self, immediates: &[&str], op: &str) -> Result<ValueType, String> {
+        let name = immediates.first().copied().unwrap_or_default();
+        return self
+            .locals
+            .get(name)
+            .copied()
+            .ok_or_else(|| self.error(format!("`{}` uses the unknown local {}", op, name)));
+    }
+    fn apply(&mut self, op: &str, immediates: &[&str]) -> Result<(), String> {
+        use ValueType::*;
+        let name = immediates.first().copied().unwrap_or_default();
+        match op {
+            "nop" => {}
+            "unreachable" => self.set_unreachable(),
+            "drop" => {
+                self.pop(None, op)?;
+            }
+            "select" => {
+                self.pop(Some(I32), op)?;
+                let value_type = self.pop(None, op)?;
+                let other = self.pop(value_type, op)?;
+                if let Some(value_type) = value_type.or(other) {
+                    self.push(value_type);
+                } else {
+                    self.stack.push(None);
+                }
+            }
+            "return" => {
+                let results = self.results.clone();
+                self.pop_all(&results, op)?;
+                self.set_unreachable();
+            }
+            "br" | "br_if" => {
+                let Some(frame) = self
+                    .frames
+                    .iter()
+                    .rev()
+                    .find(|frame| frame.label.as_deref() == Some(name))
+                else {
+                    return Err(self.error(format!("`{}` to the unknown label {}", op, name)));
+                };
+                let branch = frame.branch.clone();
+                if op == "br_if" {
+                    self.pop(Some(I32), op)?;
+                    self.pop_all(&branch, op)?;
+                    for value_type in branch {
+                        self.push(value_type);
+                    }
+                } else {
+                    self.pop_all(&branch, op)?;
+                    self.set_unreachable();
+                }
+            }
+            "call" => {
+                let Some(signature) = self.declarations.functions.get(name) else {
+                    return Err(self.error(format!("call of the unknown function {}", name)));
+                };
+                let signature = signature.clone();
+                self.pop_all(&signature.params, op)?;
+                for value_type in signature.results {
+                    self.push(value_type);
+                }
+            }
+            "local.get" => {
+                let value_type = self.local(immediates, op)?;
+                self.push(value_type);
+            }
+            "local.set" | "local.tee" => {
+                let value_type = self.local(immediates, op)?;
+                self.pop(Some(value_type), op)?;
+                if op == "local.tee" {
+                    self.push(value_type);
+                }
+            }
+            "global.get" | "global.set" => {
+                let Some(&(value_type, mutable)) = self.declarations.globals.get(name) else {
+                    return Err(self.error(format!("`{}` uses the unknown global {}", op, name)));
+                };
+                if op == "global.get" {
+                    self.push(value_type);
+                } else if !mutable {
+                    return Err(self.error(format!("global {} isn't mutable", name)));
+                } else {
+                    self.pop(Some(value_type), op)?;
+                }
+            }
+            "memory.size" => self.memory_op(op, &[], &[I32])?,
+            "memory.grow" => self.memory_op(op, &[I32], &[I32])?,
+            "memory.copy" | "memory.fill" => self.memory_op(op, &[I32, I32, I32], &[])?,
+            op => {
+                let Some((prefix, instruction)) = op.split_once('.') else {
+                    return Err(self.error(format!("unknown instruction `{}`", op)));
+                };
+                let Some(value_type) = ValueType::from_name(prefix) else {
+                    return Err(self.error(format!("unknown instruction `{}`", op)));
+                };
+                return self.numeric(op, value_type, instruction, immediates);
+            }
+        }
+        return Ok(());
+    }
+    fn memory_op(
+        &mut self,
+        op: &str,
+        inputs: &[ValueType],
+        outputs: &[ValueType],
+    ) -> Result<(), String> {
+        if !self.declarations.memory {
+            return Err(self.error(format!("`{}` without a memory", op)));
+        }
+        self.pop_all(inputs, op)?;
+        for value_type in outputs {
+            self.push(*value_type);
+        }
+        return Ok(());
+    }
+    /// `i32.add`, `f64.load offset=8`, `i64.extend_i32_s` ...
+    fn numeric(
+        &mut self,
+        op: &str,
+        value_type: ValueType,
+        instruction: &str,
+        immediates: &[&str],
+    ) -> Result<(), String> {
+        use ValueType::*;
+        let float = value_type.is_float();
+        let integer_binary = [
+            "add", "sub", "mul", "div_s", "div_u", "rem_s", "rem_u", "and", "or", "xor", "shl",
+            "shr_s", "shr_u", "rotl", "rotr",
+        ];
+        let float_binary = ["add", "sub", "mul", "div", "min", "max", "copysign"];
+        let integer_compare = [
+            "eq", "ne", "lt_s", "lt_u", "gt_s", "gt_u", "le_s", "le_u", "ge_s", "ge_u",
+        ];
+        let float_compare = ["eq", "ne", "lt", "gt", "le", "ge"];
+        let integer_unary = ["clz", "ctz", "popcnt", "extend8_s", "extend16_s"];
+        let float_unary = ["abs", "neg", "sqrt", "ceil", "floor", "trunc", "nearest"];
+
+        let (inputs, outputs) = match instruction {
+            "const" => {
+                check_number(value_type, immediates.first().copied())
+                    .map_err(|message| self.error(message))?;
+                (vec![], vec![value_type])
+            }
+            _ if instruction.starts_with("load") => {
+                self.check_memarg(op, immediates)?;
+                self.check_access(op, value_type, instruction.trim_start_matches("load"))?;
+                (vec![I32], vec![value_type])
+            }
+            _ if instruction.starts_with("store") => {
+                self.check_memarg(op, immediates)?;
+                self.check_access(op, value_type, instruction.trim_start_matches("store"))?;
+                (vec![I32, value_type], vec![])
+            }
+            _ if !float && integer_binary.contains(&instruction) => {
+                (vec![value_type, value_type], vec![value_type])
+            }
+            _ if float && float_binary.contains(&instruction) => {
+                (vec![value_type, value_type], vec![value_type])
+            }
+            _ if !float && integer_compare.contains(&instruction) => {
+                (vec![value_type, value_type], vec![I32])
+            }
+            _ if float && float_compare.contains(&instruction) => {
+                (vec![value_type, value_type], vec![I32])
+            }
+            "eqz" if !float => (vec![value_type], vec![I32]),
+            "extend32_s" if value_type == I64 => (vec![I64], vec![I64]),
+            _ if !float && integer_unary.contains(&instruction) => {
+                (vec![value_type], vec![value_type])
+            }
+            _ if float && float_unary.contains(&instruction) => {
+                (vec![value_type], vec![value_type])
+            }
+            _ => {
+                let input = conversion_input(value_type, instruction)
+                    .ok_or_else(|| self.error(format!("unknown instruction `{}`", op)))?;
+                (vec![input], vec![value_type])
+            }
+        };
+        let memory = instruction.starts_with("load") || instruction.starts_with("store");
+        if !immediates.is_empty() && instruction != "const" && !memory {
+            return Err(self.error(format!("`{}` doesn't take immediates", op)));
+        }
+        self.pop_all(&inputs, op)?;
+        for value_type in outputs {
+            self.push(value_type);
+        }
+        return Ok(());
+    }
+    /// `offset=8 align=4`
+    fn check_memarg(&self, op: &str, immediates: &[&str]) -> Result<(), String> {
+        if !self.declarations.memory {
+            return Err(self.error(format!("`{}` without a memory", op)));
+        }
+        for immediate in immediates {
+            let valid = match immediate.split_once('=') {
+                Some(("offset", value)) => value.parse::<u32>().is_ok(),
+                Some(("align", value)) => value
+                    .parse::<u32>()
+                    .is_ok_and(|align| align.is_power_of_two()),
+                _ => false,
+            };
+            if !valid {
+                return Err(self.error(format!("`{}` isn't an immediate of `{}`", immediate, op)));
+            }
+        }
+        return Ok(());
+    }
+    /// `i32.load8_u`, `i64.store32` ...
+    fn check_access(&self, op: &str, value_type: ValueType, size: &str) -> Result<(), String> {
+        let bits = size.trim_end_matches("_s").trim_end_matches("_u");
+        let valid = match (value_type, bits) {
+            (_, "") => size.is_empty(),
+            (ValueType::I32, "8" | "16") | (ValueType::I64, "8" | "16" | "32") => {
+                op.contains("store") != (size.ends_with("_s") || size.ends_with("_u"))
+            }
+            _ => false,
+        };
+        if !valid {
+            return Err(self.error(format!("unknown instruction `{}`", op)));
+        }
+        return Ok(());
+    }
+}
+/// type a conversion like `f64.convert_i32_s` takes
+fn conversion_input(output: ValueType, instruction: &str) -> Option<ValueType> {
+    let (name, input) = instruction.split_once('_')?;
+    let input = input
+        .strip_suffix("_s")
+        .or_else(|| input.strip_suffix("_u"))
+        .unwrap_or(input);
+    let signed = instruction.ends_with("_s") || instruction.ends_with("_u");
+    let input = ValueType::from_name(input)?;
+    let valid = match (name, output) {
+        ("wrap", ValueType::I32) => input == ValueType::I64 && !signed,
+        ("extend", ValueType::I64) => input == ValueType::I32 && signed,
+        ("convert", ValueType::F32 | ValueType::F64) => !input.is_float() && signed,
+        ("trunc", ValueType::I32 | ValueType::I64) => input.is_float() && signed,
+        ("demote", ValueType::F32) => input == ValueType::F64 && !signed,
+        ("promote", ValueType::F64) => input == ValueType::F32 && !signed,
+        ("reinterpret", _) => {
+            !signed
+                && input.is_float() != output.is_float()
+                && matches!(
+                    (input, output),
+                    (ValueType::I32, ValueType::F32)
+                        | (ValueType::F32, ValueType::I32)
+                        | (ValueType::I64, ValueType::F64)
+                        | (ValueType::F64, ValueType::I64)
+                )
+        }
+        _ => false,
+    };
+    return valid.then_some(input);
+}
@@ -0,0 +1,171 @@
+/// Where the `{` of a block goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BraceStyle {
+    /// `if (x) {`
+    SameLine,
+    /// `if (x)` and `{` on the next line
+    NextLine,
+}
+impl BraceStyle {
+    pub fn from_name(name: &str) -> Option<BraceStyle> {
+        match name {
+            "same-line" => return Some(BraceStyle::SameLine),
+            "next-line" => return Some(BraceStyle::NextLine),
+            _ => return None,
+        }
+    }
+}
+
+/// One level of indentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    Spaces(usize),
+    Tab,
+}
+impl Indent {
+    /// `4` -> 4 spaces, `tab` -> a tab
+    pub fn from_name(name: &str) -> Option<Indent> {
+        if name == "tab" {
+            return Some(Indent::Tab);
+        }
+        return name.parse().ok().map(Indent::Spaces);
+    }
+    pub fn text(&self) -> String {
+        match self {
+            Indent::Spaces(width) => return " ".repeat(*width),
+            Indent::Tab => return "\t".to_string(),
+        }
+    }
+}
+
+/// Formatting of the generated code, picked with `--indent` and `--braces`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeStyle {
+    pub indent: Indent,
+    pub braces: BraceStyle,
+}
+impl CodeStyle {
+    pub fn new() -> CodeStyle {
+        CodeStyle {
+            indent: Indent::Spaces(4),
+            braces: BraceStyle::SameLine,
+        }
+    }
+}
+
+/// Builds generated code line by line.
+/// The hooks generate their nested blocks first, `lines` indents them to the level of the writer
+pub struct CodeWriter {
+    style: CodeStyle,
+    level: usize,
+    output: String,
+}
+impl CodeWriter {
+    pub fn new(style: CodeStyle) -> CodeWriter {
+        CodeWriter {
+            style,
+            level: 0,
+            output: String::new(),
+        }
+    }
+    pub fn indent(&mut self) {
+        self.level += 1;
+    }
+    pub fn dedent(&mut self) {
+        self.level = self.level.checked_sub(1).expect("dedent without an indent");
+    }
+    /// one line at the current level
+    pub fn line(&mut self, text: &str) {
+        if !text.is_empty() {
+            self.output += &self.style.indent.text().repeat(self.level);
+        }
+        self.output += text;
+        self.output.push('\n');
+    }
+    /// generated lines like the body of a block, their own indentation is kept
+    pub fn lines(&mut self, text: &str) {
+        for line in text.lines() {
+            self.line(line);
+        }
+    }
+    /// body of a block one level deeper, for the languages without braces
+    pub fn nested(&mut self, body: &str) {
+        self.indent();
+        self.lines(body);
+        self.dedent();
+    }
+    /// fixed text like a runtime helper, every tab at the start of a line is one level
+    pub fn template(&mut self, text: &str) {
+        for line in text.lines() {
+            let content = line.trim_start_matches('\t');
+            let depth = line.len() - content.len();
+            self.level += depth;
+            self.line(content);
+            self.level -= depth;
+        }
+    }
+    /// `header {` and the lines after it one level deeper, an empty header opens a bare block
+    pub fn open(&mut self, header: &str) {
+        match (self.style.braces, header.is_empty()) {
+            (_, true) => self.line("{"),
+            (BraceStyle::SameLine, false) => self.line(&format!("{} {{", header)),
+            (BraceStyle::NextLine, false) => {
+                self.line(header);
+                self.line("{");
+            }
+        }
+        self.indent();
+    }
+    /// `}` of the innermost block, `suffix` like the `;` after a struct follows it
+    pub fn close(&mut self, suffix: &str) {
+        self.dedent();
+        self.line(&format!("}}{}", suffix));
+    }
+    /// `} else {`, closes the innermost block and opens the next one
+    pub fn close_open(&mut self, header: &str) {
+        self.dedent();
+        match self.style.braces {
+            BraceStyle::SameLine => self.line(&format!("}} {} {{", header)),
+            BraceStyle::NextLine => {
+                self.line("}");
+                self.line(header);
+                self.line("{");
+            }
+        }
+        self.indent();
+    }
+    /// `} else if (x) {`, closes the innermost block and continues with generated lines
+    pub fn close_with(&mut self, text: &str) {
+        self.dedent();
+        let mut lines = text.lines();
+        match (self.style.braces, lines.next()) {
+            (BraceStyle::SameLine, Some(first)) => self.line(&format!("}} {}", first)),
+            (_, first) => {
+                self.line("}");
+                self.lines(first.unwrap_or(""));
+            }
+        }
+        for line in lines {
+            self.line(line);
+        }
+    }
+    /// `header {`, the body one level deeper and `}`
+    pub fn block(&mut self, header: &str, body: &str) {
+        self.open(header);
+        self.lines(body);
+        self.close("");
+    }
+    pub fn finish(self) -> String {
+        return self.output;
+    }
+}
+
+/// indents the lines of a block for python and gdscript, an empty block is `pass`
+pub fn indent(style: CodeStyle, block: &str) -> String {
+    let mut writer = CodeWriter::new(style);
+    match block.is_empty() {
+        true => writer.nested("pass"),
+        false => writer.nested(block),
+    }
+    return writer.finish();
+}
@@ -0,0 +1,189 @@
+use std::env;
+
+use crate::{
+    backend::Target,
+    lints::{Lint, LintLevel, LintLevels},
+    parser::Dialect,
+    type_map::{parse_type_alias, TypeMap},
+    writer::{BraceStyle, CodeStyle, Indent},
+};
+
+const INPUT_FILE_PATH: &str = "./CompileTargets/tic_tac_toe.rs";
+/// the extension of the target is added
+const OUTPUT_FILE_PATH: &str = "./CompileTargets/Output";
+
+const USAGE: &str = "usage: RustTranspiler [input] [output] [options]
+options:
+    --target <name>         language to generate: csharp (default), typescript, python, c, lua,
+                            gdscript, wat
+    --type-map <path>       load type aliases from a file with `source = target` lines
+    --map-type <src=target> add a single type alias
+    --dialect <name>        legacy, rust or mixed (default) declaration syntax
+    --indent <n|tab>        indentation of the generated or formatted code, 4 spaces (default) or a tab
+                            for gdscript
+    --braces <style>        same-line (default) or next-line braces of the generated or formatted code
+    --emit-const            emit immutable variables with constant values as c# `const`
+    --class <name>          generate c# as a static class with fields, methods and `Main`
+                            instead of top level statements
+    --namespace <name>      put the c# class in a namespace, the class is `Program` by default
+    --no-fold               don't fold constants or remove branches that are never taken
+    --format                write the source back in its canonical layout instead of generating code
+    --convert-names         rename functions and classes to PascalCase and locals to camelCase
+    --extern <name>         treat a name defined outside of the source as declared
+    --allow <lint>          don't report a lint
+    --warn <lint>           report a lint as a warning (default)
+    --deny <lint>           report a lint as an error
+lints:
+    unused_variable, unused_parameter, unused_function, unused_mut,
+    shadowing, constant_condition, self_assignment";
+
+pub struct Config {
+    pub input_path: String,
+    /// files of targets with more than one output file get their own extension
+    pub output_path: String,
+    pub target: Target,
+    pub type_map: TypeMap,
+    pub dialect: Dialect,
+    pub emit_const: bool,
+    /// c# namespace and static class of the program, top level statements when both are `None`
+    pub namespace: Option<String>,
+    pub class_name: Option<String>,
+    /// `None` is the convention of the target
+    pub indent: Option<Indent>,
+    pub braces: BraceStyle,
+    /// fold constant expressions before generating code
+    pub fold: bool,
+    pub convert_names: bool,
+    /// print the parsed source instead of generating code
+    pub format: bool,
+    /// names like `Console` that are declared by the target language
+    pub externals: Vec<String>,
+    pub lint_levels: LintLevels,
+}
+impl Config {
+    pub fn from_args() -> Config {
+        let mut config = Config {
+            input_path: INPUT_FILE_PATH.to_string(),
+            output_path: String::new(),
+            target: Target::CSharp,
+            type_map: TypeMap::new(),
+            dialect: Dialect::Mixed,
+            emit_const: false,
+            namespace: None,
+            class_name: None,
+            indent: None,
+            braces: BraceStyle::SameLine,
+            fold: true,
+            convert_names: false,
+            format: false,
+            externals: Vec::new(),
+            lint_levels: LintLevels::new(),
+        };
+
+        let mut paths = Vec::new();
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--type-map" => config.type_map.load(&expect_value(&mut args, &arg)),
+                "--map-type" => {
+                    let (source, target) = parse_type_alias(&expect_value(&mut args, &arg));
+                    config.type_map.insert(source, target);
+                }
+                "--target" => config.target = parse_target(&expect_value(&mut args, &arg)),
+                "--dialect" => config.dialect = parse_dialect(&expect_value(&mut args, &arg)),
+                "--indent" => config.indent = Some(parse_indent(&expect_value(&mut args, &arg))),
+                "--braces" => config.braces = parse_braces(&expect_value(&mut args, &arg)),
+                "--emit-const" => config.emit_const = true,
+                "--class" => config.class_name = Some(expect_value(&mut args, &arg)),
+                "--namespace" => config.namespace = Some(expect_value(&mut args, &arg)),
+                "--no-fold" => config.fold = false,
+                "--convert-names" => config.convert_names = true,
+                "--format" => config.format = true,
+                "--extern" => config.externals.push(expect_value(&mut args, &arg)),
+                "--allow" | "--warn" | "--deny" => {
+                    let level = LintLevel::from_name(&arg[2..]).expect("matched above");
+                    let lint = parse_lint(&expect_value(&mut args, &arg));
+                    config.lint_levels.set(lint, level);
+                }
+                "--help" | "-h" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                option if option.starts_with("--") => {
+                    panic!("unknown option: {}\n{}", option, USAGE)
+                }
+                _ => paths.push(arg),
+            }
+        }
+
+        if config.namespace.is_some() && config.class_name.is_none() {
+            config.class_name = Some("Program".to_string());
+        }
+
+        let mut paths = paths.into_iter();
+        if let Some(input_path) = paths.next() {
+            config.input_path = input_path;
+        }
+        match paths.next() {
+            Some(output_path) => config.output_path = output_path,
+            None => {
+                let extension = match config.format {
+                    true => "rs",
+                    false => config.target.extension(),
+                };
+                config.output_path = format!("{}.{}", OUTPUT_FILE_PATH, extension)
+            }
+        }
+
+        config
+    }
+    /// `--indent` and `--braces`, `indent` is the convention of the target when `--indent` is missing
+    pub fn code_style(&self, indent: Indent) -> CodeStyle {
+        CodeStyle {
+            indent: self.indent.unwrap_or(indent),
+            braces: self.braces,
+        }
+    }
+}
+
+fn parse_dialect(name: &str) -> Dialect {
+    match name {
+        "legacy" => Dialect::Legacy,
+        "rust" => Dialect::Rust,
+        "mixed" => Dialect::Mixed,
+        _ => panic!("unknown dialect: {}\n{}", name, USAGE),
+    }
+}
+
+fn parse_indent(name: &str) -> Indent {
+    match Indent::from_name(name) {
+        Some(indent) => indent,
+        None => panic!("unknown indentation: {}\n{}", name, USAGE),
+    }
+}
+
+fn parse_braces(name: &str) -> BraceStyle {
+    match BraceStyle::from_name(name) {
+        Some(braces) => braces,
+        None => panic!("unknown brace style: {}\n{}", name, USAGE),
+    }
+}
+
+fn parse_target(name: &str) -> Target {
+    match Target::from_name(name) {
+        Some(target) => target,
+        None => panic!("unknown target: {}\n{}", name, USAGE),
+    }
+}
+
+fn parse_lint(id: &str) -> Lint {
+    match Lint::from_id(id) {
+        Some(lint) => lint,
+        None => panic!("unknown lint: {}\n{}", id, USAGE),
+    }
+}
+
+fn expect_value(args: &mut impl Iterator<Item = String>, option: &str) -> String {
+    args.next()
+        .unwrap_or_else(|| panic!("option: {} expects a value\n{}", option, USAGE))
+}
@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+
+use crate::{
+    ir::{Callee, Class, Expr, Function, Interpolation, Statement},
+    tokens::TokenKind,
+    types::Type,
+};
+
+/// constants of these types are copied to their uses, unsigned c# ints wrap around on subtraction
+/// and a folded float would change the type of `var`
+const PROPAGATED_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "isize", "u8", "u16", "usize", "sbyte", "short", "int", "long",
+    "byte", "ushort", "bool", "str", "String", "string",
+];
+
+struct ConstantFolder {
+    /// values of the immutable variables in every block the folder is in,
+    /// None for a name that hides a constant of an outer block
+    frames: Vec<HashMap<String, Option<Expr>>>,
+}
+impl ConstantFolder {
+    fn declare(&mut self, name: &str, value: Option<Expr>) {
+        let frame = self.frames.last_mut().expect("the program has a frame");
+        frame.insert(name.to_string(), value);
+    }
+    fn lookup(&self, name: &str) -> Option<&Expr> {
+        return self
+            .frames
+            .iter()
+            .rev()
+            .find_map(|frame| frame.get(name))?
+            .as_ref();
+    }
+}
+
+/// Evaluates arithmetic, comparisons, boolean logic and string concatenation of literals,
+/// copies the values of immutable constants to their uses and removes branches that are never taken
+pub fn fold_constants(program: Vec<Statement>) -> Vec<Statement> {
+    let mut folder = ConstantFolder { frames: Vec::new() };
+    return fold_block(&mut folder, program);
+}
+
+fn fold_block(folder: &mut ConstantFolder, statements: Vec<Statement>) -> Vec<Statement> {
+    folder.frames.push(HashMap::new());
+    let mut output = Vec::new();
+    for statement in statements {
+        fold_statement(folder, statement, &mut output);
+    }
+    folder.frames.pop();
+    return output;
+}
+/// `if true { .. }` leaves its statements, in a block when they declare something so the names stay local
+fn fold_branch(folder: &mut ConstantFolder, branch: Vec<Statement>, output: &mut Vec<Statement>) {
+    let branch = fold_block(folder, branch);
+    let declares = branch.iter().any(|statement| match statement {
+        Statement::Let { .. }
+        | Statement::LetTuple { .. }
+        | Statement::Function(_)
+        | Statement::Class(_) => true,
+        Statement::Expression(value) => value.declares(),
+        Statement::Assign { target, value, .. } => target.declares() || value.declares(),
+        _ => false,
+    });
+    if declares {
+        output.push(Statement::Block(branch));
+    } else {
+        output.extend(branch);
+    }
+}
+
+fn fold_statement(folder: &mut ConstantFolder, statement: Statement, output: &mut Vec<Statement>) {
+    let statement = match statement {
+        Statement::Let {
+            name,
+            var_type,
+            inferred,
+            mutable,
+            constant,
+            value,
+        } => {
+            let value = value.map(|value| fold_expr(folder, value));
+            let propagated = match (&value, &inferred) {
+                (Some(value), Some(Type::Symbol(symbol)))
+                    if !mutable
+                        && is_literal(value)
+                        && !is_long(value)
+                        && PROPAGATED_TYPES.contains(&&**symbol) =>
+                {
+                    Some(value.clone())
+                }
+                _ => None,
+            };
+            folder.declare(&name, propagated);
+            Statement::Let {
+                name,
+                var_type,
+                inferred,
+                mutable,
+                constant,
+                value,
+            }
+        }
+        Statement::LetTuple {
+            names,
+            types,
+            inferred,
+            value,
+        } => {
+            let value = value.map(|value| fold_expr(folder, value));
+            for name in &names {
+                folder.declare(name, None);
+            }
+            Statement::LetTuple {
+                names,
+                types,
+                inferred,
+                value,
+            }
+        }
+        Statement::Assign {
+            target,
+            operator,
+            value,
+        } => Statement::Assign {
+            target: fold_target(folder, target),
+            operator,
+            value: fold_expr(folder, value),
+        },
+        Statement::Expression(value) => Statement::Expression(fold_expr(folder, value)),
+        Statement::If {
+            condition,
+            then,
+            otherwise,
+        } => match fold_expr(folder, condition) {
+            Expr::Bool(true) => return fold_branch(folder, then, output),
+            Expr::Bool(false) => {
+                if let Some(otherwise) = otherwise {
+                    fold_branch(folder, otherwise, output);
+                }
+                return;
+            }
+            condition => Statement::If {
+                condition,
+                then: fold_block(folder, then),
+                otherwise: otherwise.map(|otherwise| fold_block(folder, otherwise)),
+            },
+        },
+        Statement::While { condition, body } => match fold_expr(folder, condition) {
+            Expr::Bool(false) => return,
+            condition => Statement::While {
+                condition,
+                body: fold_block(folder, body),
+            },
+        },
+        Statement::ForRange {
+            variable,
+            from,
+            to,
+            body,
+        } => {
+            let from = fold_expr(folder, from);
+            let to = fold_expr(folder, to);
+            folder
+                .frames
+                .push(HashMap::from([(variable.clone(), None)]));
+            let body = fold_block(folder, body);
+            folder.frames.pop();
+            Statement::ForRange {
+                variable,
+                from,
+                to,
+                body,
+            }
+        }
+        Statement::ForEach {
+            variable,
+            iterable,
+            body,
+        } => {
+            let iterable = fold_expr(folder, iterable);
+            folder
+                .frames
+                .push(HashMap::from([(variable.clone(), None)]));
+            let body = fold_block(folder, body);
+            folder.frames.pop();
+            Statement::ForEach {
+                variable,
+                iterable,
+                body,
+            }
+        }
+        Statement::Block(body) => Statement::Block(fold_block(folder, body)),
+        Statement::Return(value) => Statement::Return(value.map(|value| fold_expr(folder, value))),
+        Statement::Break => Statement::Break,
+        Statement::Continue => Statement::Continue,
+        Statement::Function(function) => Statement::Function(fold_function(folder, function)),
+        Statement::Class(class) => Statement::Class(Class {
+            methods: class
+                .methods
+                .into_iter()
+                .map(|method| fold_function(folder, method))
+                .collect(),
+            ..class
+        }),
+    };
+    output.push(statement);
+}
+fn fold_function(folder: &mut ConstantFolder, function: Function) -> Function {
+    // parameters hide constants with the same name
+    let parameters = function
+        .parameters
+        .iter()
+        .map(|parameter| (parameter.name.clone(), None))
+        .collect();
+    folder.frames.push(parameters);
+    let body = fold_block(folder, function.body);
+    folder.frames.pop();
+    return Function { body, ..function };
+}
+
+/// number, bool or string that is known at compile time
+fn is_literal(value: &Expr) -> bool {
+    return matches!(
+        value,
+        Expr::Number(_) | Expr::Float(_) | Expr::Bool(_) | Expr::String(_)
+    );
+}
+/// a copy of a number outside of the range of an int would lose the 64-bit type of its variable
+fn is_long(value: &Expr) -> bool {
+    return matches!(value, Expr::Number(number) if i32::try_from(*number).is_err());
+}
+/// $"{"a"}b{x}" -> $"ab{x}", a string without values is a plain literal
+fn fold_interpolated(folder: &mut ConstantFolder, parts: Vec<Interpolation>) -> Expr {
+    let mut folded: Vec<Interpolation> = Vec::new();
+    for part in parts {
+        let part = match part {
+            Interpolation::Value(value) => match fold_expr(folder, value) {
+                Expr::String(text) if is_literal_string(&text) => {
+                    Interpolation::Text(text[1..text.len() - 1].to_string())
+                }
+                value => Interpolation::Value(value),
+            },
+            text => text,
+        };
+        match (folded.last_mut(), part) {
+            (Some(Interpolation::Text(last)), Interpolation::Text(text)) => *last += &text,
+            (_, part) => folded.push(part),
+        }
+    }
+    match folded.as_slice() {
+        [] => return Expr::String("\"\"".to_string()),
+        [Interpolation::Text(text)] => return Expr::String(format!("\"{}\"", text)),
+        _ => return Expr::Interpolated(folded),
+    }
+}
+/// the variable that is assigned to stays, only the indexes are folded
+fn fold_target(folder: &mut ConstantFolder, target: Expr) -> Expr {
+    match target {
+        Expr::Index { value, indexes } => Expr::Index {
+            value: Box::new(fold_target(folder, *value)),
+            indexes: fold_values(folder, indexes),
+        },
+        Expr::Field { value, name } => Expr::Field {
+            value: Box::new(fold_target(folder, *value)),
+            name,
+        },
+        target => target,
+    }
+}
+fn fold_values(folder: &mut ConstantFolder, values: Vec<Expr>) -> Vec<Expr> {
+    return values
+        .into_iter()
+        .map(|value| fold_expr(folder, value))
+        .collect();
+}
+fn fold_box(folder: &mut ConstantFolder, value: Expr) -> Box<Expr> {
+    return Box::new(fold_expr(folder, value));
+}
+
+fn fold_expr(folder: &mut ConstantFolder, expression: Expr) -> Expr {
+    match expression {
+        Expr::Variable { name, var_type } => match folder.lookup(&name) {
+            Some(value) => value.clone(),
+            None => Expr::Variable { name, var_type },
+        },
+        Expr::Unary { operator, value } => {
+            let value = fold_expr(folder, *value);
+            match (operator, value) {
+                // -0 is written as 0
+                (TokenKind::Minus, Expr::Number(number)) => Expr::Number(-number),
+                (TokenKind::Plus, Expr::Number(number)) => Expr::Number(number),
+                (TokenKind::Minus, Expr::Float(number)) => Expr::Float(-number),
+                (TokenKind::Plus, Expr::Float(number)) => Expr::Float(number),
+                (TokenKind::Not, Expr::Bool(value)) => Expr::Bool(!value),
+                (operator, value) => Expr::Unary {
+                    operator,
+                    value: Box::new(value),
+                },
+            }
+        }
+        Expr::Binary { l, operator, r } => {
+            let l = fold_expr(folder, *l);
+            let divisor = matches!(operator, TokenKind::Slash | TokenKind::Percent);
+            let r = match fold_expr(folder, (*r).clone()) {
+                // c# rejects a division by a constant zero, the source only divides by zero at runtime
+                Expr::Number(value) if divisor && value == 0 => *r,
+                folded => folded,
+            };
+            match fold_binary(&l, operator, &r) {
+                Some(value) => value,
+                None => Expr::Binary {
+                    l: Box::new(l),
+                    operator,
+                    r: Box::new(r),
+                },
+            }
+        }
+        Expr::Interpolated(parts) => fold_interpolated(folder, parts),
+        Expr::Tuple(values) => Expr::Tuple(fold_values(folder, values)),
+        Expr::Field { value, name } => Expr::Field {
+            value: fold_box(folder, *value),
+            name,
+        },
+        Expr::StaticField { value, name } => Expr::StaticField {
+            value: fold_box(folder, *value),
+            name,
+        },
+        Expr::Index { value, indexes } => Expr::Index {
+            value: fold_box(folder, *value),
+            indexes: fold_values(folder, indexes),
+        },
+        Expr::Call { callee, arguments } => {
+            let callee = match callee {
+                Callee::Method {
+                    receiver,
+                    name,
+                    collection,
+                } => Callee::Method {
+                    // a method can change its receiver
+                    receiver: Box::new(fold_target(folder, *receiver)),
+                    name,
+                    collection,
+                },
+                Callee::Value(value) => Callee::Value(fold_box(folder, *value)),
+                callee => callee,
+            };
+            Expr::Call {
+                callee,
+                arguments: fold_values(folder, arguments),
+            }
+        }
+        Expr::New { class, fields } => Expr::New {
+            class,
+            fields: fields
+                .into_iter()
+                .map(|(name, value)| (name, fold_expr(folder, value)))
+                .collect(),
+        },
+        Expr::Array(values) => Expr::Array(fold_values(folder, values)),
+        Expr::List {
+            element_type,
+            values,
+        } => Expr::List {
+            element_type,
+            values: fold_values(folder, values),
+        },
+        Expr::Out { name, var_type } => {
+            // `out i32 x` declares x
+            if var_type.is_some() {
+                folder.declare(&name, None);
+            }
+            Expr::Out { name, var_type }
+        }
+        Expr::Ref {
+            value,
+            mutable: true,
+        } => Expr::Ref {
+            value: Box::new(fold_target(folder, *value)),
+            mutable: true,
+        },
+        Expr::Ref {
+            value,
+            mutable: false,
+        } => Expr::Ref {
+            value: fold_box(folder, *value),
+            mutable: false,
+        },
+        expression => expression,
+    }
+}
+
+fn fold_binary(l: &Expr, operator: TokenKind, r: &Expr) -> Option<Expr> {
+    match (l, r) {
+        (Expr::Number(l), Expr::Number(r)) => return fold_numbers(*l, operator, *r),
+        (Expr::Bool(l), Expr::Bool(r)) => {
+            let value = match operator {
+                TokenKind::And => *l && *r,
+                TokenKind::Or => *l || *r,
+                TokenKind::Equals => l == r,
+                TokenKind::NotEquals => l != r,
+                _ => return None,
+            };
+            return Some(Expr::Bool(value));
+        }
+        // the right side of `false && x` is never evaluated
+        (Expr::Bool(l), r) => match (operator, *l) {
+            (TokenKind::And, false) | (TokenKind::Or, true) => return Some(Expr::Bool(*l)),
+            (TokenKind::And, true) | (TokenKind::Or, false) => return Some(r.clone()),
+            _ => return None,
+        },
+        (Expr::String(l), Expr::String(r)) if is_literal_string(l) && is_literal_string(r) => {
+            match operator {
+                // "a" + "b" -> "ab"
+                TokenKind::Plus => {
+                    return Some(Expr::String(format!("{}{}", &l[..l.len() - 1], &r[1..])))
+                }
+                // escapes can spell the same string differently
+                TokenKind::Equals | TokenKind::NotEquals
+                    if !l.contains('\\') && !r.contains('\\') =>
+                {
+                    return Some(Expr::Bool((l == r) == (operator == TokenKind::Equals)))
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    }
+}
+fn is_literal_string(text: &str) -> bool {
+    return text.len() >= 2 && text.starts_with('"') && text.ends_with('"');
+}
+/// Only int literals are folded, floats are left to the target so `1.0 / 3.0` keeps its fraction
+fn fold_numbers(l: i128, operator: TokenKind, r: i128) -> Option<Expr> {
+    let comparison = match operator {
+        TokenKind::Less => Some(l < r),
+        TokenKind::LessEquals => Some(l <= r),
+        TokenKind::Greater => Some(l > r),
+        TokenKind::GreaterEquals => Some(l >= r),
+        TokenKind::Equals => Some(l == r),
+        TokenKind::NotEquals => Some(l != r),
+        _ => None,
+    };
+    if let Some(comparison) = comparison {
+        return Some(Expr::Bool(comparison));
+    }
+
+    // integer division rounds towards zero
+    let value = match operator {
+        TokenKind::Plus => l.checked_add(r),
+        TokenKind::Minus => l.checked_sub(r),
+        TokenKind::Star => l.checked_mul(r),
+        TokenKind::Slash => l.checked_div(r),
+        TokenKind::Percent => l.checked_rem(r),
+        _ => None,
+    }?;
+    // an int that overflows wraps around at runtime, a larger literal would change the type of `var`
+    if i32::try_from(value).is_err() {
+        return None;
+    }
+    return Some(Expr::Number(value));
+}
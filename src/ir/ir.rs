@@ -0,0 +1,293 @@
+use crate::{tokens::TokenKind, types::Type};
+
+// Lowered form of the program that every backend generates code from.
+// Statements and values are separate, there are no `;` or `else` nodes, loops are explicit
+// and calls know what they call. Types are the source types, backends map them themselves.
+
+#[derive(Debug, Clone)]
+pub enum Statement {
+    // let x: T = value, value is None for `let x;`
+    Let {
+        name: String,
+        // type written in the source, None when it is inferred
+        var_type: Option<Type>,
+        // declared or inferred type
+        inferred: Option<Type>,
+        mutable: bool,
+        // immutable with a primitive type and a value known at compile time
+        constant: bool,
+        value: Option<Expr>,
+    },
+    // let (a, b): (i32, bool) = value
+    LetTuple {
+        names: Vec<String>,
+        types: Option<Vec<Type>>,
+        // declared or inferred types, None when one of them is unknown
+        inferred: Option<Vec<Type>>,
+        value: Option<Expr>,
+    },
+    // target operator value, the operator is `=`, `+=` or `-=`
+    Assign {
+        target: Expr,
+        operator: TokenKind,
+        value: Expr,
+    },
+    Expression(Expr),
+    // else if is an if inside of `otherwise`
+    If {
+        condition: Expr,
+        then: Vec<Statement>,
+        otherwise: Option<Vec<Statement>>,
+    },
+    While {
+        condition: Expr,
+        body: Vec<Statement>,
+    },
+    // for variable in from..to, `to` is evaluated once
+    ForRange {
+        variable: String,
+        from: Expr,
+        to: Expr,
+        body: Vec<Statement>,
+    },
+    // for variable in iterable
+    ForEach {
+        variable: String,
+        iterable: Expr,
+        body: Vec<Statement>,
+    },
+    // statements with their own scope, left by removing a branch that is always taken
+    Block(Vec<Statement>),
+    Return(Option<Expr>),
+    Break,
+    Continue,
+    Function(Function),
+    Class(Class),
+}
+
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    pub public: bool,
+    pub parameters: Vec<Parameter>,
+    pub output: Option<Type>,
+    pub body: Vec<Statement>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Parameter {
+    pub name: String,
+    pub var_type: Type,
+}
+
+#[derive(Debug, Clone)]
+pub struct Class {
+    pub name: String,
+    pub public: bool,
+    pub fields: Vec<Field>,
+    pub methods: Vec<Function>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub var_type: Type,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Number(i128),
+    // written with a `.`, never folded with arithmetic
+    Float(f32),
+    // the text with the quotes
+    String(String),
+    // $"text {value}"
+    Interpolated(Vec<Interpolation>),
+    Bool(bool),
+    // variable, parameter or function declared in the source
+    Variable {
+        name: String,
+        var_type: Option<Type>,
+    },
+    // name from the target like `Console` or a name that couldn't be resolved, written as it is
+    External(String),
+    Unary {
+        operator: TokenKind,
+        value: Box<Expr>,
+    },
+    Binary {
+        l: Box<Expr>,
+        operator: TokenKind,
+        r: Box<Expr>,
+    },
+    Tuple(Vec<Expr>),
+    // value.name
+    Field {
+        value: Box<Expr>,
+        name: String,
+    },
+    // Type::name that is not called
+    StaticField {
+        value: Box<Expr>,
+        name: String,
+    },
+    Index {
+        value: Box<Expr>,
+        indexes: Vec<Expr>,
+    },
+    Call {
+        callee: Callee,
+        arguments: Vec<Expr>,
+    },
+    // Point { x = 1; y = 2; }
+    New {
+        class: String,
+        fields: Vec<(String, Expr)>,
+    },
+    // {1, 2, 3}, multi dimensional arrays are arrays of arrays
+    Array(Vec<Expr>),
+    // vec![1, 2], element type from the declaration or the first value
+    List {
+        element_type: Option<Type>,
+        values: Vec<Expr>,
+    },
+    // Vec::new(), the type is known when the value is assigned to a declaration with a type
+    NewCollection {
+        collection: String,
+        var_type: Option<Type>,
+    },
+    // out x or out i32 x which declares x
+    Out {
+        name: String,
+        var_type: Option<Type>,
+    },
+    // &value or &mut value
+    Ref {
+        value: Box<Expr>,
+        mutable: bool,
+    },
+}
+
+/// Piece of an interpolated string
+#[derive(Debug, Clone)]
+pub enum Interpolation {
+    /// text between the values, escapes like `\n` are kept as they are written
+    Text(String),
+    Value(Expr),
+}
+
+#[derive(Debug, Clone)]
+pub enum Callee {
+    // function declared in the source
+    Function(String),
+    // value.name(), collection is the kind of the receiver when its type is known (`Vec`, `String`, `Array` ...)
+    Method {
+        receiver: Box<Expr>,
+        name: String,
+        collection: Option<String>,
+    },
+    // Type::name()
+    Static {
+        type_name: String,
+        name: String,
+    },
+    // everything else, like a function from the target
+    Value(Box<Expr>),
+}
+impl Expr {
+    /// values directly inside of this one
+    pub fn children(&self) -> Vec<&Expr> {
+        match self {
+            Expr::Unary { value, .. }
+            | Expr::Field { value, .. }
+            | Expr::StaticField { value, .. }
+            | Expr::Ref { value, .. } => vec![value],
+            Expr::Binary { l, r, .. } => vec![l, r],
+            Expr::Tuple(values) | Expr::Array(values) | Expr::List { values, .. } => {
+                values.iter().collect()
+            }
+            Expr::Index { value, indexes } => {
+                let mut children = vec![&**value];
+                children.extend(indexes);
+                children
+            }
+            Expr::Call { callee, arguments } => {
+                let mut children = match callee {
+                    Callee::Method { receiver, .. } => vec![&**receiver],
+                    Callee::Value(value) => vec![&**value],
+                    Callee::Function(_) | Callee::Static { .. } => Vec::new(),
+                };
+                children.extend(arguments);
+                children
+            }
+            Expr::New { fields, .. } => fields.iter().map(|(_, value)| value).collect(),
+            Expr::Interpolated(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    Interpolation::Value(value) => Some(value),
+                    Interpolation::Text(_) => None,
+                })
+                .collect(),
+            Expr::Number(_)
+            | Expr::Float(_)
+            | Expr::String(_)
+            | Expr::Bool(_)
+            | Expr::Variable { .. }
+            | Expr::External(_)
+            | Expr::NewCollection { .. }
+            | Expr::Out { .. } => Vec::new(),
+        }
+    }
+    pub fn children_mut(&mut self) -> Vec<&mut Expr> {
+        match self {
+            Expr::Unary { value, .. }
+            | Expr::Field { value, .. }
+            | Expr::StaticField { value, .. }
+            | Expr::Ref { value, .. } => vec![value],
+            Expr::Binary { l, r, .. } => vec![l, r],
+            Expr::Tuple(values) | Expr::Array(values) | Expr::List { values, .. } => {
+                values.iter_mut().collect()
+            }
+            Expr::Index { value, indexes } => {
+                let mut children = vec![&mut **value];
+                children.extend(indexes);
+                children
+            }
+            Expr::Call { callee, arguments } => {
+                let mut children = match callee {
+                    Callee::Method { receiver, .. } => vec![&mut **receiver],
+                    Callee::Value(value) => vec![&mut **value],
+                    Callee::Function(_) | Callee::Static { .. } => Vec::new(),
+                };
+                children.extend(arguments);
+                children
+            }
+            Expr::New { fields, .. } => fields.iter_mut().map(|(_, value)| value).collect(),
+            Expr::Interpolated(parts) => parts
+                .iter_mut()
+                .filter_map(|part| match part {
+                    Interpolation::Value(value) => Some(value),
+                    Interpolation::Text(_) => None,
+                })
+                .collect(),
+            Expr::Number(_)
+            | Expr::Float(_)
+            | Expr::String(_)
+            | Expr::Bool(_)
+            | Expr::Variable { .. }
+            | Expr::External(_)
+            | Expr::NewCollection { .. }
+            | Expr::Out { .. } => Vec::new(),
+        }
+    }
+    /// `out i32 x` somewhere inside declares x in the enclosing block
+    pub fn declares(&self) -> bool {
+        if let Expr::Out {
+            var_type: Some(_), ..
+        } = self
+        {
+            return true;
+        }
+        return self.children().into_iter().any(Expr::declares);
+    }
+}
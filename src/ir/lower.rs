@@ -0,0 +1,629 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    collections::{collection_name, handle_collection_name},
+    expression::{Expression, InterpolationPart},
+    ir::{Callee, Class, Expr, Field, Function, Interpolation, Parameter, Statement},
+    resolver::{Resolution, SymbolId, SymbolKind},
+    tokens::{Span, TokenKind},
+    type_map::TypeMap,
+    types::Type,
+};
+
+struct Lowering<'a> {
+    resolution: &'a Resolution,
+    inferred: &'a HashMap<SymbolId, Type>,
+    type_map: &'a TypeMap,
+    /// immutable variables with constant values, they can be used in other constants
+    constants: HashSet<SymbolId>,
+    /// names of the symbols and of the temporaries that were already created
+    taken: HashSet<String>,
+}
+impl Lowering<'_> {
+    fn symbol(&self, span: Span) -> Option<SymbolId> {
+        return self.resolution.references.get(&span).copied();
+    }
+    /// declared or inferred type of the variable at `span`
+    fn symbol_type(&self, span: Span) -> Option<Type> {
+        let id = self.symbol(span)?;
+        let symbol = &self.resolution.symbols[id];
+        match symbol.kind {
+            SymbolKind::Variable | SymbolKind::Parameter | SymbolKind::Field => {
+                return symbol
+                    .var_type
+                    .clone()
+                    .or_else(|| self.inferred.get(&id).cloned());
+            }
+            _ => return None,
+        }
+    }
+    /// `name` or `name_2` ... that is not used by any symbol
+    fn temporary(&mut self, name: &str) -> String {
+        let mut temporary = name.to_string();
+        let mut i = 2;
+        while self.taken.contains(&temporary) {
+            temporary = format!("{}_{}", name, i);
+            i += 1;
+        }
+        self.taken.insert(temporary.clone());
+        return temporary;
+    }
+}
+
+/// Lowers the checked ast into the ir that the backends generate code from
+pub fn lower(
+    expressions: Vec<Expression>,
+    resolution: &Resolution,
+    inferred: &HashMap<SymbolId, Type>,
+    type_map: &TypeMap,
+) -> Vec<Statement> {
+    let mut lowering = Lowering {
+        resolution,
+        inferred,
+        type_map,
+        constants: HashSet::new(),
+        taken: resolution
+            .symbols
+            .iter()
+            .map(|symbol| symbol.name.clone())
+            .collect(),
+    };
+    return lower_block(&mut lowering, expressions);
+}
+
+fn lower_block(lowering: &mut Lowering, expressions: Vec<Expression>) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    for expression in expressions {
+        match expression {
+            // separators and attributes only matter to the parser and the analysis
+            Expression::Keyword(TokenKind::SemiColon | TokenKind::Comma)
+            | Expression::Attribute { .. } => {}
+            Expression::Else {
+                condition, inside, ..
+            } => {
+                let otherwise = match condition {
+                    Some(condition) => vec![Statement::If {
+                        condition: lower_expr(lowering, *condition),
+                        then: lower_block(lowering, inside),
+                        otherwise: None,
+                    }],
+                    None => lower_block(lowering, inside),
+                };
+                attach_else(statements.last_mut(), otherwise);
+            }
+            expression => lower_statement(lowering, expression, &mut statements),
+        }
+    }
+    return statements;
+}
+/// else and else if belong to the last if of the chain before them
+fn attach_else(previous: Option<&mut Statement>, otherwise: Vec<Statement>) {
+    let Some(Statement::If {
+        otherwise: previous_otherwise,
+        ..
+    }) = previous
+    else {
+        panic!("else has to follow an if");
+    };
+    match previous_otherwise {
+        Some(chain) if matches!(chain.as_slice(), [Statement::If { .. }]) => {
+            attach_else(chain.last_mut(), otherwise)
+        }
+        Some(_) => panic!("if already has an else"),
+        None => *previous_otherwise = Some(otherwise),
+    }
+}
+
+fn lower_statement(
+    lowering: &mut Lowering,
+    expression: Expression,
+    statements: &mut Vec<Statement>,
+) {
+    let statement = match expression {
+        Expression::Assignment {
+            target,
+            operator,
+            value,
+        } => lower_assignment(lowering, *target, operator.kind, *value),
+        Expression::VariableDeclaration { .. } | Expression::TupleDeclaration { .. } => {
+            lower_declaration(lowering, expression, None)
+        }
+        Expression::Keyword(TokenKind::Break) => Statement::Break,
+        Expression::Keyword(TokenKind::Continue) => Statement::Continue,
+        Expression::Return { value, .. } => match *value {
+            Expression::Keyword(TokenKind::SemiColon) => Statement::Return(None),
+            value => Statement::Return(Some(lower_expr(lowering, value))),
+        },
+        Expression::If {
+            condition, inside, ..
+        } => Statement::If {
+            condition: lower_expr(lowering, *condition),
+            then: lower_block(lowering, inside),
+            otherwise: None,
+        },
+        Expression::While {
+            condition, inside, ..
+        } => Statement::While {
+            condition: lower_expr(lowering, *condition),
+            body: lower_block(lowering, inside),
+        },
+        Expression::For {
+            iterator_name,
+            iteration_target,
+            inside,
+            ..
+        } => match *iteration_target {
+            Expression::Range { from, to } => {
+                let stable = is_stable(lowering, &to);
+                let from = lower_expr(lowering, *from);
+                let mut to = lower_expr(lowering, *to);
+                // the end of a rust range is evaluated once, c like loops check it every iteration
+                if !stable {
+                    let name = lowering.temporary(&format!("{}_end", iterator_name));
+                    statements.push(Statement::Let {
+                        name: name.clone(),
+                        var_type: None,
+                        // ranges count with the same integers as the loop variable
+                        inferred: Some(Type::Symbol("i32".to_string())),
+                        mutable: false,
+                        constant: false,
+                        value: Some(to),
+                    });
+                    to = Expr::Variable {
+                        name,
+                        var_type: Some(Type::Symbol("i32".to_string())),
+                    };
+                }
+                Statement::ForRange {
+                    variable: iterator_name,
+                    from,
+                    to,
+                    body: lower_block(lowering, inside),
+                }
+            }
+            iterable => Statement::ForEach {
+                variable: iterator_name,
+                iterable: lower_expr(lowering, iterable),
+                body: lower_block(lowering, inside),
+            },
+        },
+        Expression::Function {
+            name,
+            properties,
+            public,
+            output,
+            inside,
+            ..
+        } => Statement::Function(lower_function(
+            lowering, name, properties, public, output, inside,
+        )),
+        Expression::Class {
+            public,
+            name,
+            properties,
+            functions,
+            ..
+        } => Statement::Class(lower_class(lowering, public, name, properties, functions)),
+        expression => Statement::Expression(lower_expr(lowering, expression)),
+    };
+    statements.push(statement);
+}
+/// literals and variables that can't change inside of a loop
+fn is_stable(lowering: &Lowering, value: &Expression) -> bool {
+    match value {
+        Expression::Number(_) | Expression::Float(_) => true,
+        Expression::Identifier { span, .. } => match lowering.symbol(*span) {
+            Some(id) => !lowering.resolution.symbols[id].mutable,
+            None => true,
+        },
+        Expression::Grouping(inside) => is_stable(lowering, inside),
+        _ => false,
+    }
+}
+
+fn lower_function(
+    lowering: &mut Lowering,
+    name: String,
+    properties: Vec<Expression>,
+    public: bool,
+    output: Option<Type>,
+    inside: Vec<Expression>,
+) -> Function {
+    let parameters = properties
+        .into_iter()
+        .map(|property| match property {
+            Expression::FunctionProperty {
+                var_name, var_type, ..
+            } => Parameter {
+                name: var_name,
+                var_type,
+            },
+            default => panic!("expected function property, found {:?}", default),
+        })
+        .collect();
+    return Function {
+        name,
+        public,
+        parameters,
+        output,
+        body: lower_block(lowering, inside),
+    };
+}
+fn lower_class(
+    lowering: &mut Lowering,
+    public: bool,
+    name: String,
+    properties: Vec<Expression>,
+    functions: Vec<Expression>,
+) -> Class {
+    let fields = properties
+        .into_iter()
+        .map(|property| match property {
+            Expression::ClassProperty {
+                var_name, var_type, ..
+            } => Field {
+                name: var_name,
+                var_type,
+            },
+            default => panic!("expected class property, found {:?}", default),
+        })
+        .collect();
+    let methods = functions
+        .into_iter()
+        .map(|function| match function {
+            Expression::Function {
+                name,
+                properties,
+                public,
+                output,
+                inside,
+                ..
+            } => lower_function(lowering, name, properties, public, output, inside),
+            default => panic!("expected function in class, found {:?}", default),
+        })
+        .collect();
+    return Class {
+        name,
+        public,
+        fields,
+        methods,
+    };
+}
+
+fn lower_assignment(
+    lowering: &mut Lowering,
+    target: Expression,
+    operator: TokenKind,
+    value: Expression,
+) -> Statement {
+    match target {
+        Expression::VariableDeclaration { .. } | Expression::TupleDeclaration { .. } => {
+            return lower_declaration(lowering, target, Some(value));
+        }
+        _ => {}
+    }
+    let expected = match &target {
+        Expression::Identifier { span, .. } => lowering.symbol_type(*span),
+        _ => None,
+    };
+    return Statement::Assign {
+        target: lower_expr(lowering, target),
+        operator,
+        value: lower_value(lowering, value, expected.as_ref()),
+    };
+}
+fn lower_declaration(
+    lowering: &mut Lowering,
+    target: Expression,
+    value: Option<Expression>,
+) -> Statement {
+    match target {
+        Expression::VariableDeclaration {
+            var_type,
+            name,
+            mutable,
+            span,
+        } => {
+            let id = lowering.symbol(span);
+            let inferred = var_type.clone().or_else(|| lowering.symbol_type(span));
+            let constant = match (&var_type, &value) {
+                (Some(Type::Symbol(symbol)), Some(value)) => {
+                    !mutable
+                        && lowering.type_map.get(symbol).is_some()
+                        && is_constant_value(lowering, value)
+                }
+                _ => false,
+            };
+            if let (true, Some(id)) = (constant, id) {
+                lowering.constants.insert(id);
+            }
+            let value = value.map(|value| lower_value(lowering, value, inferred.as_ref()));
+            return Statement::Let {
+                name,
+                var_type,
+                inferred,
+                mutable,
+                constant,
+                value,
+            };
+        }
+        Expression::TupleDeclaration { var_type, bindings } => {
+            let (names, inferred): (Vec<String>, Vec<Option<Type>>) = bindings
+                .into_iter()
+                .map(|binding| match binding {
+                    Expression::VariableDeclaration { name, span, .. } => {
+                        (name, lowering.symbol_type(span))
+                    }
+                    default => panic!(
+                        "expected variable declaration in tuple, found {:?}",
+                        default
+                    ),
+                })
+                .unzip();
+            let types = match var_type {
+                Some(Type::Tuple(types)) if types.len() == names.len() => Some(types),
+                Some(default) => panic!(
+                    "tuple declaration of: {:?} has to have a tuple type with the same length, found: {:?}",
+                    names, default
+                ),
+                None => None,
+            };
+            let inferred = match &types {
+                Some(types) => Some(types.clone()),
+                None => inferred.into_iter().collect(),
+            };
+            return Statement::LetTuple {
+                names,
+                types,
+                inferred,
+                value: value.map(|value| lower_expr(lowering, value)),
+            };
+        }
+        default => panic!("expected declaration, found {:?}", default),
+    }
+}
+/// `let i32 x = 1 + 2;` is constant, interpolated strings are evaluated at runtime
+fn is_constant_value(lowering: &Lowering, value: &Expression) -> bool {
+    match value {
+        Expression::Number(_) | Expression::Float(_) => true,
+        Expression::String(_) => true,
+        Expression::Identifier { span, .. } => lowering
+            .symbol(*span)
+            .is_some_and(|id| lowering.constants.contains(&id)),
+        Expression::Grouping(inside) => is_constant_value(lowering, inside),
+        Expression::Prefix { value, .. } => is_constant_value(lowering, value),
+        Expression::Binary { l, r, .. } => {
+            is_constant_value(lowering, l) && is_constant_value(lowering, r)
+        }
+        _ => false,
+    }
+}
+
+/// value assigned to something of the `expected` type, `vec![]` and `Vec::new()` take their types from it
+fn lower_value(lowering: &mut Lowering, value: Expression, expected: Option<&Type>) -> Expr {
+    match value {
+        Expression::MacroCall { name, values } if name == "vec" => {
+            let element_type = match expected {
+                Some(Type::Generic { name, arguments })
+                    if name == "Vec" && arguments.len() == 1 =>
+                {
+                    Some(arguments[0].clone())
+                }
+                _ => literal_type(values.first()),
+            };
+            return Expr::List {
+                element_type,
+                values: lower_values(lowering, values),
+            };
+        }
+        Expression::FunctionCall { left, values } => match *left {
+            Expression::StaticMember { member, name } => {
+                let collection = match &*member {
+                    Expression::Identifier {
+                        name: type_name, ..
+                    } if (name == "new" || name == "with_capacity")
+                        && handle_collection_name(type_name).is_some() =>
+                    {
+                        Some(type_name.clone())
+                    }
+                    _ => None,
+                };
+                if let Some(collection) = collection {
+                    let var_type = match expected {
+                        Some(expected @ Type::Generic { name, .. }) if *name == collection => {
+                            Some(expected.clone())
+                        }
+                        _ => None,
+                    };
+                    return Expr::NewCollection {
+                        collection,
+                        var_type,
+                    };
+                }
+                let left = Expression::StaticMember { member, name };
+                return lower_call(lowering, left, values);
+            }
+            left => return lower_call(lowering, left, values),
+        },
+        Expression::Grouping(inside) => return lower_value(lowering, *inside, expected),
+        value => return lower_expr(lowering, value),
+    }
+}
+/// source type of a literal, used when there is no declared type to take it from
+fn literal_type(value: Option<&Expression>) -> Option<Type> {
+    let name = match value {
+        Some(Expression::Number(_)) => "i32",
+        // rust reads an untyped float literal as f64
+        Some(Expression::Float(_)) => "f64",
+        Some(Expression::String(_) | Expression::Interpolated(_)) => "str",
+        _ => return None,
+    };
+    return Some(Type::Symbol(name.to_string()));
+}
+
+fn lower_values(lowering: &mut Lowering, values: Vec<Expression>) -> Vec<Expr> {
+    return values
+        .into_iter()
+        .filter(|value| {
+            !matches!(
+                value,
+                Expression::Keyword(TokenKind::SemiColon | TokenKind::Comma)
+            )
+        })
+        .map(|value| lower_expr(lowering, value))
+        .collect();
+}
+
+fn lower_expr(lowering: &mut Lowering, expression: Expression) -> Expr {
+    match expression {
+        Expression::Number(value) => Expr::Number(value),
+        Expression::Float(value) => Expr::Float(value),
+        Expression::String(value) => Expr::String(value),
+        Expression::Interpolated(parts) => Expr::Interpolated(
+            parts
+                .into_iter()
+                .map(|part| match part {
+                    InterpolationPart::Text(text) => Interpolation::Text(text),
+                    InterpolationPart::Value(value) => {
+                        Interpolation::Value(lower_expr(lowering, value))
+                    }
+                })
+                .collect(),
+        ),
+        Expression::Identifier { name, span } => lower_identifier(lowering, name, span),
+        Expression::Grouping(inside) => lower_expr(lowering, *inside),
+        Expression::Prefix { prefix, value } => Expr::Unary {
+            operator: prefix.kind,
+            value: Box::new(lower_expr(lowering, *value)),
+        },
+        Expression::Binary { l, operator, r } => Expr::Binary {
+            l: Box::new(lower_expr(lowering, *l)),
+            operator: operator.kind,
+            r: Box::new(lower_expr(lowering, *r)),
+        },
+        Expression::Tuple(values) => Expr::Tuple(lower_values(lowering, values)),
+        Expression::Borrow { value, mutable, .. } => Expr::Ref {
+            value: Box::new(lower_expr(lowering, *value)),
+            mutable,
+        },
+        Expression::MemberExpr { member, name, .. } => Expr::Field {
+            value: Box::new(lower_expr(lowering, *member)),
+            name,
+        },
+        Expression::StaticMember { member, name } => Expr::StaticField {
+            value: Box::new(lower_expr(lowering, *member)),
+            name,
+        },
+        Expression::IndexArray { left, indexes } => Expr::Index {
+            value: Box::new(lower_expr(lowering, *left)),
+            indexes: lower_values(lowering, indexes),
+        },
+        Expression::FunctionCall { .. } | Expression::MacroCall { .. } => {
+            lower_value(lowering, expression, None)
+        }
+        Expression::ClassInstantiation {
+            name, properties, ..
+        } => {
+            let fields = properties
+                .into_iter()
+                .filter(|property| !matches!(property, Expression::Keyword(_)))
+                .map(|property| match property {
+                    Expression::Assignment { target, value, .. } => match *target {
+                        Expression::Identifier { name, .. } => (name, lower_expr(lowering, *value)),
+                        default => panic!("expected field name, found {:?}", default),
+                    },
+                    default => panic!("expected `field = value`, found {:?}", default),
+                })
+                .collect();
+            Expr::New {
+                class: name,
+                fields,
+            }
+        }
+        Expression::ArrayInitialization { properties } => {
+            Expr::Array(lower_values(lowering, properties))
+        }
+        Expression::Out {
+            var_type, var_name, ..
+        } => Expr::Out {
+            name: var_name,
+            var_type,
+        },
+        Expression::Range { .. } => panic!("encountered range in un expected position"),
+        Expression::Keyword(token_kind) => {
+            panic!("key word: {:?} doesn't have a handler", token_kind)
+        }
+        default => panic!("{:?} can't be used as a value", default),
+    }
+}
+fn lower_identifier(lowering: &Lowering, name: String, span: Span) -> Expr {
+    let Some(id) = lowering.symbol(span) else {
+        return Expr::External(name);
+    };
+    match lowering.resolution.symbols[id].kind {
+        SymbolKind::External => match name.as_str() {
+            "true" => Expr::Bool(true),
+            "false" => Expr::Bool(false),
+            _ => Expr::External(name),
+        },
+        _ => Expr::Variable {
+            var_type: lowering.symbol_type(span),
+            name,
+        },
+    }
+}
+fn lower_call(lowering: &mut Lowering, left: Expression, values: Vec<Expression>) -> Expr {
+    let callee = match left {
+        Expression::MemberExpr { member, name, .. } => {
+            let collection = receiver_type(lowering, &member)
+                .as_ref()
+                .and_then(collection_name)
+                .map(|name| name.to_string());
+            Callee::Method {
+                receiver: Box::new(lower_expr(lowering, *member)),
+                name,
+                collection,
+            }
+        }
+        Expression::StaticMember { member, name } => match *member {
+            Expression::Identifier {
+                name: type_name, ..
+            } => match (type_name.as_str(), name.as_str()) {
+                // strings are the same value in every target
+                ("String", "new") if values.is_empty() => return Expr::String("\"\"".to_string()),
+                ("String", "from") if values.len() == 1 => {
+                    let value = values.into_iter().next().expect("length is checked");
+                    return lower_expr(lowering, value);
+                }
+                _ => Callee::Static { type_name, name },
+            },
+            member => Callee::Value(Box::new(Expr::StaticField {
+                value: Box::new(lower_expr(lowering, member)),
+                name,
+            })),
+        },
+        Expression::Identifier { name, span }
+            if lowering
+                .symbol(span)
+                .is_some_and(|id| lowering.resolution.symbols[id].kind == SymbolKind::Function) =>
+        {
+            Callee::Function(name)
+        }
+        left => Callee::Value(Box::new(lower_expr(lowering, left))),
+    };
+    return Expr::Call {
+        callee,
+        arguments: lower_values(lowering, values),
+    };
+}
+/// Type of a method receiver when it is known, decides how methods of collections are written
+fn receiver_type(lowering: &Lowering, member: &Expression) -> Option<Type> {
+    match member {
+        Expression::Identifier { span, .. } => lowering.symbol_type(*span),
+        Expression::String(_) | Expression::Interpolated(_) => {
+            Some(Type::Symbol("str".to_string()))
+        }
+        Expression::Grouping(inside) => receiver_type(lowering, inside),
+        _ => None,
+    }
+}
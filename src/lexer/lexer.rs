@@ -1,6 +1,6 @@
 use crate::tokens::*;
 use core::str;
-use std::{cmp, collections::HashMap, usize};
+use std::{cmp, collections::HashMap};
 
 struct Pattern {
     kind: TokenKind,
@@ -12,7 +12,7 @@ impl Pattern {
         let split = value.split("").map(|s| s.to_string());
         let mut source: Vec<String> = Vec::new();
         for item in split {
-            if &item != "" {
+            if !item.is_empty() {
                 source.push(item);
             }
         }
@@ -24,11 +24,7 @@ impl Pattern {
     }
     pub fn on_match_non_number(&self, lex: &mut Lexer) {
         lex.advance(self.value.len() as u16);
-        lex.push(Token {
-            kind: self.kind.clone(),
-            value: self.value_string.clone(),
-            line: lex.current_line,
-        });
+        lex.push(self.kind, self.value_string.clone());
     }
 }
 
@@ -37,6 +33,10 @@ pub struct Lexer {
     pub source: Vec<String>,
     pos: u16,
     current_line: u16,
+    /// pos of the first character in the current line
+    line_start: u16,
+    /// where the token that is being matched begins
+    token_start: Span,
     black_list: Vec<TokenKind>,
 }
 impl Lexer {
@@ -45,7 +45,7 @@ impl Lexer {
         let split = source.split("").map(|s| s.to_string());
         let mut source: Vec<String> = Vec::new();
         for item in split {
-            if &item != "" && &item != "\r" {
+            if !item.is_empty() && &item != "\r" {
                 source.push(item);
             }
         }
@@ -55,6 +55,8 @@ impl Lexer {
             pos: 0,
             black_list,
             current_line: 0,
+            line_start: 0,
+            token_start: Span::default(),
         }
     }
     fn get_patterns() -> Vec<Pattern> {
@@ -79,11 +81,14 @@ impl Lexer {
             Pattern::new(TokenKind::Greater, ">"),
             Pattern::new(TokenKind::Or, "||"),
             Pattern::new(TokenKind::And, "&&"),
+            Pattern::new(TokenKind::Ampersand, "&"),
             Pattern::new(TokenKind::DotDot, ".."),
             Pattern::new(TokenKind::Dot, "."),
             Pattern::new(TokenKind::SemiColon, ";"),
+            Pattern::new(TokenKind::ColonColon, "::"),
             Pattern::new(TokenKind::Colon, ":"),
             Pattern::new(TokenKind::Question, "?"),
+            Pattern::new(TokenKind::Hash, "#"),
             Pattern::new(TokenKind::Comma, ","),
             Pattern::new(TokenKind::PlusPlus, "++"),
             Pattern::new(TokenKind::MinusMinus, "--"),
@@ -101,15 +106,21 @@ impl Lexer {
         for i in self.pos..self.pos + amount {
             if self.source[i as usize] == "\n" {
                 self.current_line += 1;
+                self.line_start = i + 1;
             }
         }
 
         self.pos += amount;
     }
 
-    pub fn push(&mut self, token: Token) {
-        if !self.black_list.contains(&token.kind) {
-            self.tokens.push(token);
+    pub fn push(&mut self, kind: TokenKind, value: String) {
+        if !self.black_list.contains(&kind) {
+            self.tokens.push(Token {
+                kind,
+                value,
+                line: self.token_start.line,
+                column: self.token_start.column,
+            });
         }
     }
     fn at(&self) -> String {
@@ -141,6 +152,10 @@ pub fn tokenize(source: String, black_list: Vec<TokenKind>) -> Vec<Token> {
     let reserved_symbols = reserved_symbols();
 
     while !lexer.eof() {
+        lexer.token_start = Span {
+            line: lexer.current_line,
+            column: lexer.pos - lexer.line_start,
+        };
         let mut matched = false;
         // Check if is number
         let at = &lexer.at();
@@ -171,11 +186,11 @@ pub fn tokenize(source: String, black_list: Vec<TokenKind>) -> Vec<Token> {
             );
         }
     }
-    lexer.push(Token {
-        kind: TokenKind::EndOfFile,
-        value: "Eof".to_string(),
+    lexer.token_start = Span {
         line: lexer.current_line,
-    });
+        column: lexer.pos - lexer.line_start,
+    };
+    lexer.push(TokenKind::EndOfFile, "Eof".to_string());
     return lexer.tokens;
 }
 
@@ -194,17 +209,9 @@ fn handle_comments(lexer: &mut Lexer) {
     // println!("Match {:?}", &value);
 
     lexer.advance(value.len() as u16);
-    lexer.push(Token {
-        kind: TokenKind::Comment,
-        value,
-        line: lexer.current_line,
-    });
-    lexer.advance(1 as u16);
-    lexer.push(Token {
-        kind: TokenKind::NextLine,
-        value: "\n".to_string(),
-        line: lexer.current_line,
-    });
+    lexer.push(TokenKind::Comment, value);
+    lexer.advance(1);
+    lexer.push(TokenKind::NextLine, "\n".to_string());
 }
 fn handle_strings(lexer: &mut Lexer) {
     let mut current_index = (lexer.pos + 1) as usize;
@@ -229,11 +236,7 @@ fn handle_strings(lexer: &mut Lexer) {
 
     println!("handle_strings {value}");
     lexer.advance(value.len() as u16);
-    lexer.push(Token {
-        kind: TokenKind::String,
-        value,
-        line: lexer.current_line,
-    });
+    lexer.push(TokenKind::String, value);
 }
 fn handle_symbols(lexer: &mut Lexer, reserved_symbols: &HashMap<String, TokenKind>) {
     let mut value = String::new();
@@ -252,20 +255,19 @@ fn handle_symbols(lexer: &mut Lexer, reserved_symbols: &HashMap<String, TokenKin
     }
     lexer.advance(value.len() as u16);
 
+    // `name!` followed by anything other than `=` is a macro invocation like `vec![1, 2]`
+    if !lexer.eof() && lexer.at() == "!" && lexer.next_ncharacters(2) != "!=" {
+        lexer.advance(1);
+        lexer.push(TokenKind::Macro, value);
+        return;
+    }
+
     let kind = reserved_symbols.get(&value);
     match kind {
         // The division was valid
-        Some(x) => lexer.push(Token {
-            kind: x.clone(),
-            value,
-            line: lexer.current_line,
-        }),
+        Some(x) => lexer.push(*x, value),
         // The division was invalid
-        None => lexer.push(Token {
-            kind: TokenKind::Identifier,
-            value,
-            line: lexer.current_line,
-        }),
+        None => lexer.push(TokenKind::Identifier, value),
     }
 }
 fn handle_standard_pattern_tokenization(
@@ -322,6 +324,8 @@ pub fn reserved_symbols() -> HashMap<String, TokenKind> {
         ("for".to_string(), TokenKind::For),
         ("while".to_string(), TokenKind::While),
         ("return".to_string(), TokenKind::Return),
+        ("break".to_string(), TokenKind::Break),
+        ("continue".to_string(), TokenKind::Continue),
     ]);
 }
 
@@ -331,20 +335,19 @@ const SYMBOLS: [&str; 55] = [
     "M", "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z", "_", "\\", "'",
 ];
 
-fn is_symbol(char: &String, beginning: bool) -> bool {
-    return SYMBOLS.contains(&(char.as_str()))
-        || (!beginning && NUMBERS.contains(&(char.as_str())));
+fn is_symbol(char: &str, beginning: bool) -> bool {
+    return SYMBOLS.contains(&char) || (!beginning && NUMBERS.contains(&char));
 }
 
 fn handle_number_tokenization(lexer: &mut Lexer) {
     let mut value = String::new();
     let mut current_index = lexer.pos as usize;
-    loop {
-        let char = &lexer.source[current_index];
+    // a number can end the source, like the value of an interpolated string
+    while let Some(char) = lexer.source.get(current_index) {
         current_index += 1;
 
         if (!is_number(char) && char != ".") ||/* makes iterators inside numbers work like: 0..100*/
-        (char == "." &&  /* next char because the index is increased just before */&lexer.source[current_index ] == ".")
+        (char == "." &&  /* next char because the index is increased just before */lexer.source.get(current_index).is_some_and(|next| next == "."))
         {
             break;
         }
@@ -352,14 +355,9 @@ fn handle_number_tokenization(lexer: &mut Lexer) {
     }
     // println!("Match {:?}", &value);
     lexer.advance(value.len() as u16);
-    lexer.push(Token {
-        kind: TokenKind::Number,
-        value,
-        line: lexer.current_line,
-    });
+    lexer.push(TokenKind::Number, value);
 }
 const NUMBERS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
-fn is_number(char: &String) -> bool {
-    let char_str = char.as_str();
-    return NUMBERS.contains(&(char_str));
+fn is_number(char: &str) -> bool {
+    return NUMBERS.contains(&char);
 }
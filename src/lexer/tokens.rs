@@ -1,3 +1,5 @@
+use std::fmt;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TokenKind {
     Tab,
@@ -8,7 +10,6 @@ pub enum TokenKind {
     CloseCurly,
     CloseParen,
     OpenParen,
-    Inst,
 
     DotDot,
     Dot,
@@ -24,9 +25,12 @@ pub enum TokenKind {
     Greater,
     Or,
     And,
+    Ampersand,
     SemiColon,
+    ColonColon,
     Colon,
     Question,
+    Hash,
     Comma,
     PlusEquals,
     MinusEquals,
@@ -47,6 +51,8 @@ pub enum TokenKind {
     Comment,
     String,
     Identifier,
+    /// name of a macro invocation like `vec!`, the value doesn't contain the `!`
+    Macro,
 
     Const,
     Fn,
@@ -64,6 +70,8 @@ pub enum TokenKind {
     While,
     Out,
     Let,
+    Break,
+    Continue,
 
     Mut,
 }
@@ -73,9 +81,28 @@ pub struct Token {
     pub kind: TokenKind,
     pub value: String,
     pub line: u16,
+    pub column: u16,
 }
 impl Token {
+    pub fn span(&self) -> Span {
+        Span {
+            line: self.line,
+            column: self.column,
+        }
+    }
     pub fn debug(&self, index: u32) {
         println!("{}.    {:?} {}", index, self.kind, self.value);
     }
 }
+
+/// Position of the first character of a token, both line and column start at 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Span {
+    pub line: u16,
+    pub column: u16,
+}
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line: {} column: {}", self.line + 1, self.column + 1)
+    }
+}
@@ -1,35 +1,98 @@
-use std::fs;
+// explicit `return` is the style used across the whole transpiler
+#![allow(clippy::needless_return)]
 
+use std::{fs, path::Path};
+
+use config::Config;
 use tokens::TokenKind;
+use writer::Indent;
+
+mod config;
 
+#[path = "analysis/control_flow.rs"]
+mod control_flow;
+#[path = "analysis/diagnostic.rs"]
+mod diagnostic;
 #[path = "lexer/lexer.rs"]
 mod lexer;
+#[path = "analysis/lints.rs"]
+mod lints;
+#[path = "analysis/mutability.rs"]
+mod mutability;
+#[path = "analysis/naming.rs"]
+mod naming;
+#[path = "analysis/rename.rs"]
+mod rename;
+#[path = "analysis/resolver.rs"]
+mod resolver;
+#[path = "analysis/shadowing.rs"]
+mod shadowing;
+#[path = "analysis/type_checker.rs"]
+mod type_checker;
 
 #[path = "parser/parser.rs"]
 mod parser;
 
-#[path = "codeGen/code_gen.rs"]
-mod code_gen;
+#[path = "ir/const_fold.rs"]
+mod const_fold;
+#[path = "ir/ir.rs"]
+mod ir;
+#[path = "ir/lower.rs"]
+mod lower;
+
+#[path = "codeGen/backend.rs"]
+mod backend;
+#[path = "codeGen/c.rs"]
+mod c;
+#[path = "codeGen/collections.rs"]
+mod collections;
+#[path = "codeGen/csharp.rs"]
+mod csharp;
 #[path = "parser/expression.rs"]
 mod expression;
+#[path = "codeGen/gdscript.rs"]
+mod gdscript;
 #[path = "parser/lookup.rs"]
 mod lookup;
+#[path = "codeGen/lua.rs"]
+mod lua;
+#[path = "codeGen/printer.rs"]
+mod printer;
+#[path = "codeGen/python.rs"]
+mod python;
+#[path = "codeGen/reserved.rs"]
+mod reserved;
 #[path = "parser/type_lookup.rs"]
 mod type_lookup;
+#[path = "codeGen/type_map.rs"]
+mod type_map;
 #[path = "parser/types.rs"]
 mod types;
+#[path = "codeGen/typescript.rs"]
+mod typescript;
+#[path = "codeGen/wat.rs"]
+mod wat;
+#[path = "codeGen/wat_check.rs"]
+mod wat_check;
+#[path = "codeGen/writer.rs"]
+mod writer;
 // #[path = "parser/statement.rs"]
 // mod statement;
 #[path = "lexer/tokens.rs"]
 mod tokens;
-const INPUT_FILE_PATH: &str = "./CompileTargets/tic_tac_toe.rs";
-const OUTPUT_FILE_PATH: &str = "./CompileTargets/Output.cs";
+#[path = "parser/visitor.rs"]
+mod visitor;
 
 fn main() {
-    let content = fs::read_to_string(INPUT_FILE_PATH).expect("Couldn't find input file!");
+    let config = Config::from_args();
+    let content = fs::read_to_string(&config.input_path).expect("Couldn't find input file!");
     println!("content:{:?} ------------ \n", content);
 
     println!("tokens:  ------------ \n");
+    let comments = match config.format {
+        true => printer::source_comments(&content),
+        false => Vec::new(),
+    };
     let tokens = lexer::tokenize(
         content,
         vec![
@@ -39,21 +102,69 @@ fn main() {
             TokenKind::NextLine,
         ],
     );
-    let mut i: u32 = 0;
-    for token in &tokens {
-        token.debug(i);
-        i += 1;
+    for (i, token) in tokens.iter().enumerate() {
+        token.debug(i as u32);
     }
 
     println!("ast:  ------------ \n");
 
-    let expressions = parser::parse(tokens);
+    let expressions = parser::parse(tokens, config.dialect);
+    if config.format {
+        let source = printer::print_source(
+            &expressions,
+            comments,
+            config.dialect,
+            config.code_style(Indent::Spaces(4)),
+        );
+        printer::check_round_trip(&expressions, &source, config.dialect);
+        fs::write(&config.output_path, &source).expect("Couldn't find output file!");
+        println!("Output:  ------------ \n");
+        println!("{}", source);
+        return;
+    }
+    let mut expressions = control_flow::implicit_returns(expressions);
     println!("{:?}", expressions);
 
-    println!("Output:  ------------ \n");
+    let mut resolution = resolver::resolve(&expressions, &config.externals);
+    let mut diagnostics = resolution.diagnostics.clone();
+    let mutability = mutability::check_mutability(&expressions, &resolution);
+    diagnostics.extend(mutability.diagnostics);
+    let typing = type_checker::check_types(&expressions, &resolution, &config.type_map);
+    diagnostics.extend(typing.diagnostics);
+    diagnostics.extend(control_flow::check_control_flow(&expressions));
+    diagnostics.extend(lints::check_lints(
+        &expressions,
+        &resolution,
+        &mutability.mutated,
+        &config.lint_levels,
+    ));
+    if config.convert_names {
+        diagnostics.extend(naming::convert_names(&mut expressions, &mut resolution));
+    }
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span);
+    if diagnostic::report(&diagnostics) {
+        std::process::exit(1);
+    }
+
+    shadowing::rename_shadowed(&mut expressions, &resolution);
+
+    let mut program = lower::lower(expressions, &resolution, &typing.inferred, &config.type_map);
+    if config.fold {
+        program = const_fold::fold_constants(program);
+    }
 
-    let output_code = code_gen::convert_expressions_to_code(expressions);
-    fs::write(OUTPUT_FILE_PATH, &output_code).expect("Couldn't find output file!");
+    println!("Output:  ------------ \n");
 
-    println!("{}", output_code);
+    let mut backend = backend::create_backend(&config);
+    let files = backend.generate(&program);
+    let output_path = Path::new(&config.output_path);
+    for (i, file) in files.iter().enumerate() {
+        // the first file is the output, the others are written next to it
+        let path = match i {
+            0 => output_path.to_path_buf(),
+            _ => output_path.with_extension(file.extension),
+        };
+        fs::write(&path, &file.content).expect("Couldn't find output file!");
+        println!("{}", file.content);
+    }
 }
@@ -1,22 +1,22 @@
-use core::panic;
-use std::{
-    default,
-    env::{consts, VarError},
-    thread::current,
-};
-
 use crate::{
-    lexer,
-    parser::{self, Parser},
-    tokens::{Token, TokenKind},
-    types::{parse_symbol_type, parse_type, Type},
+    lexer::tokenize,
+    parser::{Dialect, Parser},
+    tokens::{Span, Token, TokenKind},
+    types::{parse_type, Type},
 };
 
 #[derive(Debug, Clone)]
 pub enum Expression {
-    Number(f32),
+    Number(i128),
+    // a number written with a `.`, `2.0` stays a float
+    Float(f32),
     String(String),
-    Identifier(String),
+    // $"text {value}", the values are parsed like any other expression
+    Interpolated(Vec<InterpolationPart>),
+    Identifier {
+        name: String,
+        span: Span,
+    },
     Prefix {
         prefix: Token,
         value: Box<Expression>,
@@ -28,25 +28,37 @@ pub enum Expression {
         operator: Token,
         value: Box<Expression>,
     },
-    // type name mutable
+    // type name mutable, without a type annotation the type is inferred
     VariableDeclaration {
-        var_type: Type,
+        var_type: Option<Type>,
         name: String,
         mutable: bool,
+        span: Span,
+    },
+    // let (a, mut b) = pair; every binding is a VariableDeclaration without a type
+    TupleDeclaration {
+        var_type: Option<Type>,
+        bindings: Vec<Expression>,
     },
     Grouping(Box<Expression>),
+    Tuple(Vec<Expression>),
+    // &value, &mut value
+    Borrow {
+        value: Box<Expression>,
+        mutable: bool,
+        span: Span,
+    },
     Class {
         public: bool,
         name: String,
+        span: Span,
         properties: Vec<Expression>,
         functions: Vec<Expression>,
     },
     ClassProperty {
         var_name: String,
         var_type: Type,
-    },
-    ClassFunction {
-        name: String, /* ,type : Type */
+        span: Span,
     },
 
     Binary {
@@ -56,6 +68,7 @@ pub enum Expression {
     },
     ClassInstantiation {
         name: String,
+        span: Span,
         properties: Vec<Expression>,
     },
     ArrayInitialization {
@@ -63,6 +76,7 @@ pub enum Expression {
     },
     Function {
         name: String,
+        span: Span,
         properties: Vec<Expression>,
         public: bool,
         output: Option<Type>,
@@ -71,21 +85,39 @@ pub enum Expression {
     FunctionProperty {
         var_name: String,
         var_type: Type,
+        mutable: bool,
+        span: Span,
     },
+    // the span points at the name after the dot
     MemberExpr {
         member: Box<Expression>,
         name: String,
+        span: Span,
+    },
+    // Vec::new
+    StaticMember {
+        member: Box<Expression>,
+        name: String,
+    },
+    // vec![1, 2, 3]
+    MacroCall {
+        name: String,
+        values: Vec<Expression>,
     },
+    // spans of control flow point at their keyword
     Return {
         value: Box<Expression>,
+        span: Span,
     },
     If {
         condition: Box<Expression>,
         inside: Vec<Expression>,
+        span: Span,
     },
     Else {
         condition: Option<Box<Expression>>,
         inside: Vec<Expression>,
+        span: Span,
     },
     IndexArray {
         left: Box<Expression>,
@@ -95,9 +127,11 @@ pub enum Expression {
     While {
         condition: Box<Expression>,
         inside: Vec<Expression>,
+        span: Span,
     },
     For {
         iterator_name: String,
+        iterator_span: Span,
         iteration_target: Box<Expression>,
         inside: Vec<Expression>,
     },
@@ -112,7 +146,81 @@ pub enum Expression {
     Out {
         var_type: Option<Type>,
         var_name: String,
+        span: Span,
     },
+    // #[allow(unused_variable)] applies to the next statement, #![...] to the whole block
+    Attribute {
+        name: String,
+        arguments: Vec<String>,
+        inner: bool,
+        span: Span,
+    },
+}
+/// Piece of an interpolated string
+#[derive(Debug, Clone)]
+pub enum InterpolationPart {
+    /// text between the values, escapes like `\n` are kept as they are written
+    Text(String),
+    Value(Expression),
+}
+/// the values between the braces of an interpolated string
+pub fn interpolated_values(parts: &[InterpolationPart]) -> impl Iterator<Item = &Expression> {
+    return parts.iter().filter_map(|part| match part {
+        InterpolationPart::Value(value) => Some(value),
+        InterpolationPart::Text(_) => None,
+    });
+}
+
+impl Expression {
+    /// Span of the first token inside of the expression that has one, numbers and strings don't
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Expression::Identifier { span, .. }
+            | Expression::VariableDeclaration { span, .. }
+            | Expression::Borrow { span, .. }
+            | Expression::Class { span, .. }
+            | Expression::ClassProperty { span, .. }
+            | Expression::ClassInstantiation { span, .. }
+            | Expression::Function { span, .. }
+            | Expression::FunctionProperty { span, .. }
+            | Expression::Return { span, .. }
+            | Expression::If { span, .. }
+            | Expression::Else { span, .. }
+            | Expression::While { span, .. }
+            | Expression::For {
+                iterator_span: span,
+                ..
+            }
+            | Expression::Out { span, .. }
+            | Expression::Attribute { span, .. } => Some(*span),
+            Expression::Prefix { prefix, .. } => Some(prefix.span()),
+            Expression::Assignment {
+                target, operator, ..
+            } => target.span().or(Some(operator.span())),
+            Expression::Binary { l, operator, .. } => l.span().or(Some(operator.span())),
+            Expression::MemberExpr { member, span, .. } => member.span().or(Some(*span)),
+            Expression::Grouping(inside) => inside.span(),
+            Expression::StaticMember { member: left, .. }
+            | Expression::Range { from: left, .. }
+            | Expression::IndexArray { left, .. }
+            | Expression::FunctionCall { left, .. } => left.span(),
+            Expression::TupleDeclaration {
+                bindings: values, ..
+            }
+            | Expression::Tuple(values)
+            | Expression::MacroCall { values, .. }
+            | Expression::ArrayInitialization { properties: values } => {
+                values.iter().find_map(|value| value.span())
+            }
+            Expression::Interpolated(parts) => {
+                interpolated_values(parts).find_map(|value| value.span())
+            }
+            Expression::Number(_)
+            | Expression::Float(_)
+            | Expression::String(_)
+            | Expression::Keyword(_) => None,
+        }
+    }
 }
 pub fn parse_indexing_array(parser: &mut Parser, _: &i8, left: Expression) -> Expression {
     parser.expect(&TokenKind::OpenBracket);
@@ -132,13 +240,13 @@ pub fn parse_indexing_array(parser: &mut Parser, _: &i8, left: Expression) -> Ex
 }
 
 pub fn parse_else(parser: &mut Parser) -> Expression {
-    parser.expect(&TokenKind::Else);
+    let span = parser.expect(&TokenKind::Else).span();
 
     match parser.current_token_kind() {
         TokenKind::If => {
             parser.expect(&TokenKind::If);
 
-            let condition = parse_expr(parser, &0);
+            let condition = parse_condition(parser);
             parser.expect(&TokenKind::OpenCurly);
             let mut inside = Vec::new();
             while parser.current_token_kind() != &TokenKind::CloseCurly {
@@ -149,6 +257,7 @@ pub fn parse_else(parser: &mut Parser) -> Expression {
             return Expression::Else {
                 condition: Some(Box::new(condition)),
                 inside,
+                span,
             };
         }
         _ => {
@@ -162,25 +271,33 @@ pub fn parse_else(parser: &mut Parser) -> Expression {
             return Expression::Else {
                 condition: None,
                 inside,
+                span,
             };
         }
     }
 }
 
 pub fn parse_out(parser: &mut Parser) -> Expression {
-    // out i32 name;
+    // out i32 name declares the variable, out name passes an existing one
 
     // move past out
     parser.advance();
-    let var_type = if parser.current_token_kind() == &TokenKind::Identifier {
-        let output = Some(parse_type(parser, &0));
-        output
+    let untyped = matches!(
+        parser.get_token(parser.index + 1).kind,
+        TokenKind::CloseParen | TokenKind::Comma
+    );
+    let var_type = if parser.current_token_kind() == &TokenKind::Identifier && !untyped {
+        Some(parse_type(parser, &0))
     } else {
         None
     };
 
-    let var_name = (&parser.expect(&TokenKind::Identifier).value).to_owned();
-    return Expression::Out { var_type, var_name };
+    let name = parser.expect(&TokenKind::Identifier);
+    return Expression::Out {
+        var_type,
+        var_name: name.value.to_owned(),
+        span: name.span(),
+    };
 }
 pub fn parse_function_call(parser: &mut Parser, _: &i8, left: Expression) -> Expression {
     parser.expect(&TokenKind::OpenParen);
@@ -199,18 +316,20 @@ pub fn parse_function_call(parser: &mut Parser, _: &i8, left: Expression) -> Exp
     };
 }
 pub fn parse_return(parser: &mut Parser) -> Expression {
-    parser.expect(&TokenKind::Return);
+    let span = parser.expect(&TokenKind::Return).span();
     let value = parse_expr(parser, &0);
 
     Expression::Return {
         value: Box::new(value),
+        span,
     }
 }
 pub fn parse_for(parser: &mut Parser) -> Expression {
     parser.expect(&TokenKind::For);
-    let iterator_name = parser.expect(&TokenKind::Identifier).value.to_owned();
+    let iterator = parser.expect(&TokenKind::Identifier);
+    let (iterator_name, iterator_span) = (iterator.value.to_owned(), iterator.span());
     parser.expect(&TokenKind::In);
-    let iteration_target = parse_expr(parser, &0);
+    let iteration_target = parse_condition(parser);
 
     parser.expect(&TokenKind::OpenCurly);
     let mut inside = Vec::new();
@@ -220,13 +339,14 @@ pub fn parse_for(parser: &mut Parser) -> Expression {
     parser.expect(&TokenKind::CloseCurly);
     return Expression::For {
         iterator_name,
+        iterator_span,
         iteration_target: Box::new(iteration_target),
         inside,
     };
 }
 pub fn parse_if(parser: &mut Parser) -> Expression {
-    parser.expect(&TokenKind::If);
-    let condition = parse_expr(parser, &0);
+    let span = parser.expect(&TokenKind::If).span();
+    let condition = parse_condition(parser);
     parser.expect(&TokenKind::OpenCurly);
     let mut inside = Vec::new();
     while parser.current_token_kind() != &TokenKind::CloseCurly {
@@ -237,11 +357,12 @@ pub fn parse_if(parser: &mut Parser) -> Expression {
     return Expression::If {
         condition: Box::new(condition),
         inside,
+        span,
     };
 }
 pub fn parse_while(parser: &mut Parser) -> Expression {
-    parser.expect(&TokenKind::While);
-    let condition = parse_expr(parser, &0);
+    let span = parser.expect(&TokenKind::While).span();
+    let condition = parse_condition(parser);
     parser.expect(&TokenKind::OpenCurly);
     let mut inside = Vec::new();
     while parser.current_token_kind() != &TokenKind::CloseCurly {
@@ -252,8 +373,18 @@ pub fn parse_while(parser: &mut Parser) -> Expression {
     return Expression::While {
         condition: Box::new(condition),
         inside,
+        span,
     };
 }
+/// `if x == Test {}` would otherwise parse the body as a class instantiation
+fn parse_condition(parser: &mut Parser) -> Expression {
+    let allowed = parser.class_instantiation_allowed;
+    parser.class_instantiation_allowed = false;
+    let condition = parse_expr(parser, &0);
+    parser.class_instantiation_allowed = allowed;
+
+    return condition;
+}
 pub fn parse_range(parser: &mut Parser, _: &i8, left: Expression) -> Expression {
     parser.expect(&TokenKind::DotDot);
     let to = parse_expr(parser, &0);
@@ -264,25 +395,81 @@ pub fn parse_range(parser: &mut Parser, _: &i8, left: Expression) -> Expression
     };
 }
 pub fn parse_function(parser: &mut Parser) -> Expression {
+    // fn pub Name(u32 x) -> Test {}
     parser.expect(&TokenKind::Fn);
 
     let public = if parser.current_token_kind() == &TokenKind::Pub {
+        parser.expect_dialect(Dialect::Legacy, "`fn pub`");
         parser.expect(&TokenKind::Pub);
         true
     } else {
         false
     };
 
-    let name = parser.expect(&TokenKind::Identifier).value.to_owned();
+    return parse_function_signature(parser, public);
+}
+pub fn parse_public(parser: &mut Parser) -> Expression {
+    // pub fn name(x: u32) -> Test {}
+    parser.expect(&TokenKind::Pub);
+    match parser.current_token_kind() {
+        TokenKind::Fn => {
+            parser.expect_dialect(Dialect::Rust, "`pub fn`");
+            parser.expect(&TokenKind::Fn);
+            return parse_function_signature(parser, true);
+        }
+        TokenKind::Class => {
+            parser.expect(&TokenKind::Class);
+            return parse_class_body(parser, true);
+        }
+        default => panic!(
+            "expected fn or class after pub but found: {:?} on line: {}",
+            default,
+            parser.current_token().line + 1
+        ),
+    }
+}
+fn parse_function_property(parser: &mut Parser) -> Expression {
+    // `x: u32` and `mut x: u32` are rust style, `u32 x` is legacy
+    let rust_style = parser.current_token_kind() == &TokenKind::Mut
+        || parser.get_token(parser.index + 1).kind == TokenKind::Colon;
+
+    if !rust_style {
+        parser.expect_dialect(Dialect::Legacy, "`Type name` property");
+        let var_type = parse_type(parser, &0);
+        let name = parser.expect(&TokenKind::Identifier);
+        return Expression::FunctionProperty {
+            var_type,
+            var_name: name.value.to_owned(),
+            // there is no way to mark legacy properties as mutable
+            mutable: true,
+            span: name.span(),
+        };
+    }
+
+    parser.expect_dialect(Dialect::Rust, "`name: Type` property");
+    let mutable = parser.current_token_kind() == &TokenKind::Mut;
+    if mutable {
+        parser.advance();
+    }
+    let name = parser.expect(&TokenKind::Identifier);
+    let (var_name, span) = (name.value.to_owned(), name.span());
+    parser.expect(&TokenKind::Colon);
+
+    return Expression::FunctionProperty {
+        var_type: parse_type(parser, &0),
+        var_name,
+        mutable,
+        span,
+    };
+}
+fn parse_function_signature(parser: &mut Parser, public: bool) -> Expression {
+    let name = parser.expect(&TokenKind::Identifier);
+    let (name, span) = (name.value.to_owned(), name.span());
     parser.expect(&TokenKind::OpenParen);
 
     let mut properties = Vec::new();
     while parser.current_token_kind() != &TokenKind::CloseParen {
-        properties.push(Expression::FunctionProperty {
-            var_type: parse_type(parser, &0),
-
-            var_name: parser.expect(&TokenKind::Identifier).value.to_owned(),
-        });
+        properties.push(parse_function_property(parser));
         if parser.current_token_kind() == &TokenKind::Comma {
             parser.advance();
         }
@@ -306,6 +493,7 @@ pub fn parse_function(parser: &mut Parser) -> Expression {
 
     return Expression::Function {
         name,
+        span,
         properties,
         public,
         output,
@@ -327,7 +515,7 @@ pub fn parse_expr(parser: &mut Parser, bp: &i8) -> Expression {
     debug_expression(&to_debug);
 
     while parser.current_bp() > bp {
-        let led = parser.current_token().kind.clone();
+        let led = parser.current_token().kind;
         let led_fn = parser.lookup.get_led(led);
 
         debug_expression(&format!("expr led call: led:{:?} ->>:", led));
@@ -353,12 +541,16 @@ pub fn parse_class(parser: &mut Parser) -> Expression {
     if public {
         parser.expect(&TokenKind::Pub);
     }
-    let name = parser.expect(&TokenKind::Identifier).value.to_owned();
-    debug_expression(&format!("parse class",));
+    return parse_class_body(parser, public);
+}
+fn parse_class_body(parser: &mut Parser, public: bool) -> Expression {
+    let name = parser.expect(&TokenKind::Identifier);
+    let (name, span) = (name.value.to_owned(), name.span());
+    debug_expression("parse class");
     parser.expect(&TokenKind::OpenCurly);
 
     let mut properties = Vec::new();
-    let mut functions = Vec::new();
+    let functions = Vec::new();
     while parser.current_token_kind() != &TokenKind::EndOfFile
         && parser.current_token_kind() != &TokenKind::CloseCurly
     {
@@ -369,11 +561,13 @@ pub fn parse_class(parser: &mut Parser) -> Expression {
         // Property
         if parser.current_token_kind() == &TokenKind::Identifier {
             let property_type = parse_type(parser, &0);
-            let property_name = parser.expect(&TokenKind::Identifier).value.clone();
+            let property_name = parser.expect(&TokenKind::Identifier);
+            let (property_name, span) = (property_name.value.clone(), property_name.span());
             parser.expect(&TokenKind::SemiColon);
             properties.push(Expression::ClassProperty {
                 var_name: property_name,
                 var_type: property_type,
+                span,
             });
             continue;
         }
@@ -384,6 +578,7 @@ pub fn parse_class(parser: &mut Parser) -> Expression {
     Expression::Class {
         public,
         name,
+        span,
         functions,
         properties,
     }
@@ -401,9 +596,9 @@ pub fn parse_array_initialization(parser: &mut Parser) -> Expression {
 
     Expression::ArrayInitialization { properties }
 }
-pub fn parse_class_instantiation(parser: &mut Parser, bp: &i8, left: Expression) -> Expression {
-    let name = match left {
-        Expression::Identifier(text) => text,
+pub fn parse_class_instantiation(parser: &mut Parser, _: &i8, left: Expression) -> Expression {
+    let (name, span) = match left {
+        Expression::Identifier { name, span } => (name, span),
         _ => {
             panic!("left is not a identifier in parse_class_instantiation ")
         }
@@ -420,11 +615,15 @@ pub fn parse_class_instantiation(parser: &mut Parser, bp: &i8, left: Expression)
     parser.expect(&TokenKind::CloseCurly);
     Expression::ClassInstantiation {
         name,
-        properties: properties,
+        span,
+        properties,
     }
 }
 pub fn parse_variable_declaration(parser: &mut Parser) -> Expression {
     // let mut i32 name = 1+2;
+    // let mut name: i32 = 1+2;
+    // let name = 1+2;
+    // let (a, b): (i32, i32) = pair;
 
     // move past let
     parser.advance();
@@ -432,9 +631,26 @@ pub fn parse_variable_declaration(parser: &mut Parser) -> Expression {
     if mutable {
         parser.advance();
     }
-    let var_type = parse_type(parser, &0);
+    if parser.current_token_kind() == &TokenKind::OpenParen {
+        return parse_tuple_declaration(parser);
+    }
 
-    let name = (&parser.expect(&TokenKind::Identifier).value).to_owned();
+    // `name:`, `name =` and `name;` are rust style, anything else starts with the type
+    let rust_style = matches!(
+        parser.get_token(parser.index + 1).kind,
+        TokenKind::Colon | TokenKind::Assignment | TokenKind::SemiColon
+    );
+    let (var_type, name, span) = if rust_style {
+        parser.expect_dialect(Dialect::Rust, "`let name: Type`");
+        let name = parser.expect(&TokenKind::Identifier);
+        let (name, span) = (name.value.to_owned(), name.span());
+        (parse_type_annotation(parser), name, span)
+    } else {
+        parser.expect_dialect(Dialect::Legacy, "`let Type name`");
+        let var_type = parse_type(parser, &0);
+        let name = parser.expect(&TokenKind::Identifier);
+        (Some(var_type), name.value.to_owned(), name.span())
+    };
 
     debug_expression(&format!(
         "variable_declaration_expression: type{:?} mut:{} name:{} next_token_kind:{:?}",
@@ -447,8 +663,45 @@ pub fn parse_variable_declaration(parser: &mut Parser) -> Expression {
         var_type,
         name,
         mutable,
+        span,
     };
 }
+fn parse_tuple_declaration(parser: &mut Parser) -> Expression {
+    parser.expect(&TokenKind::OpenParen);
+    let mut bindings = Vec::new();
+    while parser.current_token_kind() != &TokenKind::CloseParen {
+        let mutable = parser.current_token_kind() == &TokenKind::Mut;
+        if mutable {
+            parser.advance();
+        }
+        let name = parser.expect(&TokenKind::Identifier);
+        bindings.push(Expression::VariableDeclaration {
+            var_type: None,
+            name: name.value.to_owned(),
+            mutable,
+            span: name.span(),
+        });
+        if parser.current_token_kind() == &TokenKind::Comma {
+            parser.advance();
+        }
+    }
+    parser.expect(&TokenKind::CloseParen);
+    let var_type = parse_type_annotation(parser);
+
+    debug_expression(&format!(
+        "tuple_declaration_expression: type{:?} bindings:{:?}",
+        var_type, bindings,
+    ));
+    return Expression::TupleDeclaration { var_type, bindings };
+}
+/// `: Type`, returns None when there is no annotation
+fn parse_type_annotation(parser: &mut Parser) -> Option<Type> {
+    if parser.current_token_kind() != &TokenKind::Colon {
+        return None;
+    }
+    parser.expect(&TokenKind::Colon);
+    return Some(parse_type(parser, &0));
+}
 pub fn parse_assignment(parser: &mut Parser, _: &i8, target: Expression) -> Expression {
     debug_expression(&format!(
         "assignment_expr: target: {:?} current kind: {:?} ",
@@ -476,7 +729,7 @@ pub fn parse_binary_expr(parser: &mut Parser, bp: &i8, left: Expression) -> Expr
         parser.current_token_kind()
     ));
 
-    let right = parse_expr(parser, &bp);
+    let right = parse_expr(parser, bp);
 
     Expression::Binary {
         l: Box::new(left),
@@ -486,21 +739,80 @@ pub fn parse_binary_expr(parser: &mut Parser, bp: &i8, left: Expression) -> Expr
 }
 pub fn parse_member_expr(parser: &mut Parser, _: &i8, left: Expression) -> Expression {
     parser.expect(&TokenKind::Dot);
-    let name = parser.expect(&TokenKind::Identifier).value.to_string();
+    let name = parser.expect(&TokenKind::Identifier);
+    let (name, span) = (name.value.to_string(), name.span());
 
     Expression::MemberExpr {
         member: Box::new(left),
         name,
+        span,
     }
 }
 
+pub fn parse_static_member_expr(parser: &mut Parser, _: &i8, left: Expression) -> Expression {
+    parser.expect(&TokenKind::ColonColon);
+    // `new` is a keyword but it is also the name of most constructors like Vec::new
+    let name = if parser.current_token_kind() == &TokenKind::New {
+        parser.advance().value.to_string()
+    } else {
+        parser.expect(&TokenKind::Identifier).value.to_string()
+    };
+
+    Expression::StaticMember {
+        member: Box::new(left),
+        name,
+    }
+}
+pub fn parse_macro_call(parser: &mut Parser) -> Expression {
+    // vec![1, 2, 3] or vec!(1, 2, 3)
+    let name = parser.expect(&TokenKind::Macro).value.to_owned();
+    let close = match parser.advance().kind {
+        TokenKind::OpenBracket => TokenKind::CloseBracket,
+        TokenKind::OpenParen => TokenKind::CloseParen,
+        default => panic!(
+            "expected [ or ( after macro: {}! but found: {:?}",
+            name, default
+        ),
+    };
+
+    let mut values = Vec::new();
+    while parser.current_token_kind() != &close {
+        values.push(parse_expr(parser, &0));
+        if parser.current_token_kind() == &TokenKind::Comma {
+            parser.advance();
+        }
+    }
+    parser.expect(&close);
+
+    Expression::MacroCall { name, values }
+}
+
 pub fn parse_grouping(parser: &mut Parser) -> Expression {
     parser.advance();
+    if parser.current_token_kind() == &TokenKind::CloseParen {
+        parser.advance();
+        return Expression::Tuple(Vec::new());
+    }
 
     let expression_inside = parse_expr(parser, &0);
+    // (a, b) is a tuple not a grouping
+    if parser.current_token_kind() == &TokenKind::Comma {
+        let mut values = vec![expression_inside];
+        while parser.current_token_kind() == &TokenKind::Comma {
+            parser.advance();
+            if parser.current_token_kind() == &TokenKind::CloseParen {
+                break;
+            }
+            values.push(parse_expr(parser, &0));
+        }
+        parser.expect(&TokenKind::CloseParen);
+
+        debug_expression("parsed tuple ");
+        return Expression::Tuple(values);
+    }
     parser.expect(&TokenKind::CloseParen);
 
-    debug_expression(&format!("parsed grouping ",));
+    debug_expression("parsed grouping ");
 
     Expression::Grouping(Box::new(expression_inside))
 }
@@ -509,7 +821,26 @@ pub fn parse_number_nod(parser: &mut Parser) -> Expression {
         "parse number nod {:?}",
         parser.current_token().value
     ));
-    Expression::Number(parser.advance().value.parse::<f32>().unwrap())
+    let value = &parser.advance().value;
+    if value.contains('.') {
+        return Expression::Float(value.parse::<f32>().unwrap());
+    }
+    Expression::Number(value.parse::<i128>().unwrap())
+}
+pub fn parse_borrow_nod(parser: &mut Parser) -> Expression {
+    let span = parser.expect(&TokenKind::Ampersand).span();
+    let mutable = parser.current_token_kind() == &TokenKind::Mut;
+    if mutable {
+        parser.advance();
+    }
+    // binds tighter than binary operators but not tighter than member access or calls
+    let value = Box::new(parse_expr(parser, &4));
+
+    Expression::Borrow {
+        value,
+        mutable,
+        span,
+    }
 }
 pub fn parse_prefix_nod(parser: &mut Parser) -> Expression {
     debug_expression(&format!(
@@ -528,7 +859,45 @@ pub fn parse_identifier_nod(parser: &mut Parser) -> Expression {
         "parse identifier nod {:?}",
         parser.current_token().value
     ));
-    Expression::Identifier(parser.advance().value.to_string())
+    let token = parser.advance();
+    Expression::Identifier {
+        name: token.value.to_string(),
+        span: token.span(),
+    }
+}
+pub fn parse_attribute(parser: &mut Parser) -> Expression {
+    // #[name(argument, argument)] or #![name(argument)]
+    let span = parser.expect(&TokenKind::Hash).span();
+    let inner = parser.current_token_kind() == &TokenKind::Not;
+    if inner {
+        parser.advance();
+    }
+    parser.expect(&TokenKind::OpenBracket);
+    let name = parser.expect(&TokenKind::Identifier).value.to_owned();
+
+    let mut arguments = Vec::new();
+    if parser.current_token_kind() == &TokenKind::OpenParen {
+        parser.advance();
+        while parser.current_token_kind() != &TokenKind::CloseParen {
+            arguments.push(parser.expect(&TokenKind::Identifier).value.to_owned());
+            if parser.current_token_kind() == &TokenKind::Comma {
+                parser.advance();
+            }
+        }
+        parser.expect(&TokenKind::CloseParen);
+    }
+    parser.expect(&TokenKind::CloseBracket);
+
+    debug_expression(&format!(
+        "parsed attribute: {} {:?} inner:{}",
+        name, arguments, inner
+    ));
+    return Expression::Attribute {
+        name,
+        arguments,
+        inner,
+        span,
+    };
 }
 pub fn parse_keyword_nod(parser: &mut Parser) -> Expression {
     debug_expression(&format!(
@@ -543,7 +912,93 @@ pub fn parse_string_nod(parser: &mut Parser) -> Expression {
         "parse string  nod {:?}",
         parser.current_token().value
     ));
-    Expression::String(parser.advance().value.to_string())
+    let token = parser.advance().clone();
+    if token.value.starts_with('$') {
+        return parse_interpolation(parser.dialect, &token);
+    }
+    Expression::String(token.value)
+}
+/// `$"[{x}] {{"` -> Text("["), Value(x), Text("] {")
+fn parse_interpolation(dialect: Dialect, token: &Token) -> Expression {
+    let inside: Vec<char> = token.value["$\"".len()..token.value.len() - 1]
+        .chars()
+        .collect();
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut i = 0;
+    while i < inside.len() {
+        let char = inside[i];
+        i += 1;
+        match char {
+            // `{{` and `}}` are escaped braces
+            '{' | '}' if inside.get(i) == Some(&char) => {
+                i += 1;
+                text.push(char);
+            }
+            '{' => {
+                if !text.is_empty() {
+                    parts.push(InterpolationPart::Text(std::mem::take(&mut text)));
+                }
+                let start = i;
+                let mut depth = 1;
+                while i < inside.len() {
+                    match inside[i] {
+                        '{' => depth += 1,
+                        '}' if depth == 1 => break,
+                        '}' => depth -= 1,
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                if i == inside.len() {
+                    panic!(
+                        "unclosed {{ in the interpolated string on line: {}",
+                        token.line + 1
+                    );
+                }
+                let code: String = inside[start..i].iter().collect();
+                i += 1;
+                // the value starts after `$"` and the text before it
+                let span = Span {
+                    line: token.line,
+                    column: token.column + 2 + start as u16,
+                };
+                parts.push(InterpolationPart::Value(parse_interpolated_value(
+                    dialect, &code, span,
+                )));
+            }
+            _ => text.push(char),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(InterpolationPart::Text(text));
+    }
+    return Expression::Interpolated(parts);
+}
+/// parses the code between the braces, its tokens get their position in the file
+fn parse_interpolated_value(dialect: Dialect, code: &str, span: Span) -> Expression {
+    if code.trim().is_empty() {
+        panic!("empty value in the interpolated string on {}", span);
+    }
+    let mut tokens = tokenize(
+        code.to_string(),
+        vec![
+            TokenKind::Tab,
+            TokenKind::WhiteSpace,
+            TokenKind::Comment,
+            TokenKind::NextLine,
+        ],
+    );
+    for token in &mut tokens {
+        if token.line == 0 {
+            token.column += span.column;
+        }
+        token.line += span.line;
+    }
+    let mut parser = Parser::new(tokens, dialect);
+    let value = parse_expr(&mut parser, &0);
+    parser.expect(&TokenKind::EndOfFile);
+    return value;
 }
 
 const SHOW_EXPRESSION_DEBUG: bool = true;
@@ -2,55 +2,49 @@ use std::collections::HashMap;
 
 use crate::{
     expression::{
-        self, parse_array_initialization, parse_assignment, parse_binary_expr, parse_class,
-        parse_class_instantiation, parse_else, parse_expr, parse_for, parse_function,
-        parse_function_call, parse_grouping, parse_identifier_nod, parse_if, parse_indexing_array,
-        parse_keyword_nod, parse_member_expr, parse_number_nod, parse_out, parse_prefix_nod,
-        parse_range, parse_return, parse_string_nod, parse_variable_declaration, parse_while,
+        parse_array_initialization, parse_assignment, parse_attribute, parse_binary_expr,
+        parse_borrow_nod, parse_class, parse_class_instantiation, parse_else, parse_for,
+        parse_function, parse_function_call, parse_grouping, parse_identifier_nod, parse_if,
+        parse_indexing_array, parse_keyword_nod, parse_macro_call, parse_member_expr,
+        parse_number_nod, parse_out, parse_prefix_nod, parse_public, parse_range, parse_return,
+        parse_static_member_expr, parse_string_nod, parse_variable_declaration, parse_while,
         Expression,
     },
-    parser::{self, Parser},
+    parser::Parser,
     tokens::TokenKind,
 };
+
+type LedFn = fn(&mut Parser, &i8, Expression) -> Expression;
+type NodFn = fn(&mut Parser) -> Expression;
+
 pub struct Lookup {
     pub binding_power_lu: HashMap<TokenKind, i8>,
 
-    pub led_lu: HashMap<TokenKind, fn(&mut Parser, &i8, Expression) -> Expression>,
-    pub nod_lu: HashMap<TokenKind, fn(&mut Parser) -> Expression>,
+    pub led_lu: HashMap<TokenKind, LedFn>,
+    pub nod_lu: HashMap<TokenKind, NodFn>,
 }
 impl Lookup {
-    pub fn get_led(
-        &self,
-        token_kind: TokenKind,
-    ) -> &fn(&mut Parser, &i8, Expression) -> Expression {
-        self.led_lu.get(&token_kind).expect(&format!(
-            "led was not found for token kind: {:?}",
-            token_kind
-        ))
+    pub fn get_led(&self, token_kind: TokenKind) -> &LedFn {
+        self.led_lu
+            .get(&token_kind)
+            .unwrap_or_else(|| panic!("led was not found for token kind: {:?}", token_kind))
     }
-    pub fn get_nod(&self, token_kind: TokenKind) -> &fn(&mut Parser) -> Expression {
-        self.nod_lu.get(&token_kind).expect(&format!(
-            "nod was not found for token kind: {:?}",
-            token_kind
-        ))
+    pub fn get_nod(&self, token_kind: TokenKind) -> &NodFn {
+        self.nod_lu
+            .get(&token_kind)
+            .unwrap_or_else(|| panic!("nod was not found for token kind: {:?}", token_kind))
     }
     pub fn get_bp(&self, token_kind: &TokenKind) -> &i8 {
-        self.binding_power_lu.get(token_kind).expect(&format!(
-            "bp was not found for token kind: {:?}",
-            token_kind
-        ))
+        self.binding_power_lu
+            .get(token_kind)
+            .unwrap_or_else(|| panic!("bp was not found for token kind: {:?}", token_kind))
     }
-    fn led(
-        &mut self,
-        token_kind: TokenKind,
-        bp: i8,
-        function: fn(&mut Parser, &i8, Expression) -> Expression,
-    ) {
+    fn led(&mut self, token_kind: TokenKind, bp: i8, function: LedFn) {
         self.led_lu.insert(token_kind, function);
         self.binding_power_lu.insert(token_kind, bp);
     }
 
-    fn nod(&mut self, token_kind: TokenKind, bp: i8, function: fn(&mut Parser) -> Expression) {
+    fn nod(&mut self, token_kind: TokenKind, bp: i8, function: NodFn) {
         self.nod_lu.insert(token_kind, function);
         if bp >= -1 {
             self.binding_power_lu.insert(token_kind, bp);
@@ -65,8 +59,6 @@ impl Lookup {
         };
 
         lookup.led(TokenKind::Assignment, 1, parse_assignment);
-        lookup.led(TokenKind::Equals, 1, parse_assignment);
-        lookup.led(TokenKind::NotEquals, 1, parse_assignment);
         lookup.led(TokenKind::PlusEquals, 1, parse_assignment);
         lookup.led(TokenKind::MinusEquals, 1, parse_assignment);
         // lookup.led(TokenKind::StarEquals, 1, parse_assignment);
@@ -82,24 +74,28 @@ impl Lookup {
         lookup.led(TokenKind::Greater, 4, parse_binary_expr);
         lookup.led(TokenKind::GreaterEquals, 4, parse_binary_expr);
         lookup.led(TokenKind::Equals, 4, parse_binary_expr);
+        lookup.led(TokenKind::NotEquals, 4, parse_binary_expr);
 
         lookup.led(TokenKind::Or, 1, parse_binary_expr);
         lookup.led(TokenKind::And, 1, parse_binary_expr);
 
-        lookup.led(TokenKind::Dot, 1, parse_member_expr);
+        // postfix operators bind tighter than any binary operator so `1 + v.len()` is `1 + (v.len())`
+        lookup.led(TokenKind::Dot, 5, parse_member_expr);
+        lookup.led(TokenKind::ColonColon, 5, parse_static_member_expr);
         lookup.led(TokenKind::DotDot, 1, parse_range);
 
-        lookup.led(TokenKind::OpenParen, 2, parse_function_call);
+        lookup.led(TokenKind::OpenParen, 5, parse_function_call);
         lookup.led(TokenKind::OpenBracket, 5, parse_indexing_array);
 
         lookup.binding_power_lu.insert(TokenKind::CloseBracket, -1);
         lookup.led(TokenKind::OpenCurly, 5, parse_class_instantiation);
         lookup.binding_power_lu.insert(TokenKind::CloseCurly, 0);
 
-        lookup.nod(TokenKind::OpenParen, 2, parse_grouping);
+        lookup.nod(TokenKind::OpenParen, -99, parse_grouping);
         lookup.nod(TokenKind::CloseParen, 0, parse_grouping);
 
         lookup.nod(TokenKind::Fn, 0, parse_function);
+        lookup.nod(TokenKind::Pub, 0, parse_public);
         lookup.nod(TokenKind::If, 0, parse_if);
         lookup.nod(TokenKind::Else, 0, parse_else);
         lookup.nod(TokenKind::While, 0, parse_while);
@@ -112,6 +108,8 @@ impl Lookup {
         lookup.nod(TokenKind::String, 0, parse_string_nod);
         lookup.nod(TokenKind::Identifier, 0, parse_identifier_nod);
         lookup.nod(TokenKind::Number, 0, parse_number_nod);
+        lookup.nod(TokenKind::Macro, 0, parse_macro_call);
+        lookup.nod(TokenKind::Hash, 0, parse_attribute);
 
         // -99 so I don't add new bp in lookup and override old one
         lookup.nod(TokenKind::Minus, -99, parse_prefix_nod);
@@ -119,10 +117,14 @@ impl Lookup {
         lookup.nod(TokenKind::Plus, -99, parse_prefix_nod);
 
         lookup.nod(TokenKind::Not, 0, parse_prefix_nod);
+        lookup.nod(TokenKind::Ampersand, 0, parse_borrow_nod);
 
-        lookup.nod(TokenKind::OpenCurly, 0, parse_array_initialization);
+        // -99 so the class instantiation bp stays
+        lookup.nod(TokenKind::OpenCurly, -99, parse_array_initialization);
 
         lookup.nod(TokenKind::Return, 0, parse_return);
+        lookup.nod(TokenKind::Break, 0, parse_keyword_nod);
+        lookup.nod(TokenKind::Continue, 0, parse_keyword_nod);
 
         lookup.nod(TokenKind::SemiColon, -1, parse_keyword_nod);
         lookup.nod(TokenKind::Comma, -1, parse_keyword_nod);
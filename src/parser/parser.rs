@@ -1,25 +1,41 @@
 use crate::{
-    expression::{self, parse_expr, Expression},
+    expression::{parse_expr, Expression},
     lookup::Lookup,
     tokens::{Token, TokenKind},
     type_lookup::TypeLookup,
 };
 
+/// Which order of declarations is accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// `let mut i32 x`, `fn pub Name(u32 x)`
+    Legacy,
+    /// `let mut x: i32`, `pub fn name(x: u32)`
+    Rust,
+    /// both of them
+    Mixed,
+}
+
 pub struct Parser {
     pub index: usize,
     pub tokens: Vec<Token>,
     pub lookup: Lookup,
     pub type_lookup: TypeLookup,
+    pub dialect: Dialect,
+    /// false while parsing conditions, where `{` starts the body
+    pub class_instantiation_allowed: bool,
 }
 
 const TAKEN_ARRAY_LENGTH_CHECK_SAFETY_CHECK: bool = false;
 impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Parser {
+    pub fn new(tokens: Vec<Token>, dialect: Dialect) -> Parser {
         Parser {
             index: 0,
             tokens,
             lookup: Lookup::new(),
             type_lookup: TypeLookup::new(),
+            dialect,
+            class_instantiation_allowed: true,
         }
     }
     pub fn get_token(&self, index: usize) -> &Token {
@@ -35,17 +51,42 @@ impl Parser {
     }
     pub fn advance(&mut self) -> &Token {
         self.index += 1;
-        &self.get_token(self.index - 1)
+        self.get_token(self.index - 1)
     }
     pub fn current_token(&self) -> &Token {
-        &self.get_token(self.index)
+        self.get_token(self.index)
     }
     pub fn current_token_kind(&self) -> &TokenKind {
         &self.get_token(self.index).kind
     }
     pub fn current_bp(&self) -> &i8 {
+        // only `Name {` instantiates a class, `{` after anything else starts a block or an array
+        if self.current_token_kind() == &TokenKind::OpenCurly
+            && (!self.class_instantiation_allowed
+                || self.get_token(self.index - 1).kind != TokenKind::Identifier)
+        {
+            return &0;
+        }
         self.lookup.get_bp(self.current_token_kind())
     }
+    pub fn current_type_bp(&self) -> &i8 {
+        self.type_lookup.get_bp(self.current_token_kind())
+    }
+
+    /// panics when `syntax` belongs to a different dialect than the one that is parsed
+    pub fn expect_dialect(&self, syntax: Dialect, description: &str) {
+        if self.dialect == Dialect::Mixed || self.dialect == syntax {
+            return;
+        }
+
+        panic!(
+            "{} is {:?} syntax and is not allowed in the {:?} dialect, on line: {}",
+            description,
+            syntax,
+            self.dialect,
+            self.current_token().line + 1
+        );
+    }
 
     pub fn expect(&mut self, expected: &TokenKind) -> &Token {
         let current = self.advance();
@@ -53,11 +94,16 @@ impl Parser {
             return current;
         }
 
-        panic!("Expected: {:?} but found: {:?} ", expected, current);
+        panic!(
+            "Expected: {:?} but found: {:?} on line: {}",
+            expected,
+            current,
+            current.line + 1
+        );
     }
 }
-pub fn parse(tokens: Vec<Token>) -> Vec<Expression> {
-    let mut parser = Parser::new(tokens);
+pub fn parse(tokens: Vec<Token>, dialect: Dialect) -> Vec<Expression> {
+    let mut parser = Parser::new(tokens, dialect);
 
     let mut parsed_lines: Vec<Expression> = Vec::new();
     while parser.current_token_kind() != &TokenKind::EndOfFile {
@@ -3,40 +3,42 @@ use std::collections::HashMap;
 use crate::{
     parser::Parser,
     tokens::TokenKind,
-    types::{parse_array_type, parse_symbol_type, Type},
+    types::{
+        parse_array_type, parse_generic_type, parse_reference_type, parse_symbol_type,
+        parse_tuple_type, Type,
+    },
 };
 
+type TypeLedFn = fn(&mut Parser, &i8, Type) -> Type;
+type TypeNodFn = fn(&mut Parser) -> Type;
+
 pub struct TypeLookup {
     pub binding_power_lu: HashMap<TokenKind, i8>,
 
-    pub led_lu: HashMap<TokenKind, fn(&mut Parser, &i8, Type) -> Type>,
-    pub nod_lu: HashMap<TokenKind, fn(&mut Parser) -> Type>,
+    pub led_lu: HashMap<TokenKind, TypeLedFn>,
+    pub nod_lu: HashMap<TokenKind, TypeNodFn>,
 }
 impl TypeLookup {
-    pub fn get_led(&self, token_kind: TokenKind) -> &fn(&mut Parser, &i8, Type) -> Type {
-        self.led_lu.get(&token_kind).expect(&format!(
-            "led was not found for token kind: {:?}",
-            token_kind
-        ))
+    pub fn get_led(&self, token_kind: TokenKind) -> &TypeLedFn {
+        self.led_lu
+            .get(&token_kind)
+            .unwrap_or_else(|| panic!("led was not found for token kind: {:?}", token_kind))
     }
-    pub fn get_nod(&self, token_kind: TokenKind) -> &fn(&mut Parser) -> Type {
-        self.nod_lu.get(&token_kind).expect(&format!(
-            "nod was not found for token kind: {:?}",
-            token_kind
-        ))
+    pub fn get_nod(&self, token_kind: TokenKind) -> &TypeNodFn {
+        self.nod_lu
+            .get(&token_kind)
+            .unwrap_or_else(|| panic!("nod was not found for token kind: {:?}", token_kind))
     }
+    /// Any token that isn't a part of type syntax ends the type, so it has bp of 0
     pub fn get_bp(&self, token_kind: &TokenKind) -> &i8 {
-        self.binding_power_lu.get(token_kind).expect(&format!(
-            "bp was not found for token kind: {:?}",
-            token_kind
-        ))
+        self.binding_power_lu.get(token_kind).unwrap_or(&0)
     }
-    fn led(&mut self, token_kind: TokenKind, bp: i8, function: fn(&mut Parser, &i8, Type) -> Type) {
+    fn led(&mut self, token_kind: TokenKind, bp: i8, function: TypeLedFn) {
         self.led_lu.insert(token_kind, function);
         self.binding_power_lu.insert(token_kind, bp);
     }
 
-    fn nod(&mut self, token_kind: TokenKind, bp: i8, function: fn(&mut Parser) -> Type) {
+    fn nod(&mut self, token_kind: TokenKind, bp: i8, function: TypeNodFn) {
         self.nod_lu.insert(token_kind, function);
         if bp >= -1 {
             self.binding_power_lu.insert(token_kind, bp);
@@ -51,7 +53,10 @@ impl TypeLookup {
         };
 
         lookup.led(TokenKind::OpenBracket, 5, parse_array_type);
+        lookup.led(TokenKind::Less, 5, parse_generic_type);
         lookup.nod(TokenKind::Identifier, 0, parse_symbol_type);
+        lookup.nod(TokenKind::OpenParen, 0, parse_tuple_type);
+        lookup.nod(TokenKind::Ampersand, 0, parse_reference_type);
 
         lookup
     }
@@ -1,14 +1,54 @@
+use std::fmt;
+
 use crate::{
-    parser::{self, Parser},
+    parser::{Dialect, Parser},
     tokens::TokenKind,
 };
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Type {
     Symbol(String),
     Array {
         left_type: Box<Type>,
         dimensions: usize,
     },
+    // Vec<i32>, HashMap<str, bool>
+    Generic {
+        name: String,
+        arguments: Vec<Type>,
+    },
+    // (i32, str)
+    Tuple(Vec<Type>),
+    // &str, &mut i32
+    Reference {
+        inner: Box<Type>,
+        mutable: bool,
+    },
+}
+/// Writes the type the way it is written in the source
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Symbol(symbol) => write!(f, "{}", symbol),
+            Type::Array {
+                left_type,
+                dimensions,
+            } => write!(f, "{}[{}]", left_type, ",".repeat(*dimensions)),
+            Type::Generic { name, arguments } => {
+                write!(f, "{}<{}>", name, join_types(arguments))
+            }
+            Type::Tuple(types) => write!(f, "({})", join_types(types)),
+            Type::Reference { inner, mutable } => {
+                write!(f, "&{}{}", if *mutable { "mut " } else { "" }, inner)
+            }
+        }
+    }
+}
+fn join_types(types: &[Type]) -> String {
+    return types
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
 }
 
 pub fn parse_symbol_type(parser: &mut Parser) -> Type {
@@ -16,7 +56,37 @@ pub fn parse_symbol_type(parser: &mut Parser) -> Type {
     return Type::Symbol(parser.expect(&TokenKind::Identifier).value.to_owned());
 }
 
-pub fn parse_array_type(parser: &mut Parser, bp: &i8, left: Type) -> Type {
+pub fn parse_tuple_type(parser: &mut Parser) -> Type {
+    debug_type("parse_tuple_type");
+    parser.expect(&TokenKind::OpenParen);
+    let mut types = Vec::new();
+    while parser.current_token_kind() != &TokenKind::CloseParen {
+        types.push(parse_type(parser, &0));
+        if parser.current_token_kind() == &TokenKind::Comma {
+            parser.advance();
+        }
+    }
+    parser.expect(&TokenKind::CloseParen);
+
+    return Type::Tuple(types);
+}
+
+pub fn parse_reference_type(parser: &mut Parser) -> Type {
+    debug_type("parse_reference_type");
+    parser.expect_dialect(Dialect::Rust, "reference type");
+    parser.expect(&TokenKind::Ampersand);
+    let mutable = parser.current_token_kind() == &TokenKind::Mut;
+    if mutable {
+        parser.advance();
+    }
+
+    return Type::Reference {
+        inner: Box::new(parse_type(parser, &0)),
+        mutable,
+    };
+}
+
+pub fn parse_array_type(parser: &mut Parser, _: &i8, left: Type) -> Type {
     debug_type("parse_array_type");
     parser.expect(&TokenKind::OpenBracket);
     let mut dimensions = 0;
@@ -31,33 +101,38 @@ pub fn parse_array_type(parser: &mut Parser, bp: &i8, left: Type) -> Type {
         dimensions,
     };
 }
+pub fn parse_generic_type(parser: &mut Parser, _: &i8, left: Type) -> Type {
+    debug_type("parse_generic_type");
+    let name = match left {
+        Type::Symbol(name) => name,
+        _ => panic!(
+            "expected type name before generic arguments, found {:?}",
+            left
+        ),
+    };
+
+    parser.expect(&TokenKind::Less);
+    let mut arguments = Vec::new();
+    while parser.current_token_kind() != &TokenKind::Greater {
+        arguments.push(parse_type(parser, &0));
+        if parser.current_token_kind() == &TokenKind::Comma {
+            parser.advance();
+        }
+    }
+    parser.expect(&TokenKind::Greater);
+
+    return Type::Generic { name, arguments };
+}
 pub fn parse_type(parser: &mut Parser, bp: &i8) -> Type {
     debug_type(" type:");
     let nod = parser.current_token();
-    // let mut to_debug = format!("parse_expr: nod:{:?} bp:{} ", nod, bp,);
     let mut left = parser.type_lookup.get_nod(nod.kind)(parser);
 
-    // to_debug += &format!(
-    //     "current_token_kind:{:?} current_bp:{}",
-    //     parser.current_token_kind(),
-    //     parser.lookup.get_bp(&TokenKind::Plus)
-    // );
-    // debug_expression(&to_debug);
-
-    while parser.current_bp() > bp {
-        let led = parser.current_token().kind.clone();
+    while parser.current_type_bp() > bp {
+        let led = parser.current_token().kind;
         let led_fn = parser.type_lookup.get_led(led);
 
-        // debug_expression(&format!("expr led call: led:{:?} ->>:", led));
-
-        left = led_fn(parser, &parser.current_bp().to_owned(), left);
-
-        // debug_expression(&format!(
-        //     "expr while loop: current kind{:?} current bp:{} bp:{}",
-        //     parser.current_token_kind(),
-        //     parser.current_bp(),
-        //     bp
-        // ));
+        left = led_fn(parser, &parser.current_type_bp().to_owned(), left);
     }
     return left;
 }
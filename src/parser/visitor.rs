@@ -0,0 +1,577 @@
+use crate::{
+    expression::{interpolated_values, Expression, InterpolationPart},
+    types::Type,
+};
+
+/// Walks the ast without changing it. Every method visits the children by default,
+/// an implementation overrides the nodes it cares about and calls `walk_*` to keep going.
+pub trait Visitor {
+    fn visit_expression(&mut self, expression: &Expression) {
+        walk_expression(self, expression);
+    }
+    /// body of a function, loop or branch
+    fn visit_block(&mut self, block: &[Expression]) {
+        for expression in block {
+            self.visit_expression(expression);
+        }
+    }
+    fn visit_type(&mut self, var_type: &Type) {
+        walk_type(self, var_type);
+    }
+}
+
+/// Walks the ast and changes it in place
+pub trait VisitorMut {
+    fn visit_expression_mut(&mut self, expression: &mut Expression) {
+        walk_expression_mut(self, expression);
+    }
+    fn visit_block_mut(&mut self, block: &mut Vec<Expression>) {
+        for expression in block {
+            self.visit_expression_mut(expression);
+        }
+    }
+    fn visit_type_mut(&mut self, var_type: &mut Type) {
+        walk_type_mut(self, var_type);
+    }
+}
+
+/// Takes the ast apart and builds a new one, a block can get more or less statements than it had
+pub trait Fold {
+    fn fold_expression(&mut self, expression: Expression) -> Expression {
+        fold_children(self, expression)
+    }
+    fn fold_block(&mut self, block: Vec<Expression>) -> Vec<Expression> {
+        block
+            .into_iter()
+            .map(|expression| self.fold_expression(expression))
+            .collect()
+    }
+    fn fold_type(&mut self, var_type: Type) -> Type {
+        fold_type_children(self, var_type)
+    }
+}
+
+/// Visits the children of `expression` in the order they are written in the source
+pub fn walk_expression<V: Visitor + ?Sized>(visitor: &mut V, expression: &Expression) {
+    match expression {
+        Expression::Interpolated(parts) => {
+            for value in interpolated_values(parts) {
+                visitor.visit_expression(value);
+            }
+        }
+        Expression::Prefix { value, .. }
+        | Expression::Grouping(value)
+        | Expression::Borrow { value, .. }
+        | Expression::Return { value, .. }
+        | Expression::MemberExpr { member: value, .. }
+        | Expression::StaticMember { member: value, .. } => visitor.visit_expression(value),
+        Expression::Assignment { target, value, .. } => {
+            visitor.visit_expression(target);
+            visitor.visit_expression(value);
+        }
+        Expression::Binary { l, r, .. } => {
+            visitor.visit_expression(l);
+            visitor.visit_expression(r);
+        }
+        Expression::Range { from, to } => {
+            visitor.visit_expression(from);
+            visitor.visit_expression(to);
+        }
+        Expression::VariableDeclaration {
+            var_type: Some(var_type),
+            ..
+        }
+        | Expression::Out {
+            var_type: Some(var_type),
+            ..
+        }
+        | Expression::ClassProperty { var_type, .. }
+        | Expression::FunctionProperty { var_type, .. } => visitor.visit_type(var_type),
+        Expression::TupleDeclaration { var_type, bindings } => {
+            if let Some(var_type) = var_type {
+                visitor.visit_type(var_type);
+            }
+            for binding in bindings {
+                visitor.visit_expression(binding);
+            }
+        }
+        Expression::Tuple(values)
+        | Expression::MacroCall { values, .. }
+        | Expression::ClassInstantiation {
+            properties: values, ..
+        }
+        | Expression::ArrayInitialization { properties: values } => {
+            for value in values {
+                visitor.visit_expression(value);
+            }
+        }
+        Expression::Class {
+            properties,
+            functions,
+            ..
+        } => {
+            for property in properties {
+                visitor.visit_expression(property);
+            }
+            for function in functions {
+                visitor.visit_expression(function);
+            }
+        }
+        Expression::Function {
+            properties,
+            output,
+            inside,
+            ..
+        } => {
+            for property in properties {
+                visitor.visit_expression(property);
+            }
+            if let Some(output) = output {
+                visitor.visit_type(output);
+            }
+            visitor.visit_block(inside);
+        }
+        Expression::If {
+            condition, inside, ..
+        }
+        | Expression::While {
+            condition, inside, ..
+        } => {
+            visitor.visit_expression(condition);
+            visitor.visit_block(inside);
+        }
+        Expression::Else {
+            condition, inside, ..
+        } => {
+            if let Some(condition) = condition {
+                visitor.visit_expression(condition);
+            }
+            visitor.visit_block(inside);
+        }
+        Expression::For {
+            iteration_target,
+            inside,
+            ..
+        } => {
+            visitor.visit_expression(iteration_target);
+            visitor.visit_block(inside);
+        }
+        Expression::IndexArray {
+            left,
+            indexes: values,
+        }
+        | Expression::FunctionCall { left, values } => {
+            visitor.visit_expression(left);
+            for value in values {
+                visitor.visit_expression(value);
+            }
+        }
+        Expression::Number(_)
+        | Expression::Float(_)
+        | Expression::String(_)
+        | Expression::Identifier { .. }
+        | Expression::Keyword(_)
+        | Expression::VariableDeclaration { .. }
+        | Expression::Out { .. }
+        | Expression::Attribute { .. } => {}
+    }
+}
+pub fn walk_type<V: Visitor + ?Sized>(visitor: &mut V, var_type: &Type) {
+    match var_type {
+        Type::Symbol(_) => {}
+        Type::Array { left_type, .. } => visitor.visit_type(left_type),
+        Type::Generic {
+            arguments: types, ..
+        }
+        | Type::Tuple(types) => {
+            for var_type in types {
+                visitor.visit_type(var_type);
+            }
+        }
+        Type::Reference { inner, .. } => visitor.visit_type(inner),
+    }
+}
+
+pub fn walk_expression_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expression: &mut Expression) {
+    match expression {
+        Expression::Interpolated(parts) => {
+            for part in parts {
+                if let InterpolationPart::Value(value) = part {
+                    visitor.visit_expression_mut(value);
+                }
+            }
+        }
+        Expression::Prefix { value, .. }
+        | Expression::Grouping(value)
+        | Expression::Borrow { value, .. }
+        | Expression::Return { value, .. }
+        | Expression::MemberExpr { member: value, .. }
+        | Expression::StaticMember { member: value, .. } => visitor.visit_expression_mut(value),
+        Expression::Assignment { target, value, .. } => {
+            visitor.visit_expression_mut(target);
+            visitor.visit_expression_mut(value);
+        }
+        Expression::Binary { l, r, .. } => {
+            visitor.visit_expression_mut(l);
+            visitor.visit_expression_mut(r);
+        }
+        Expression::Range { from, to } => {
+            visitor.visit_expression_mut(from);
+            visitor.visit_expression_mut(to);
+        }
+        Expression::VariableDeclaration {
+            var_type: Some(var_type),
+            ..
+        }
+        | Expression::Out {
+            var_type: Some(var_type),
+            ..
+        }
+        | Expression::ClassProperty { var_type, .. }
+        | Expression::FunctionProperty { var_type, .. } => visitor.visit_type_mut(var_type),
+        Expression::TupleDeclaration { var_type, bindings } => {
+            if let Some(var_type) = var_type {
+                visitor.visit_type_mut(var_type);
+            }
+            for binding in bindings {
+                visitor.visit_expression_mut(binding);
+            }
+        }
+        Expression::Tuple(values)
+        | Expression::MacroCall { values, .. }
+        | Expression::ClassInstantiation {
+            properties: values, ..
+        }
+        | Expression::ArrayInitialization { properties: values } => {
+            for value in values {
+                visitor.visit_expression_mut(value);
+            }
+        }
+        Expression::Class {
+            properties,
+            functions,
+            ..
+        } => {
+            for property in properties {
+                visitor.visit_expression_mut(property);
+            }
+            for function in functions {
+                visitor.visit_expression_mut(function);
+            }
+        }
+        Expression::Function {
+            properties,
+            output,
+            inside,
+            ..
+        } => {
+            for property in properties {
+                visitor.visit_expression_mut(property);
+            }
+            if let Some(output) = output {
+                visitor.visit_type_mut(output);
+            }
+            visitor.visit_block_mut(inside);
+        }
+        Expression::If {
+            condition, inside, ..
+        }
+        | Expression::While {
+            condition, inside, ..
+        } => {
+            visitor.visit_expression_mut(condition);
+            visitor.visit_block_mut(inside);
+        }
+        Expression::Else {
+            condition, inside, ..
+        } => {
+            if let Some(condition) = condition {
+                visitor.visit_expression_mut(condition);
+            }
+            visitor.visit_block_mut(inside);
+        }
+        Expression::For {
+            iteration_target,
+            inside,
+            ..
+        } => {
+            visitor.visit_expression_mut(iteration_target);
+            visitor.visit_block_mut(inside);
+        }
+        Expression::IndexArray {
+            left,
+            indexes: values,
+        }
+        | Expression::FunctionCall { left, values } => {
+            visitor.visit_expression_mut(left);
+            for value in values {
+                visitor.visit_expression_mut(value);
+            }
+        }
+        Expression::Number(_)
+        | Expression::Float(_)
+        | Expression::String(_)
+        | Expression::Identifier { .. }
+        | Expression::Keyword(_)
+        | Expression::VariableDeclaration { .. }
+        | Expression::Out { .. }
+        | Expression::Attribute { .. } => {}
+    }
+}
+pub fn walk_type_mut<V: VisitorMut + ?Sized>(visitor: &mut V, var_type: &mut Type) {
+    match var_type {
+        Type::Symbol(_) => {}
+        Type::Array { left_type, .. } => visitor.visit_type_mut(left_type),
+        Type::Generic {
+            arguments: types, ..
+        }
+        | Type::Tuple(types) => {
+            for var_type in types {
+                visitor.visit_type_mut(var_type);
+            }
+        }
+        Type::Reference { inner, .. } => visitor.visit_type_mut(inner),
+    }
+}
+
+/// Rebuilds `expression` with folded children
+pub fn fold_children<F: Fold + ?Sized>(folder: &mut F, expression: Expression) -> Expression {
+    match expression {
+        Expression::Interpolated(parts) => Expression::Interpolated(
+            parts
+                .into_iter()
+                .map(|part| match part {
+                    InterpolationPart::Value(value) => {
+                        InterpolationPart::Value(folder.fold_expression(value))
+                    }
+                    text => text,
+                })
+                .collect(),
+        ),
+        Expression::Prefix { prefix, value } => Expression::Prefix {
+            prefix,
+            value: fold_box(folder, *value),
+        },
+        Expression::Grouping(value) => Expression::Grouping(fold_box(folder, *value)),
+        Expression::Borrow {
+            value,
+            mutable,
+            span,
+        } => Expression::Borrow {
+            value: fold_box(folder, *value),
+            mutable,
+            span,
+        },
+        Expression::Return { value, span } => Expression::Return {
+            value: fold_box(folder, *value),
+            span,
+        },
+        Expression::MemberExpr { member, name, span } => Expression::MemberExpr {
+            member: fold_box(folder, *member),
+            name,
+            span,
+        },
+        Expression::StaticMember { member, name } => Expression::StaticMember {
+            member: fold_box(folder, *member),
+            name,
+        },
+        Expression::Assignment {
+            target,
+            operator,
+            value,
+        } => Expression::Assignment {
+            target: fold_box(folder, *target),
+            operator,
+            value: fold_box(folder, *value),
+        },
+        Expression::Binary { l, operator, r } => Expression::Binary {
+            l: fold_box(folder, *l),
+            operator,
+            r: fold_box(folder, *r),
+        },
+        Expression::Range { from, to } => Expression::Range {
+            from: fold_box(folder, *from),
+            to: fold_box(folder, *to),
+        },
+        Expression::VariableDeclaration {
+            var_type,
+            name,
+            mutable,
+            span,
+        } => Expression::VariableDeclaration {
+            var_type: var_type.map(|var_type| folder.fold_type(var_type)),
+            name,
+            mutable,
+            span,
+        },
+        Expression::Out {
+            var_type,
+            var_name,
+            span,
+        } => Expression::Out {
+            var_type: var_type.map(|var_type| folder.fold_type(var_type)),
+            var_name,
+            span,
+        },
+        Expression::ClassProperty {
+            var_name,
+            var_type,
+            span,
+        } => Expression::ClassProperty {
+            var_name,
+            var_type: folder.fold_type(var_type),
+            span,
+        },
+        Expression::FunctionProperty {
+            var_name,
+            var_type,
+            mutable,
+            span,
+        } => Expression::FunctionProperty {
+            var_name,
+            var_type: folder.fold_type(var_type),
+            mutable,
+            span,
+        },
+        Expression::TupleDeclaration { var_type, bindings } => Expression::TupleDeclaration {
+            var_type: var_type.map(|var_type| folder.fold_type(var_type)),
+            bindings: fold_all(folder, bindings),
+        },
+        Expression::Tuple(values) => Expression::Tuple(fold_all(folder, values)),
+        Expression::MacroCall { name, values } => Expression::MacroCall {
+            name,
+            values: fold_all(folder, values),
+        },
+        Expression::ClassInstantiation {
+            name,
+            span,
+            properties,
+        } => Expression::ClassInstantiation {
+            name,
+            span,
+            properties: fold_all(folder, properties),
+        },
+        Expression::ArrayInitialization { properties } => Expression::ArrayInitialization {
+            properties: fold_all(folder, properties),
+        },
+        Expression::Class {
+            public,
+            name,
+            span,
+            properties,
+            functions,
+        } => Expression::Class {
+            public,
+            name,
+            span,
+            properties: fold_all(folder, properties),
+            functions: fold_all(folder, functions),
+        },
+        Expression::Function {
+            name,
+            span,
+            properties,
+            public,
+            output,
+            inside,
+        } => Expression::Function {
+            name,
+            span,
+            properties: fold_all(folder, properties),
+            public,
+            output: output.map(|output| folder.fold_type(output)),
+            inside: folder.fold_block(inside),
+        },
+        Expression::If {
+            condition,
+            inside,
+            span,
+        } => Expression::If {
+            condition: Box::new(folder.fold_expression(*condition)),
+            inside: folder.fold_block(inside),
+            span,
+        },
+        Expression::While {
+            condition,
+            inside,
+            span,
+        } => Expression::While {
+            condition: Box::new(folder.fold_expression(*condition)),
+            inside: folder.fold_block(inside),
+            span,
+        },
+        Expression::Else {
+            condition,
+            inside,
+            span,
+        } => Expression::Else {
+            condition: condition.map(|condition| Box::new(folder.fold_expression(*condition))),
+            inside: folder.fold_block(inside),
+            span,
+        },
+        Expression::For {
+            iterator_name,
+            iterator_span,
+            iteration_target,
+            inside,
+        } => Expression::For {
+            iterator_name,
+            iterator_span,
+            iteration_target: Box::new(folder.fold_expression(*iteration_target)),
+            inside: folder.fold_block(inside),
+        },
+        Expression::IndexArray { left, indexes } => Expression::IndexArray {
+            left: Box::new(folder.fold_expression(*left)),
+            indexes: fold_all(folder, indexes),
+        },
+        Expression::FunctionCall { left, values } => Expression::FunctionCall {
+            left: Box::new(folder.fold_expression(*left)),
+            values: fold_all(folder, values),
+        },
+        leaf @ (Expression::Number(_)
+        | Expression::Float(_)
+        | Expression::String(_)
+        | Expression::Identifier { .. }
+        | Expression::Keyword(_)
+        | Expression::Attribute { .. }) => leaf,
+    }
+}
+fn fold_box<F: Fold + ?Sized>(folder: &mut F, expression: Expression) -> Box<Expression> {
+    return Box::new(folder.fold_expression(expression));
+}
+/// folds every expression of a list that is not a block, like the values of a call
+fn fold_all<F: Fold + ?Sized>(folder: &mut F, expressions: Vec<Expression>) -> Vec<Expression> {
+    return expressions
+        .into_iter()
+        .map(|expression| folder.fold_expression(expression))
+        .collect();
+}
+pub fn fold_type_children<F: Fold + ?Sized>(folder: &mut F, var_type: Type) -> Type {
+    match var_type {
+        Type::Symbol(_) => var_type,
+        Type::Array {
+            left_type,
+            dimensions,
+        } => Type::Array {
+            left_type: Box::new(folder.fold_type(*left_type)),
+            dimensions,
+        },
+        Type::Generic { name, arguments } => Type::Generic {
+            name,
+            arguments: arguments
+                .into_iter()
+                .map(|argument| folder.fold_type(argument))
+                .collect(),
+        },
+        Type::Tuple(types) => Type::Tuple(
+            types
+                .into_iter()
+                .map(|var_type| folder.fold_type(var_type))
+                .collect(),
+        ),
+        Type::Reference { inner, mutable } => Type::Reference {
+            inner: Box::new(folder.fold_type(*inner)),
+            mutable,
+        },
+    }
+}